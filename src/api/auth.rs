@@ -0,0 +1,47 @@
+use argon2::password_hash::PasswordHash;
+use argon2::{Argon2, PasswordVerifier};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+
+use crate::error::{AppError, Result};
+
+pub struct AuthState {
+    pub api_key_hash: String,
+}
+
+/// Validate a bearer token or `X-API-Key` header against the configured hash
+pub async fn require_api_key(
+    State(state): State<Arc<AuthState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let provided = extract_key(&request).ok_or(AppError::Unauthorized)?;
+
+    let parsed_hash =
+        PasswordHash::new(&state.api_key_hash).map_err(|_| AppError::Unauthorized)?;
+
+    Argon2::default()
+        .verify_password(provided.as_bytes(), &parsed_hash)
+        .map_err(|_| AppError::Unauthorized)?;
+
+    Ok(next.run(request).await)
+}
+
+fn extract_key(request: &Request) -> Option<String> {
+    let headers = request.headers();
+
+    if let Some(value) = headers.get("x-api-key") {
+        return value.to_str().ok().map(str::to_string);
+    }
+
+    if let Some(value) = headers.get(axum::http::header::AUTHORIZATION) {
+        let value = value.to_str().ok()?;
+        return value.strip_prefix("Bearer ").map(str::to_string);
+    }
+
+    None
+}
@@ -1,30 +1,48 @@
 use axum::{
-    extract::{Path, State},
-    response::{IntoResponse, Response},
+    extract::{Path, Query, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
     Json,
 };
 use chrono::{DateTime, Utc};
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::convert::Infallible;
 use std::sync::Arc;
+use std::time::Duration;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+use crate::db::dialects::get_dialect;
+use crate::db::health::InstanceHealthStatus;
 use crate::db::instance::InstanceStatus;
 use crate::db::manager::InstanceManager;
+use crate::db::params::bind_params;
 use crate::db::query::QueryExecutor;
 use crate::error::Result;
+use crate::jobs::JobQueue;
 
-use super::response::{create_json_response, create_sse_response, create_text_response};
+use super::response::{
+    create_csv_response, create_json_response, create_sse_response, create_text_response,
+    create_tsv_response, JsonQueryResult,
+};
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct CreateDbRequest {
     pub dialect: String,
     /// Optional db_id to restore an existing archived database
     #[serde(default)]
     pub db_id: Option<Uuid>,
+    /// Restore the most recent snapshot at or before this time instead of
+    /// the latest archive-time backup
+    #[serde(default)]
+    pub restore_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CreateDbResponse {
     pub db_id: Uuid,
     pub dialect: String,
@@ -34,7 +52,7 @@ pub struct CreateDbResponse {
     pub restored: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DbStatusResponse {
     pub db_id: Uuid,
     pub dialect: String,
@@ -48,17 +66,49 @@ pub struct DbStatusResponse {
     /// When the database was archived (if archived)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub archived_at: Option<DateTime<Utc>>,
+    /// Latest state observed by the instance health watchdog, if it's
+    /// checked this instance at least once since startup
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_status: Option<InstanceHealthStatus>,
+    /// When the watchdog last checked this instance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_health_check: Option<DateTime<Utc>>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct DestroyDbResponse {
     pub db_id: Uuid,
     pub status: InstanceStatus,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize)]
+pub struct InstanceEventResponse {
+    pub previous_status: Option<String>,
+    pub new_status: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<crate::storage::InstanceEvent> for InstanceEventResponse {
+    fn from(event: crate::storage::InstanceEvent) -> Self {
+        Self {
+            previous_status: event.previous_status,
+            new_status: event.new_status,
+            reason: event.reason,
+            created_at: event.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct QueryRequest {
     pub query: String,
+    /// Positional parameters substituted for `?` (MySQL/SQL Server) or
+    /// `$1`, `$2`, ... (Postgres) placeholders in `query`, server-side,
+    /// before the query is dispatched to the CLI client
+    #[serde(default)]
+    #[schema(value_type = Option<Vec<Object>>)]
+    pub params: Option<Vec<JsonValue>>,
     /// Output format: "text", "json", "jsonl" (default: "json")
     #[serde(default)]
     pub format: Option<String>,
@@ -72,6 +122,8 @@ enum OutputFormat {
     Text,
     Json,
     Jsonl,
+    Csv,
+    Tsv,
 }
 
 impl QueryRequest {
@@ -83,6 +135,9 @@ impl QueryRequest {
             (Some("json"), _) => OutputFormat::Json,
             // Explicit format=jsonl (implies SSE transport)
             (Some("jsonl"), _) => OutputFormat::Jsonl,
+            // Explicit format=csv/tsv
+            (Some("csv"), _) => OutputFormat::Csv,
+            (Some("tsv"), _) => OutputFormat::Tsv,
             // Explicit transport=sse (implies jsonl format)
             (None, Some("sse")) => OutputFormat::Jsonl,
             // No params: default to json
@@ -95,17 +150,36 @@ impl QueryRequest {
 
 pub struct AppState {
     pub manager: Arc<InstanceManager>,
-    pub query_executor: QueryExecutor,
+    pub query_executor: Arc<QueryExecutor>,
+    pub jobs: Arc<JobQueue>,
     pub inactivity_timeout_secs: i64,
+    /// How long a presigned backup download/upload URL stays valid (see
+    /// `Config::backup_presign_ttl_secs`)
+    pub backup_presign_ttl_secs: u64,
 }
 
+/// Create a new database instance, or restore one from backup.
+///
+/// If `db_id` names a previously-archived instance, it's restored instead
+/// of creating a fresh database; `restore_at` picks the most recent
+/// snapshot at or before that time instead of the latest archive-time
+/// backup.
+#[utoipa::path(
+    post,
+    path = "/db/new",
+    request_body = CreateDbRequest,
+    responses(
+        (status = 200, description = "Database instance created or restored", body = CreateDbResponse),
+    ),
+    tag = "db",
+)]
 pub async fn create_db(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateDbRequest>,
 ) -> Result<Json<CreateDbResponse>> {
     let (instance, restored) = state
         .manager
-        .get_or_create_instance(&req.dialect, req.db_id)
+        .get_or_create_instance(&req.dialect, req.db_id, req.restore_at)
         .await?;
 
     Ok(Json(CreateDbResponse {
@@ -116,6 +190,18 @@ pub async fn create_db(
     }))
 }
 
+/// Get a database instance's status, falling back to archived metadata if
+/// it isn't currently running.
+#[utoipa::path(
+    get,
+    path = "/db/{db_id}",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    responses(
+        (status = 200, description = "Instance status", body = DbStatusResponse),
+        (status = 404, description = "Database instance not found"),
+    ),
+    tag = "db",
+)]
 pub async fn get_db_status(
     State(state): State<Arc<AppState>>,
     Path(db_id): Path<Uuid>,
@@ -124,12 +210,14 @@ pub async fn get_db_status(
     match state.manager.get_instance(db_id).await {
         Ok(instance) => {
             // Check metadata for backup info
-            let stored = state.manager.get_stored_instance(db_id)?;
+            let stored = state.manager.get_stored_instance(db_id).await?;
             let backup_available = stored.as_ref().map(|s| s.backup_key.is_some()).unwrap_or(false);
 
             let expires_at = instance.last_activity
                 + chrono::Duration::seconds(state.inactivity_timeout_secs);
 
+            let health = state.manager.instance_health(instance.id).await;
+
             Ok(Json(DbStatusResponse {
                 db_id: instance.id,
                 dialect: instance.dialect,
@@ -139,11 +227,13 @@ pub async fn get_db_status(
                 expires_at,
                 backup_available,
                 archived_at: None,
+                health_status: health.as_ref().map(|h| h.status),
+                last_health_check: health.map(|h| h.last_checked_at),
             }))
         }
         Err(crate::error::AppError::DbNotFound) => {
             // Check if archived
-            if let Some(stored) = state.manager.get_stored_instance(db_id)? {
+            if let Some(stored) = state.manager.get_stored_instance(db_id).await? {
                 let expires_at = stored.last_activity
                     + chrono::Duration::seconds(state.inactivity_timeout_secs);
 
@@ -156,6 +246,8 @@ pub async fn get_db_status(
                     expires_at,
                     backup_available: stored.backup_key.is_some(),
                     archived_at: stored.archived_at,
+                    health_status: None,
+                    last_health_check: None,
                 }))
             } else {
                 Err(crate::error::AppError::DbNotFound)
@@ -165,6 +257,170 @@ pub async fn get_db_status(
     }
 }
 
+/// Resource usage of the container currently hosting a database instance.
+/// Instances share a pool container with other databases of the same
+/// dialect, so this reflects the whole container's usage - the same
+/// granularity `db_api_container_*` metrics and memory-pressure eviction
+/// already act on - not `db_id` in isolation.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct InstanceStatsResponse {
+    pub db_id: Uuid,
+    pub cpu_percent: f64,
+    pub memory_usage_bytes: u64,
+    pub memory_limit_bytes: u64,
+    pub network_rx_bytes: u64,
+    pub network_tx_bytes: u64,
+}
+
+/// Resource usage (CPU, memory, network) for a database instance's container.
+#[utoipa::path(
+    get,
+    path = "/db/{db_id}/stats",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    responses(
+        (status = 200, description = "Container resource usage", body = InstanceStatsResponse),
+        (status = 404, description = "Database instance not found"),
+    ),
+    tag = "db",
+)]
+pub async fn get_db_stats(
+    State(state): State<Arc<AppState>>,
+    Path(db_id): Path<Uuid>,
+) -> Result<Json<InstanceStatsResponse>> {
+    let stats = state.manager.instance_stats(db_id).await?;
+
+    Ok(Json(InstanceStatsResponse {
+        db_id,
+        cpu_percent: stats.cpu_percent,
+        memory_usage_bytes: stats.memory_usage_bytes,
+        memory_limit_bytes: stats.memory_limit_bytes,
+        network_rx_bytes: stats.network_rx_bytes,
+        network_tx_bytes: stats.network_tx_bytes,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    /// Cap the number of events returned, newest first (default: unbounded)
+    limit: Option<u32>,
+}
+
+pub async fn get_db_history(
+    State(state): State<Arc<AppState>>,
+    Path(db_id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<Vec<InstanceEventResponse>>> {
+    let events = state.manager.instance_history(db_id, query.limit).await?;
+    Ok(Json(events.into_iter().map(Into::into).collect()))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupDownloadUrlResponse {
+    pub url: String,
+    pub expires_in_secs: u64,
+}
+
+/// Issue a presigned URL a client can use to download this database's latest
+/// backup directly from the configured `BackupStore`, without streaming it
+/// through this service.
+#[utoipa::path(
+    get,
+    path = "/db/{db_id}/backup/download-url",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    responses(
+        (status = 200, description = "Presigned download URL", body = BackupDownloadUrlResponse),
+        (status = 404, description = "Database instance or backup not found"),
+        (status = 503, description = "Backup store not configured, or doesn't support presigned URLs"),
+    ),
+    tag = "db",
+)]
+pub async fn get_backup_download_url(
+    State(state): State<Arc<AppState>>,
+    Path(db_id): Path<Uuid>,
+) -> Result<Json<BackupDownloadUrlResponse>> {
+    let backup = state
+        .manager
+        .backup()
+        .ok_or_else(|| crate::error::AppError::PresignFailed("no backup store configured".to_string()))?;
+
+    let stored = state
+        .manager
+        .get_stored_instance(db_id)
+        .await?
+        .ok_or(crate::error::AppError::DbNotFound)?;
+    let backup_key = stored.backup_key.ok_or(crate::error::AppError::BackupNotFound)?;
+
+    let ttl = Duration::from_secs(state.backup_presign_ttl_secs);
+    let url = backup.presigned_download_url(&backup_key, ttl).await?;
+
+    Ok(Json(BackupDownloadUrlResponse {
+        url,
+        expires_in_secs: state.backup_presign_ttl_secs,
+    }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BackupUploadUrlResponse {
+    pub url: String,
+    pub backup_key: String,
+    pub expires_in_secs: u64,
+}
+
+/// Issue a presigned URL a client can use to push a new backup object
+/// directly to the configured `BackupStore`, without streaming it through
+/// this service. The caller is responsible for recording the returned
+/// `backup_key` against the instance (e.g. via a follow-up call) once the
+/// upload completes; this endpoint only issues the URL.
+#[utoipa::path(
+    post,
+    path = "/db/{db_id}/backup/upload-url",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    responses(
+        (status = 200, description = "Presigned upload URL", body = BackupUploadUrlResponse),
+        (status = 404, description = "Database instance not found"),
+        (status = 503, description = "Backup store not configured, or doesn't support presigned URLs"),
+    ),
+    tag = "db",
+)]
+pub async fn get_backup_upload_url(
+    State(state): State<Arc<AppState>>,
+    Path(db_id): Path<Uuid>,
+) -> Result<Json<BackupUploadUrlResponse>> {
+    let backup = state
+        .manager
+        .backup()
+        .ok_or_else(|| crate::error::AppError::PresignFailed("no backup store configured".to_string()))?;
+
+    // Require the instance to exist (active or archived) before handing out
+    // an upload URL for it, the same way the download side requires a
+    // stored instance to read `backup_key` from.
+    state
+        .manager
+        .get_stored_instance(db_id)
+        .await?
+        .ok_or(crate::error::AppError::DbNotFound)?;
+
+    let ttl = Duration::from_secs(state.backup_presign_ttl_secs);
+    let (url, backup_key) = backup.presigned_upload_url(db_id, ttl).await?;
+
+    Ok(Json(BackupUploadUrlResponse {
+        url,
+        backup_key,
+        expires_in_secs: state.backup_presign_ttl_secs,
+    }))
+}
+
+/// Destroy a database instance and its container.
+#[utoipa::path(
+    delete,
+    path = "/db/{db_id}",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    responses(
+        (status = 200, description = "Instance destroyed", body = DestroyDbResponse),
+        (status = 404, description = "Database instance not found"),
+    ),
+    tag = "db",
+)]
 pub async fn destroy_db(
     State(state): State<Arc<AppState>>,
     Path(db_id): Path<Uuid>,
@@ -177,6 +433,30 @@ pub async fn destroy_db(
     }))
 }
 
+/// Execute a query against a database instance.
+///
+/// `format` controls the response shape: `text` returns raw CLI output for
+/// each statement joined by `---` separators, `json` (default) returns a
+/// JSON array of per-statement result objects (`columns`/`rows`/
+/// `affected_rows`/`error`/`messages`), `jsonl` streams the same data as
+/// newline-delimited `QueryEvent`s over SSE (`transport=sse` also implies
+/// `jsonl`), and `csv`/`tsv` stream the result rows as a downloadable
+/// delimited file. In SSE mode, events arrive under four names: `line`
+/// (informational text), `record` (one row), `error` (a query error), and
+/// `done`/`statement` marking the end of a statement — `done` for the last
+/// statement in the batch, `statement` for the boundaries in between.
+#[utoipa::path(
+    post,
+    path = "/db/{db_id}/query",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    request_body = QueryRequest,
+    responses(
+        (status = 200, description = "Query results; shape depends on `format` (see operation description)", body = Vec<JsonQueryResult>),
+        (status = 400, description = "SQL syntax error or invalid query parameters"),
+        (status = 404, description = "Database instance not found"),
+    ),
+    tag = "db",
+)]
 pub async fn execute_query(
     State(state): State<Arc<AppState>>,
     Path(db_id): Path<Uuid>,
@@ -188,22 +468,161 @@ pub async fn execute_query(
     let instance = state.manager.get_instance(db_id).await?;
     let format = req.resolve_format();
 
+    let sql = match &req.params {
+        Some(params) => {
+            let dialect = get_dialect(&instance.dialect)?;
+            bind_params(dialect.as_ref(), &req.query, params)?
+        }
+        None => req.query.clone(),
+    };
+
     match format {
         OutputFormat::Text => {
             // Return raw CLI output
-            let output = state.query_executor.execute_raw(&instance, &req.query).await?;
+            let output = state.query_executor.execute_raw(&instance, &sql).await?;
             Ok(create_text_response(output))
         }
         OutputFormat::Json => {
             // Return traditional JSON array
-            let stream = state.query_executor.execute(&instance, &req.query).await?;
+            let stream = state.query_executor.execute(&instance, &sql).await?;
             let events: Vec<_> = stream.collect().await;
             Ok(create_json_response(events).into_response())
         }
         OutputFormat::Jsonl => {
             // Return SSE stream with JSONL events
-            let stream = state.query_executor.execute(&instance, &req.query).await?;
+            let stream = state.query_executor.execute(&instance, &sql).await?;
             Ok(create_sse_response(stream).into_response())
         }
+        OutputFormat::Csv => {
+            let stream = state.query_executor.execute(&instance, &sql).await?;
+            Ok(create_csv_response(stream, db_id))
+        }
+        OutputFormat::Tsv => {
+            let stream = state.query_executor.execute(&instance, &sql).await?;
+            Ok(create_tsv_response(stream, db_id))
+        }
     }
 }
+
+/// Submit a query to run in the background instead of blocking the
+/// request, returning a job id to poll via `GET /jobs/{job_id}`.
+///
+/// `format`/`transport` on `QueryRequest` are ignored here; the completed
+/// job's result is always the same JSON array `format=json` would have
+/// returned.
+#[utoipa::path(
+    post,
+    path = "/db/{db_id}/query/async",
+    params(("db_id" = Uuid, Path, description = "Database instance id")),
+    request_body = QueryRequest,
+    responses(
+        (status = 200, description = "Job submitted", body = SubmitJobResponse),
+        (status = 400, description = "SQL syntax error or invalid query parameters"),
+        (status = 404, description = "Database instance not found"),
+    ),
+    tag = "db",
+)]
+pub async fn execute_query_async(
+    State(state): State<Arc<AppState>>,
+    Path(db_id): Path<Uuid>,
+    Json(req): Json<QueryRequest>,
+) -> Result<Json<SubmitJobResponse>> {
+    state.manager.touch_instance(db_id).await?;
+
+    let instance = state.manager.get_instance(db_id).await?;
+
+    let sql = match &req.params {
+        Some(params) => {
+            let dialect = get_dialect(&instance.dialect)?;
+            bind_params(dialect.as_ref(), &req.query, params)?
+        }
+        None => req.query.clone(),
+    };
+
+    let job_id = state.jobs.enqueue_query(db_id, sql).await?;
+
+    Ok(Json(SubmitJobResponse { job_id }))
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SubmitJobResponse {
+    pub job_id: Uuid,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JobStatusResponse {
+    pub job_id: Uuid,
+    pub kind: String,
+    pub status: &'static str,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    /// Present once the job completes successfully; for a `query` job this
+    /// is the same JSON array `format=json` would have returned, serialized
+    /// as a string.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Poll a background job's status and, once it completes, its result or
+/// error.
+#[utoipa::path(
+    get,
+    path = "/jobs/{job_id}",
+    params(("job_id" = Uuid, Path, description = "Job id returned by the submitting endpoint")),
+    responses(
+        (status = 200, description = "Job status", body = JobStatusResponse),
+        (status = 404, description = "Job not found"),
+    ),
+    tag = "jobs",
+)]
+pub async fn get_job_status(
+    State(state): State<Arc<AppState>>,
+    Path(job_id): Path<Uuid>,
+) -> Result<Json<JobStatusResponse>> {
+    let job = state
+        .jobs
+        .get_job(job_id)
+        .await?
+        .ok_or(crate::error::AppError::JobNotFound)?;
+
+    Ok(Json(JobStatusResponse {
+        job_id: job.id,
+        kind: job.kind,
+        status: job.status.as_str(),
+        attempts: job.attempts,
+        created_at: job.created_at,
+        heartbeat_at: job.heartbeat_at,
+        result: job.result,
+        error: job.error,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LogsQuery {
+    /// Seed the stream with the last N lines before following
+    tail: Option<usize>,
+}
+
+pub async fn stream_logs(
+    State(state): State<Arc<AppState>>,
+    Path(db_id): Path<Uuid>,
+    Query(query): Query<LogsQuery>,
+) -> Result<Sse<impl futures::Stream<Item = std::result::Result<Event, Infallible>>>> {
+    let instance = state.manager.get_instance(db_id).await?;
+
+    let stream = state
+        .manager
+        .docker()
+        .follow_logs(&instance.container_id, query.tail)
+        .await?
+        .map(|line| match line {
+            Ok(text) => Event::default().event("line").data(text),
+            Err(e) => Event::default().event("error").data(e.to_string()),
+        })
+        .map(Ok);
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
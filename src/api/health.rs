@@ -1,39 +1,226 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
 use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write as _;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::db::manager::InstanceManager;
 use crate::docker::DockerManager;
-use crate::error::Result;
 
 pub struct HealthState {
     pub docker: Arc<DockerManager>,
+    pub manager: Arc<InstanceManager>,
+}
+
+pub struct MetricsState {
+    pub docker: Arc<DockerManager>,
+    pub manager: Arc<InstanceManager>,
+    /// Used to derive the "approaching idle timeout" cutoff, at a fraction
+    /// of the configured inactivity timeout
+    pub inactivity_timeout: Duration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
 }
 
 #[derive(Debug, Serialize)]
-pub struct HealthResponse {
-    pub status: &'static str,
-    pub docker: &'static str,
+pub struct Check {
+    pub status: CheckStatus,
+    pub message: String,
 }
 
 #[derive(Debug, Serialize)]
-pub struct MetricsResponse {
-    pub active_instances: usize,
+pub struct HealthResponse {
+    pub status: CheckStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    pub checks: HashMap<String, Check>,
 }
 
-pub async fn health_check(State(state): State<Arc<HealthState>>) -> Result<Json<HealthResponse>> {
-    let docker_status = match state.docker.health_check().await {
-        Ok(true) => "connected",
-        _ => "disconnected",
-    };
+pub async fn health_check(State(state): State<Arc<HealthState>>) -> impl IntoResponse {
+    let (docker_check, instance_checks) = tokio::join!(
+        check_docker(&state.docker),
+        check_instances(&state.manager),
+    );
+
+    let docker_failed = docker_check.status == CheckStatus::Fail;
+    let any_instance_unhealthy = instance_checks
+        .values()
+        .any(|c| c.status != CheckStatus::Pass);
 
-    let status = if docker_status == "connected" {
-        "healthy"
+    let status = if docker_failed {
+        CheckStatus::Fail
+    } else if any_instance_unhealthy {
+        CheckStatus::Warn
     } else {
-        "unhealthy"
+        CheckStatus::Pass
     };
 
-    Ok(Json(HealthResponse {
+    let mut checks = HashMap::with_capacity(1 + instance_checks.len());
+    checks.insert("docker".to_string(), docker_check);
+    checks.extend(instance_checks);
+
+    let output = match status {
+        CheckStatus::Fail => Some("Docker daemon unreachable".to_string()),
+        CheckStatus::Warn => Some("One or more instances are unreachable".to_string()),
+        CheckStatus::Pass => None,
+    };
+
+    let response = HealthResponse {
         status,
-        docker: docker_status,
-    }))
+        output,
+        checks,
+    };
+
+    let http_status = if status == CheckStatus::Fail {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (http_status, Json(response))
+}
+
+async fn check_docker(docker: &DockerManager) -> Check {
+    match docker.health_check().await {
+        Ok(true) => Check {
+            status: CheckStatus::Pass,
+            message: "connected".to_string(),
+        },
+        _ => Check {
+            status: CheckStatus::Fail,
+            message: "disconnected".to_string(),
+        },
+    }
+}
+
+async fn check_instances(manager: &InstanceManager) -> HashMap<String, Check> {
+    let instances = manager.active_instances().await;
+
+    let checks = instances.into_iter().map(|instance| async move {
+        let key = format!("instance:{}", instance.id);
+        let check = match manager.docker().is_running(&instance.container_id).await {
+            Ok(true) => Check {
+                status: CheckStatus::Pass,
+                message: "running".to_string(),
+            },
+            Ok(false) => Check {
+                status: CheckStatus::Warn,
+                message: "container not running".to_string(),
+            },
+            Err(e) => Check {
+                status: CheckStatus::Warn,
+                message: format!("unreachable: {}", e),
+            },
+        };
+        (key, check)
+    });
+
+    futures::future::join_all(checks).await.into_iter().collect()
+}
+
+/// Fraction of `inactivity_timeout` at which an idle instance is considered
+/// "approaching" eviction, for the early-warning gauge below
+const APPROACHING_IDLE_TIMEOUT_FRACTION: f64 = 0.8;
+
+/// Prometheus text-exposition metrics, driven by aggregate `MetadataBackend`
+/// queries rather than pulling every instance row into the handler
+pub async fn metrics(State(state): State<Arc<MetricsState>>) -> impl IntoResponse {
+    let metadata = state.manager.metadata();
+
+    let mut body = String::new();
+
+    let _ = writeln!(body, "# HELP db_api_instances Instance count by status.");
+    let _ = writeln!(body, "# TYPE db_api_instances gauge");
+    match metadata.count_by_status().await {
+        Ok(counts) => {
+            for (status, count) in counts {
+                let _ = writeln!(body, r#"db_api_instances{{status="{}"}} {}"#, status, count);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to collect instance status counts: {}", e),
+    }
+
+    let _ = writeln!(body, "# HELP db_api_instances_by_dialect Instance count by dialect.");
+    let _ = writeln!(body, "# TYPE db_api_instances_by_dialect gauge");
+    match metadata.count_by_dialect().await {
+        Ok(counts) => {
+            for (dialect, count) in counts {
+                let _ = writeln!(body, r#"db_api_instances_by_dialect{{dialect="{}"}} {}"#, dialect, count);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to collect instance dialect counts: {}", e),
+    }
+
+    let _ = writeln!(body, "# HELP db_api_backup_bytes_total Total size of archived backups, in bytes.");
+    let _ = writeln!(body, "# TYPE db_api_backup_bytes_total gauge");
+    match metadata.sum_backup_bytes().await {
+        Ok(bytes) => {
+            let _ = writeln!(body, "db_api_backup_bytes_total {}", bytes);
+        }
+        Err(e) => tracing::warn!("Failed to sum backup bytes: {}", e),
+    }
+
+    let approaching_cutoff = state
+        .inactivity_timeout
+        .mul_f64(APPROACHING_IDLE_TIMEOUT_FRACTION);
+    let _ = writeln!(
+        body,
+        "# HELP db_api_instances_approaching_idle_timeout Active instances past {:.0}% of the idle timeout.",
+        APPROACHING_IDLE_TIMEOUT_FRACTION * 100.0
+    );
+    let _ = writeln!(body, "# TYPE db_api_instances_approaching_idle_timeout gauge");
+    match metadata.get_expired_instances(approaching_cutoff).await {
+        Ok(instances) => {
+            let _ = writeln!(body, "db_api_instances_approaching_idle_timeout {}", instances.len());
+        }
+        Err(e) => tracing::warn!("Failed to count instances approaching idle timeout: {}", e),
+    }
+
+    let _ = writeln!(body, "# HELP db_api_docker_up Whether the Docker daemon is reachable.");
+    let _ = writeln!(body, "# TYPE db_api_docker_up gauge");
+    let docker_up = state.docker.health_check().await.unwrap_or(false) as u8;
+    let _ = writeln!(body, "db_api_docker_up {}", docker_up);
+
+    // Per-container resource usage, one-shot (cheap enough for a sweep over
+    // every db-api/pool container on each scrape) - the same snapshot
+    // `check_memory_pressure` samples for budget-driven eviction.
+    let _ = writeln!(body, "# HELP db_api_container_cpu_percent Container CPU usage percent.");
+    let _ = writeln!(body, "# TYPE db_api_container_cpu_percent gauge");
+    let _ = writeln!(body, "# HELP db_api_container_memory_usage_bytes Container memory usage, in bytes.");
+    let _ = writeln!(body, "# TYPE db_api_container_memory_usage_bytes gauge");
+    let _ = writeln!(body, "# HELP db_api_container_memory_limit_bytes Container memory limit, in bytes.");
+    let _ = writeln!(body, "# TYPE db_api_container_memory_limit_bytes gauge");
+    let _ = writeln!(body, "# HELP db_api_container_network_rx_bytes Container network bytes received.");
+    let _ = writeln!(body, "# TYPE db_api_container_network_rx_bytes gauge");
+    let _ = writeln!(body, "# HELP db_api_container_network_tx_bytes Container network bytes sent.");
+    let _ = writeln!(body, "# TYPE db_api_container_network_tx_bytes gauge");
+    match state.docker.stats_snapshot().await {
+        Ok(snapshot) => {
+            for entry in snapshot.iter().filter(|e| e.is_running) {
+                let labels = format!(
+                    r#"{{container_id="{}",dialect="{}",is_pool="{}"}}"#,
+                    entry.container_id, entry.dialect, entry.is_pool
+                );
+                let _ = writeln!(body, "db_api_container_cpu_percent{} {}", labels, entry.stats.cpu_percent);
+                let _ = writeln!(body, "db_api_container_memory_usage_bytes{} {}", labels, entry.stats.memory_usage_bytes);
+                let _ = writeln!(body, "db_api_container_memory_limit_bytes{} {}", labels, entry.stats.memory_limit_bytes);
+                let _ = writeln!(body, "db_api_container_network_rx_bytes{} {}", labels, entry.stats.network_rx_bytes);
+                let _ = writeln!(body, "db_api_container_network_tx_bytes{} {}", labels, entry.stats.network_tx_bytes);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to collect container stats: {}", e),
+    }
+
+    (
+        StatusCode::OK,
+        [("content-type", "text/plain; version=0.0.4")],
+        body,
+    )
 }
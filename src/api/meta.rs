@@ -0,0 +1,15 @@
+use axum::Json;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct BuildDetails {
+    pub version: &'static str,
+    pub git_commit_hash: &'static str,
+}
+
+pub async fn get_build_details() -> Json<BuildDetails> {
+    Json(BuildDetails {
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit_hash: env!("GIT_COMMIT_HASH"),
+    })
+}
@@ -0,0 +1,9 @@
+mod auth;
+mod db;
+mod health;
+mod meta;
+mod openapi;
+mod response;
+mod routes;
+
+pub use routes::create_router;
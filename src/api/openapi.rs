@@ -1,7 +1,45 @@
 use axum::response::Html;
+use utoipa::OpenApi;
 
-pub fn openapi_spec() -> &'static str {
-    include_str!("openapi.json")
+use super::db::{
+    create_db, destroy_db, execute_query, execute_query_async, get_backup_download_url,
+    get_backup_upload_url, get_db_stats, get_db_status, get_job_status, BackupDownloadUrlResponse,
+    BackupUploadUrlResponse, CreateDbRequest, CreateDbResponse, DbStatusResponse,
+    DestroyDbResponse, InstanceStatsResponse, JobStatusResponse, QueryRequest, SubmitJobResponse,
+};
+use super::response::JsonQueryResult;
+use crate::db::health::InstanceHealthStatus;
+use crate::db::instance::InstanceStatus;
+
+#[derive(OpenApi)]
+#[openapi(
+    paths(create_db, get_db_status, destroy_db, execute_query, execute_query_async, get_db_stats, get_job_status, get_backup_download_url, get_backup_upload_url),
+    components(schemas(
+        CreateDbRequest,
+        CreateDbResponse,
+        DbStatusResponse,
+        DestroyDbResponse,
+        QueryRequest,
+        JsonQueryResult,
+        InstanceStatus,
+        InstanceHealthStatus,
+        InstanceStatsResponse,
+        SubmitJobResponse,
+        JobStatusResponse,
+        BackupDownloadUrlResponse,
+        BackupUploadUrlResponse,
+    )),
+    tags(
+        (name = "db", description = "Database instance lifecycle and query execution"),
+        (name = "jobs", description = "Background job submission and polling"),
+    ),
+)]
+struct ApiDoc;
+
+/// Generate the OpenAPI document from the `utoipa::path`-annotated handlers
+/// and `ToSchema`-derived request/response types.
+pub fn openapi_spec() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
 }
 
 pub fn swagger_ui() -> Html<&'static str> {
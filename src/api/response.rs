@@ -1,12 +1,16 @@
 use axum::{
+    body::Body,
     http::{header, StatusCode},
     response::{Response, sse::{Event, KeepAlive, Sse}},
     Json,
 };
+use bytes::Bytes;
 use futures::stream::Stream;
 use serde::Serialize;
 use serde_json::Value as JsonValue;
 use std::convert::Infallible;
+use utoipa::ToSchema;
+use uuid::Uuid;
 
 use crate::db::query::QueryEvent;
 use crate::db::RawQueryOutput;
@@ -20,7 +24,12 @@ pub fn query_event_to_sse(event: QueryEvent) -> Result<Event, Infallible> {
         QueryEvent::Line { .. } => ("line", serde_json::to_string(&event).unwrap()),
         QueryEvent::Record { .. } => ("record", serde_json::to_string(&event).unwrap()),
         QueryEvent::Error { .. } => ("error", serde_json::to_string(&event).unwrap()),
-        QueryEvent::Done { .. } => ("done", serde_json::to_string(&event).unwrap()),
+        // Every statement ends with a `Done` event; only the last one in the
+        // batch is the overall `done`, the rest are `statement` boundaries.
+        QueryEvent::Done { is_last, .. } => (
+            if *is_last { "done" } else { "statement" },
+            serde_json::to_string(&event).unwrap(),
+        ),
     };
 
     Ok(Event::default().event(event_type).data(data))
@@ -37,13 +46,143 @@ where
     Sse::new(sse_stream).keep_alive(KeepAlive::default())
 }
 
+// ============================================================================
+// CSV / TSV Response (format=csv, format=tsv)
+// ============================================================================
+
+/// Stream a query's `Record` events as RFC 4180 CSV, emitting a new header
+/// row whenever the column set changes (e.g. between statements).
+pub fn create_csv_response<S>(stream: S, db_id: Uuid) -> Response
+where
+    S: Stream<Item = QueryEvent> + Send + 'static,
+{
+    delimited_response(stream, db_id, ',', "csv", "text/csv; charset=utf-8", csv_escape)
+}
+
+/// Stream a query's `Record` events as tab-separated values, emitting a new
+/// header row whenever the column set changes (e.g. between statements).
+/// Embedded tabs/newlines/backslashes in values are backslash-escaped
+/// rather than quoted, since TSV has no quoting convention of its own.
+pub fn create_tsv_response<S>(stream: S, db_id: Uuid) -> Response
+where
+    S: Stream<Item = QueryEvent> + Send + 'static,
+{
+    delimited_response(
+        stream,
+        db_id,
+        '\t',
+        "tsv",
+        "text/tab-separated-values; charset=utf-8",
+        tsv_escape,
+    )
+}
+
+fn delimited_response<S>(
+    stream: S,
+    db_id: Uuid,
+    delimiter: char,
+    extension: &'static str,
+    content_type: &'static str,
+    escape: fn(&str) -> String,
+) -> Response
+where
+    S: Stream<Item = QueryEvent> + Send + 'static,
+{
+    use futures::StreamExt;
+
+    let mut last_header: Option<Vec<String>> = None;
+    let delimiter_str = delimiter.to_string();
+
+    let lines = stream.filter_map(move |event| {
+        let line = match event {
+            QueryEvent::Record { columns, row, .. } => {
+                let mut line = String::new();
+                if last_header.as_ref() != Some(&columns) {
+                    line.push_str(&render_fields(&columns, &delimiter_str, escape));
+                    line.push('\n');
+                    last_header = Some(columns);
+                }
+                let values: Vec<String> = row.iter().map(value_to_field).collect();
+                line.push_str(&render_fields(&values, &delimiter_str, escape));
+                line.push('\n');
+                Some(line)
+            }
+            _ => None,
+        };
+        std::future::ready(line)
+    });
+
+    let body = Body::from_stream(lines.map(|line| Ok::<_, Infallible>(Bytes::from(line))));
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{}.{}\"", db_id, extension),
+        )
+        .body(body)
+        .unwrap()
+}
+
+fn render_fields(fields: &[String], delimiter: &str, escape: fn(&str) -> String) -> String {
+    fields
+        .iter()
+        .map(|f| escape(f))
+        .collect::<Vec<_>>()
+        .join(delimiter)
+}
+
+/// Render a JSON value as a plain (unescaped) field value; SQL `NULL`
+/// becomes an empty field.
+fn value_to_field(value: &JsonValue) -> String {
+    match value {
+        JsonValue::Null => String::new(),
+        JsonValue::Bool(b) => b.to_string(),
+        JsonValue::Number(n) => n.to_string(),
+        JsonValue::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Quote a CSV field per RFC 4180 if it contains the delimiter, a quote, or
+/// a newline, doubling any embedded quotes.
+fn csv_escape(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') || field.contains('\r') {
+        let mut out = String::with_capacity(field.len() + 2);
+        out.push('"');
+        for c in field.chars() {
+            if c == '"' {
+                out.push('"');
+            }
+            out.push(c);
+        }
+        out.push('"');
+        out
+    } else {
+        field.to_string()
+    }
+}
+
+/// Backslash-escape a TSV field's literal backslashes, tabs, and newlines.
+fn tsv_escape(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
 // ============================================================================
 // Text Response (format=text)
 // ============================================================================
 
 pub fn create_text_response(output: RawQueryOutput) -> Response {
     // Combine stderr and stdout, with stderr first if present
-    let mut body = if output.stderr.is_empty() {
+    // `execute_raw` already runs each statement as its own CLI invocation and
+    // joins the outputs with a `---` separator, so multi-statement scripts
+    // come back cleanly separated without guessing at table borders.
+    let body = if output.stderr.is_empty() {
         output.stdout
     } else if output.stdout.is_empty() {
         output.stderr
@@ -51,11 +190,6 @@ pub fn create_text_response(output: RawQueryOutput) -> Response {
         format!("{}\n{}", output.stderr.trim_end(), output.stdout)
     };
 
-    // Add '---' separators between multiple result sets
-    // MySQL tables end with +---+ and the next table starts with +---+
-    // We detect this pattern and add a separator
-    body = add_result_separators(&body);
-
     Response::builder()
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
@@ -63,40 +197,17 @@ pub fn create_text_response(output: RawQueryOutput) -> Response {
         .unwrap()
 }
 
-/// Add '---' separators between multiple result sets in text output
-fn add_result_separators(text: &str) -> String {
-    let lines: Vec<&str> = text.lines().collect();
-    if lines.len() < 2 {
-        return text.to_string();
-    }
-
-    let mut result = Vec::new();
-    let mut prev_was_table_end = false;
-
-    for line in lines {
-        let is_table_border = line.starts_with('+') && line.ends_with('+') && line.contains('-');
-
-        // If previous line was a table border (end) and this is also a border (start of new table)
-        if prev_was_table_end && is_table_border {
-            result.push("---");
-        }
-
-        result.push(line);
-        prev_was_table_end = is_table_border;
-    }
-
-    result.join("\n")
-}
-
 // ============================================================================
 // JSON Response (format=json)
 // ============================================================================
 
-#[derive(Debug, Serialize)]
-pub struct JsonQueryResponse {
+/// Result of executing a single statement within a batch
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JsonQueryResult {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub columns: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Vec<Vec<Object>>>)]
     pub rows: Option<Vec<Vec<JsonValue>>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub affected_rows: Option<u64>,
@@ -106,7 +217,7 @@ pub struct JsonQueryResponse {
     pub messages: Vec<String>,
 }
 
-impl Default for JsonQueryResponse {
+impl Default for JsonQueryResult {
     fn default() -> Self {
         Self {
             columns: None,
@@ -118,40 +229,43 @@ impl Default for JsonQueryResponse {
     }
 }
 
-pub fn create_json_response(events: Vec<QueryEvent>) -> Json<JsonQueryResponse> {
-    let mut response = JsonQueryResponse::default();
+/// Group a batch's events into one result object per statement, in the
+/// order the statements were executed.
+pub fn create_json_response(events: Vec<QueryEvent>) -> Json<Vec<JsonQueryResult>> {
+    let mut results = Vec::new();
+    let mut current = JsonQueryResult::default();
     let mut columns: Option<Vec<String>> = None;
     let mut rows: Vec<Vec<JsonValue>> = Vec::new();
 
     for event in events {
         match event {
             QueryEvent::Line { text } => {
-                response.messages.push(text);
+                current.messages.push(text);
             }
-            QueryEvent::Record { columns: cols, row } => {
+            QueryEvent::Record { columns: cols, row, .. } => {
                 if columns.is_none() {
                     columns = Some(cols);
                 }
                 rows.push(row);
             }
             QueryEvent::Error { message } => {
-                // Collect all errors into one message
-                if let Some(existing) = &response.error {
-                    response.error = Some(format!("{}\n{}", existing, message));
+                // Collect all errors for this statement into one message
+                if let Some(existing) = &current.error {
+                    current.error = Some(format!("{}\n{}", existing, message));
                 } else {
-                    response.error = Some(message);
+                    current.error = Some(message);
                 }
             }
-            QueryEvent::Done { affected_rows } => {
-                response.affected_rows = affected_rows;
+            QueryEvent::Done { affected_rows, .. } => {
+                if !rows.is_empty() {
+                    current.columns = columns.take();
+                    current.rows = Some(std::mem::take(&mut rows));
+                }
+                current.affected_rows = affected_rows;
+                results.push(std::mem::take(&mut current));
             }
         }
     }
 
-    if !rows.is_empty() {
-        response.columns = columns;
-        response.rows = Some(rows);
-    }
-
-    Json(response)
+    Json(results)
 }
@@ -1,54 +1,137 @@
 use axum::{
+    http::{Method, StatusCode, Uri},
+    middleware,
     response::Html,
     routing::{delete, get, post},
-    Router,
+    Json, Router,
 };
+use serde::Serialize;
 use std::sync::Arc;
+use tower_http::compression::{
+    predicate::{NotForContentType, Predicate, SizeAbove},
+    CompressionLayer,
+};
 
 use crate::config::Config;
 use crate::db::manager::InstanceManager;
 use crate::db::query::QueryExecutor;
 use crate::docker::DockerManager;
+use crate::jobs::JobQueue;
 
-use super::db::{create_db, destroy_db, execute_query, get_db_status, AppState};
-use super::health::{health_check, HealthState};
+use super::auth::{require_api_key, AuthState};
+use super::db::{
+    create_db, destroy_db, execute_query, execute_query_async, get_backup_download_url,
+    get_backup_upload_url, get_db_history, get_db_stats, get_db_status, get_job_status,
+    stream_logs, AppState,
+};
+use super::health::{health_check, metrics, HealthState, MetricsState};
+use super::meta::get_build_details;
 use super::openapi::{openapi_spec, swagger_ui};
 
-async fn get_openapi() -> &'static str {
-    openapi_spec()
+async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(openapi_spec())
 }
 
 async fn get_docs() -> Html<&'static str> {
     swagger_ui()
 }
 
+#[derive(Debug, Serialize)]
+struct NotFoundBody {
+    error: &'static str,
+    path: String,
+    method: String,
+}
+
+/// Fallback for unmatched routes and method mismatches, returning a
+/// machine-parseable JSON body instead of an empty 404/405.
+async fn fallback(method: Method, uri: Uri) -> (StatusCode, Json<NotFoundBody>) {
+    (
+        StatusCode::NOT_FOUND,
+        Json(NotFoundBody {
+            error: "route_not_found",
+            path: uri.path().to_string(),
+            method: method.to_string(),
+        }),
+    )
+}
+
 pub fn create_router(
     manager: Arc<InstanceManager>,
     docker: Arc<DockerManager>,
     config: &Config,
+    query_executor: Arc<QueryExecutor>,
+    jobs: Arc<JobQueue>,
 ) -> Router {
     let app_state = Arc::new(AppState {
         manager: manager.clone(),
-        query_executor: QueryExecutor::new(docker.clone(), config.query_timeout),
+        query_executor,
+        jobs,
         inactivity_timeout_secs: config.inactivity_timeout.as_secs() as i64,
+        backup_presign_ttl_secs: config.backup_presign_ttl_secs,
     });
 
-    let health_state = Arc::new(HealthState { docker });
+    let health_state = Arc::new(HealthState {
+        docker: docker.clone(),
+        manager: manager.clone(),
+    });
 
-    let db_routes = Router::new()
+    let metrics_state = Arc::new(MetricsState {
+        docker,
+        manager: manager.clone(),
+        inactivity_timeout: config.inactivity_timeout,
+    });
+
+    let mut db_routes = Router::new()
         .route("/new", post(create_db))
         .route("/{db_id}", get(get_db_status))
         .route("/{db_id}", delete(destroy_db))
         .route("/{db_id}/query", post(execute_query))
+        .route("/{db_id}/query/async", post(execute_query_async))
+        .route("/{db_id}/stats", get(get_db_stats))
+        .route("/{db_id}/logs", get(stream_logs))
+        .route("/{db_id}/history", get(get_db_history))
+        .route("/{db_id}/backup/download-url", get(get_backup_download_url))
+        .route("/{db_id}/backup/upload-url", post(get_backup_upload_url))
+        .with_state(app_state.clone());
+
+    let mut job_routes = Router::new()
+        .route("/{job_id}", get(get_job_status))
         .with_state(app_state);
 
+    // Only install the API-key layer when a key is configured, preserving
+    // open access for local development otherwise.
+    if let Some(api_key_hash) = &config.api_key_hash {
+        let auth_state = Arc::new(AuthState {
+            api_key_hash: api_key_hash.clone(),
+        });
+        db_routes = db_routes.layer(middleware::from_fn_with_state(auth_state.clone(), require_api_key));
+        job_routes = job_routes.layer(middleware::from_fn_with_state(auth_state, require_api_key));
+    }
+
     let health_routes = Router::new()
         .route("/health", get(health_check))
         .with_state(health_state);
 
+    let metrics_routes = Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(metrics_state);
+
+    // Compress responses above a small threshold when the client advertises
+    // support for it via Accept-Encoding. SSE bodies are excluded by
+    // content-type so gzip framing/flushing doesn't interfere with
+    // keep-alive event delivery.
+    let compression = CompressionLayer::new()
+        .compress_when(SizeAbove::new(256).and(NotForContentType::new("text/event-stream")));
+
     Router::new()
         .nest("/db", db_routes)
+        .nest("/jobs", job_routes)
         .merge(health_routes)
+        .merge(metrics_routes)
+        .route("/meta/build", get(get_build_details))
         .route("/openapi.json", get(get_openapi))
         .route("/docs", get(get_docs))
+        .fallback(fallback)
+        .layer(compression)
 }
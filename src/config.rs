@@ -1,8 +1,71 @@
+use std::collections::HashMap;
 use std::env;
 use std::net::{IpAddr, SocketAddr};
+use std::path::Path;
 use std::str::FromStr;
 use std::time::Duration;
 
+/// Which `MetadataBackend` implementation the control plane runs against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataBackendKind {
+    /// Local SQLite file; single-node only
+    Sqlite,
+    /// Shared Postgres instance; lets multiple `db-api` nodes coordinate
+    /// on the same instance set
+    Postgres,
+}
+
+impl MetadataBackendKind {
+    fn from_env_str(s: &str) -> Self {
+        match s {
+            "postgres" | "postgresql" => Self::Postgres,
+            _ => Self::Sqlite,
+        }
+    }
+
+    /// Infer the backend from a connection string's scheme, for deployments
+    /// that set `METADATA_POSTGRES_URL` without also setting the explicit
+    /// `METADATA_BACKEND` override
+    fn from_connection_str(url: &str) -> Self {
+        if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+            Self::Postgres
+        } else {
+            Self::Sqlite
+        }
+    }
+}
+
+/// Which `BackupStore` implementation the control plane runs against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupBackendKind {
+    /// R2 (S3-compatible) object storage
+    R2,
+    /// Local filesystem, rooted at `backup_local_dir`; needs no cloud
+    /// credentials, so the service can run and be tested standalone
+    Local,
+}
+
+impl BackupBackendKind {
+    fn from_env_str(s: &str) -> Self {
+        match s {
+            "local" => Self::Local,
+            _ => Self::R2,
+        }
+    }
+}
+
+/// A single migration/seed SQL file, run in `version` order against a newly
+/// created (or restored) instance
+#[derive(Debug, Clone)]
+pub struct MigrationFile {
+    /// 1-indexed position in the dialect's migration sequence, derived from
+    /// sorting the directory's filenames
+    pub version: u32,
+    /// File name, kept for error messages and logging
+    pub name: String,
+    pub sql: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct Config {
     pub host: IpAddr,
@@ -13,21 +76,146 @@ pub struct Config {
     pub max_db_size_mb: u32,
     pub max_connections: u32,
 
+    // Absolute lease TTL, independent of the idle `inactivity_timeout`
+    /// Hard lifetime for an instance from creation, regardless of activity.
+    /// 0 disables the lease entirely.
+    pub lease_ttl_secs: u64,
+    /// Buffer after `lease_ttl_secs` elapses before the lease is actually
+    /// torn down, so a just-expired lease isn't reaped mid-request
+    pub lease_grace_secs: u64,
+
+    // Pool container health monitoring
+    pub pool_health_check_interval_secs: u64,
+    pub pool_eject_after_failures: u32,
+
+    // Per-instance health watchdog
+    /// How often to health-check every running database instance. 0 disables
+    /// the watchdog entirely.
+    pub instance_health_check_interval_secs: u64,
+    /// Per-check timeout for an individual instance's health check command
+    pub instance_health_check_timeout_secs: u64,
+    /// Consecutive failures before an instance is marked
+    /// `InstanceStatus::Unhealthy` and a restart is attempted
+    pub instance_health_failure_threshold: u32,
+    /// Restarts attempted before giving up and archiving an instance that
+    /// keeps failing its health check
+    pub instance_health_max_restarts: u32,
+
+    // Memory-pressure eviction
+    /// How often to sample container resource stats and check the memory
+    /// budget. 0 disables the sampler entirely.
+    pub stats_sample_interval_secs: u64,
+    /// Aggregate memory budget across all running db-api/pool containers,
+    /// in MB. 0 means unlimited (the sampler still runs, for visibility, but
+    /// never evicts).
+    pub container_memory_budget_mb: u64,
+    /// Once idle pool containers are exhausted and the budget is still
+    /// exceeded, an instance idle for at least this long (rather than the
+    /// full `inactivity_timeout`) is archived early: the longest-idle one
+    /// across all containers, one per sample tick. 0 disables this fallback, leaving
+    /// over-budget containers alone until `inactivity_timeout` catches up.
+    pub memory_pressure_idle_grace_secs: u64,
+
+    // SQLite WAL maintenance (no-op under the Postgres backend)
+    /// How often to run `PRAGMA wal_checkpoint(TRUNCATE)` against the
+    /// metadata database. 0 disables the checkpoint task entirely.
+    pub wal_checkpoint_interval_secs: u64,
+    /// Busy timeout applied to the checkpoint, so it waits out (rather than
+    /// fails on) a write in progress, up to this many seconds
+    pub wal_checkpoint_busy_timeout_secs: u64,
+
+    /// Max `DbInstance` entries kept in the in-memory cache at once (0 =
+    /// unbounded). Evicted instances aren't lost - they're rehydrated from
+    /// metadata the next time they're looked up.
+    pub instance_cache_capacity: u32,
+
+    // Per-pool capacity limiting
+    /// Max logical databases per pool container (0 = unlimited)
+    pub max_instances_per_pool: u32,
+    /// How long `create_instance` waits for capacity before returning
+    /// `PoolExhausted`, when scale-out is not possible
+    pub pool_wait_timeout_secs: u64,
+
+    // Periodic snapshots of active instances
+    /// How often to snapshot active instances. 0 disables periodic snapshots.
+    pub snapshot_interval_secs: u64,
+    /// Keep at most this many snapshots per instance (0 = unlimited)
+    pub snapshot_retention_count: u32,
+    /// Prune snapshots older than this many days (0 = unlimited)
+    pub snapshot_retention_days: u32,
+
+    // Background job queue (see `crate::jobs`)
+    /// How often a worker polls for the next `new` job when the queue is
+    /// empty. 0 disables the worker loop entirely (jobs can still be
+    /// enqueued, but nothing claims them).
+    pub job_poll_interval_secs: u64,
+    /// How often a running job's heartbeat is refreshed
+    pub job_heartbeat_interval_secs: u64,
+    /// How often the reaper scans for `running` jobs with a stale heartbeat
+    pub job_reap_interval_secs: u64,
+    /// A `running` job whose heartbeat is older than this is assumed to
+    /// belong to a dead worker and is requeued
+    pub job_stale_after_secs: u64,
+
     // Storage configuration
     pub metadata_db_path: String,
+    /// Size of the SQLite connection pool backing `MetadataStore`, and thus
+    /// the number of metadata calls that can run concurrently before
+    /// callers start queuing on the pool's semaphore
+    pub metadata_pool_size: u32,
+    /// Which `MetadataBackend` to construct at startup
+    pub metadata_backend: MetadataBackendKind,
+    /// Postgres connection string, used when `metadata_backend` is `Postgres`
+    pub metadata_postgres_url: String,
+    /// Per-dialect ordered migration/seed files, loaded from
+    /// `MIGRATIONS_DIR/{dialect}/*.sql` at startup and applied after a
+    /// database is created or restored
+    pub migrations: HashMap<String, Vec<MigrationFile>>,
 
-    // R2/S3 backup configuration
+    // Backup storage configuration
+    /// Which `BackupStore` to construct at startup
+    pub backup_backend: BackupBackendKind,
     pub r2_account_id: String,
     pub r2_access_key_id: String,
     pub r2_secret_access_key: String,
     pub r2_bucket: String,
+    /// Root directory for backups when `backup_backend` is `Local`
+    pub backup_local_dir: String,
+    /// Encrypts backups at rest when set (see `storage::encryption`). A
+    /// 32-byte value is used as the raw AES-256 key directly; anything else
+    /// is treated as a passphrase and stretched with Argon2id.
+    pub backup_encryption_key: Option<String>,
+    /// Keep at most this many backups per database (0 = unlimited)
+    pub backup_retain_count: u32,
+    /// Prune backups older than this many days (0 = unlimited)
+    pub backup_max_age_days: u32,
+    /// How long a presigned backup download/upload URL stays valid
+    pub backup_presign_ttl_secs: u64,
 
     // Feature flags
     pub backup_on_expiry: bool,
+    /// Send systemd readiness/watchdog notifications (see `crate::systemd`).
+    /// Only meaningful when actually run under systemd; harmless otherwise.
+    pub systemd_notify: bool,
+    /// On SIGTERM/SIGINT, stop db-api containers instead of destroying them,
+    /// so they're recovered by `recover_existing_instances` on the next
+    /// startup rather than losing their data. Ephemeral deployments should
+    /// leave this false to tear everything down on exit.
+    pub preserve_containers_on_exit: bool,
+
+    // Authentication
+    /// Argon2 hash of the configured API key, if any. When `None`, the
+    /// API-key middleware is not installed and routes remain open.
+    pub api_key_hash: Option<String>,
 }
 
 impl Config {
     pub fn from_env() -> Self {
+        // Read up front so `metadata_backend` can fall back to sniffing the
+        // connection string's scheme when `METADATA_BACKEND` isn't set
+        // explicitly (e.g. a bare `postgres://...` URL implies Postgres).
+        let metadata_postgres_url = env::var("METADATA_POSTGRES_URL").unwrap_or_default();
+
         Self {
             host: env::var("HOST")
                 .ok()
@@ -62,32 +250,258 @@ impl Config {
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(10),
 
+            lease_ttl_secs: env::var("LEASE_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            lease_grace_secs: env::var("LEASE_GRACE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300),
+
+            pool_health_check_interval_secs: env::var("POOL_HEALTH_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(30),
+            pool_eject_after_failures: env::var("POOL_EJECT_AFTER_FAILURES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+
+            instance_health_check_interval_secs: env::var("INSTANCE_HEALTH_CHECK_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            instance_health_check_timeout_secs: env::var("INSTANCE_HEALTH_CHECK_TIMEOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            instance_health_failure_threshold: env::var("INSTANCE_HEALTH_FAILURE_THRESHOLD")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            instance_health_max_restarts: env::var("INSTANCE_HEALTH_MAX_RESTARTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(2),
+
+            stats_sample_interval_secs: env::var("STATS_SAMPLE_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            container_memory_budget_mb: env::var("CONTAINER_MEMORY_BUDGET_MB")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            memory_pressure_idle_grace_secs: env::var("MEMORY_PRESSURE_IDLE_GRACE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+
+            wal_checkpoint_interval_secs: env::var("WAL_CHECKPOINT_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(300),
+            wal_checkpoint_busy_timeout_secs: env::var("WAL_CHECKPOINT_BUSY_TIMEOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+
+            instance_cache_capacity: env::var("INSTANCE_CACHE_CAPACITY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+
+            max_instances_per_pool: env::var("MAX_INSTANCES_PER_POOL")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            pool_wait_timeout_secs: env::var("POOL_WAIT_TIMEOUT_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+
+            snapshot_interval_secs: env::var("SNAPSHOT_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            snapshot_retention_count: env::var("SNAPSHOT_RETENTION_COUNT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
+            snapshot_retention_days: env::var("SNAPSHOT_RETENTION_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+
+            job_poll_interval_secs: env::var("JOB_POLL_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(5),
+            job_heartbeat_interval_secs: env::var("JOB_HEARTBEAT_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(15),
+            job_reap_interval_secs: env::var("JOB_REAP_INTERVAL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            job_stale_after_secs: env::var("JOB_STALE_AFTER_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(120),
+
             // Storage
             metadata_db_path: env::var("METADATA_DB_PATH")
                 .unwrap_or_else(|_| "/data/metadata.db".to_string()),
+            metadata_pool_size: env::var("METADATA_POOL_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(8),
+            metadata_backend: env::var("METADATA_BACKEND")
+                .map(|s| MetadataBackendKind::from_env_str(&s))
+                .unwrap_or_else(|_| MetadataBackendKind::from_connection_str(&metadata_postgres_url)),
+            metadata_postgres_url,
+            migrations: env::var("MIGRATIONS_DIR")
+                .ok()
+                .map(|dir| load_migrations(&dir))
+                .unwrap_or_default(),
 
-            // R2
+            // Backup storage
+            backup_backend: env::var("BACKUP_BACKEND")
+                .map(|s| BackupBackendKind::from_env_str(&s))
+                .unwrap_or(BackupBackendKind::R2),
             r2_account_id: env::var("R2_ACCOUNT_ID").unwrap_or_default(),
             r2_access_key_id: env::var("R2_ACCESS_KEY_ID").unwrap_or_default(),
             r2_secret_access_key: env::var("R2_SECRET_ACCESS_KEY").unwrap_or_default(),
             r2_bucket: env::var("R2_BUCKET").unwrap_or_else(|_| "db-api-backups".to_string()),
+            backup_local_dir: env::var("BACKUP_LOCAL_DIR")
+                .unwrap_or_else(|_| "/data/backups".to_string()),
+            backup_encryption_key: env::var("BACKUP_ENCRYPTION_KEY").ok().filter(|s| !s.is_empty()),
+            backup_retain_count: env::var("BACKUP_RETAIN_COUNT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            backup_max_age_days: env::var("BACKUP_MAX_AGE_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0),
+            backup_presign_ttl_secs: env::var("BACKUP_PRESIGN_TTL_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(900),
 
             // Features
             backup_on_expiry: env::var("BACKUP_ON_EXPIRY")
                 .map(|v| v == "true" || v == "1")
                 .unwrap_or(true),
+            systemd_notify: env::var("SYSTEMD_NOTIFY")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+            preserve_containers_on_exit: env::var("PRESERVE_CONTAINERS_ON_EXIT")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(false),
+
+            // Authentication
+            api_key_hash: env::var("API_KEY").ok().map(|key| hash_api_key(&key)),
         }
     }
 
-    /// Check if backup is enabled and configured
+    /// Check if backup is enabled and configured. The local backend needs no
+    /// credentials, so it's considered configured as long as the feature flag
+    /// is on; the R2 backend additionally needs its account/key pair set.
     pub fn backup_enabled(&self) -> bool {
-        self.backup_on_expiry
-            && !self.r2_account_id.is_empty()
-            && !self.r2_access_key_id.is_empty()
-            && !self.r2_secret_access_key.is_empty()
+        if !self.backup_on_expiry {
+            return false;
+        }
+
+        match self.backup_backend {
+            BackupBackendKind::Local => true,
+            BackupBackendKind::R2 => {
+                !self.r2_account_id.is_empty()
+                    && !self.r2_access_key_id.is_empty()
+                    && !self.r2_secret_access_key.is_empty()
+            }
+        }
     }
 
     pub fn socket_addr(&self) -> SocketAddr {
         SocketAddr::new(self.host, self.port)
     }
 }
+
+/// Scan `{dir}/{dialect}/*.sql` for every dialect subdirectory, sorting each
+/// dialect's files by name to assign 1-indexed versions
+fn load_migrations(dir: &str) -> HashMap<String, Vec<MigrationFile>> {
+    let mut migrations = HashMap::new();
+
+    let dialect_dirs = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            tracing::warn!("Failed to read migrations dir {}: {}", dir, e);
+            return migrations;
+        }
+    };
+
+    for entry in dialect_dirs.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let dialect = match path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+
+        migrations.insert(dialect, load_dialect_migrations(&path));
+    }
+
+    migrations
+}
+
+/// Load and sort the `*.sql` files within a single dialect's migration dir
+fn load_dialect_migrations(dir: &Path) -> Vec<MigrationFile> {
+    let mut files: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().and_then(|e| e.to_str()) == Some("sql"))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("Failed to read migration dir {}: {}", dir.display(), e);
+            return Vec::new();
+        }
+    };
+    files.sort();
+
+    files
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, path)| {
+            let name = path.file_name()?.to_str()?.to_string();
+            match std::fs::read_to_string(&path) {
+                Ok(sql) => Some(MigrationFile {
+                    version: (i + 1) as u32,
+                    name,
+                    sql,
+                }),
+                Err(e) => {
+                    tracing::warn!("Failed to read migration {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Hash a plaintext API key with Argon2 for storage/comparison
+fn hash_api_key(key: &str) -> String {
+    use argon2::password_hash::{rand_core::OsRng, PasswordHasher, SaltString};
+    use argon2::Argon2;
+
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(key.as_bytes(), &salt)
+        .expect("failed to hash API key")
+        .to_string()
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use super::instance::DbInstance;
+
+struct CacheEntry {
+    instance: DbInstance,
+    last_accessed: Instant,
+}
+
+/// Bounded, in-memory cache of active `DbInstance` entries, evicting the
+/// least-recently-accessed entry when an insert would exceed `capacity`.
+/// Eviction only drops the cache entry - the instance stays tracked in the
+/// metadata store and is transparently rehydrated by
+/// `InstanceManager::recover_single_instance` the next time it's looked up.
+/// `capacity == 0` means unbounded (no eviction), matching the rest of the
+/// config's "0 disables the limit" convention.
+pub struct InstanceCache {
+    capacity: usize,
+    entries: RwLock<HashMap<Uuid, CacheEntry>>,
+}
+
+impl InstanceCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Look up an instance, marking it most-recently-used on a hit
+    pub async fn get(&self, id: Uuid) -> Option<DbInstance> {
+        let mut entries = self.entries.write().await;
+        let entry = entries.get_mut(&id)?;
+        entry.last_accessed = Instant::now();
+        Some(entry.instance.clone())
+    }
+
+    /// Apply `f` to a cached instance in place, marking it
+    /// most-recently-used. No-op if the instance isn't cached.
+    pub async fn mutate<F: FnOnce(&mut DbInstance)>(&self, id: Uuid, f: F) {
+        let mut entries = self.entries.write().await;
+        if let Some(entry) = entries.get_mut(&id) {
+            f(&mut entry.instance);
+            entry.last_accessed = Instant::now();
+        }
+    }
+
+    pub async fn insert(&self, id: Uuid, instance: DbInstance) {
+        let mut entries = self.entries.write().await;
+        entries.insert(
+            id,
+            CacheEntry {
+                instance,
+                last_accessed: Instant::now(),
+            },
+        );
+        Self::evict_if_needed(&mut entries, self.capacity);
+    }
+
+    pub async fn remove(&self, id: Uuid) {
+        self.entries.write().await.remove(&id);
+    }
+
+    pub async fn len(&self) -> usize {
+        self.entries.read().await.len()
+    }
+
+    pub async fn keys(&self) -> Vec<Uuid> {
+        self.entries.read().await.keys().copied().collect()
+    }
+
+    pub async fn values(&self) -> Vec<DbInstance> {
+        self.entries
+            .read()
+            .await
+            .values()
+            .map(|e| e.instance.clone())
+            .collect()
+    }
+
+    /// Load-balancing hint only (an instance can be active but evicted from
+    /// cache), so this counts by `container_id` among whatever happens to be
+    /// cached right now rather than the full active set
+    pub async fn count_by_container(&self) -> HashMap<String, usize> {
+        let entries = self.entries.read().await;
+        let mut counts = HashMap::new();
+        for entry in entries.values() {
+            *counts.entry(entry.instance.container_id.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    fn evict_if_needed(entries: &mut HashMap<Uuid, CacheEntry>, capacity: usize) {
+        if capacity == 0 {
+            return;
+        }
+        while entries.len() > capacity {
+            let lru_id = match entries.iter().min_by_key(|(_, e)| e.last_accessed) {
+                Some((id, _)) => *id,
+                None => break,
+            };
+            entries.remove(&lru_id);
+        }
+    }
+}
@@ -1,11 +1,28 @@
 mod mysql;
+mod postgres;
 mod sqlserver;
 
 use crate::error::{AppError, Result};
 
 pub use mysql::MySqlDialect;
+pub use postgres::PostgresDialect;
 pub use sqlserver::SqlServerDialect;
 
+use super::identifiers::{DatabaseName, DatabaseUser};
+
+/// Positional placeholder syntax a dialect's CLI accepts in a query string.
+/// Used by `db::params::bind_params` to substitute `QueryRequest::params`
+/// server-side before dispatch, since the CLI tools don't expose
+/// protocol-level bind parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaceholderStyle {
+    /// `?`, consumed left-to-right, one per occurrence (MySQL, SQL Server)
+    QuestionMark,
+    /// `$1`, `$2`, ..., looked up by index so a parameter can be referenced
+    /// more than once (Postgres)
+    Dollar,
+}
+
 /// Trait defining database dialect behavior
 pub trait Dialect: Send + Sync {
     /// Dialect name (e.g., "mysql", "postgres", "sqlserver")
@@ -17,13 +34,22 @@ pub trait Dialect: Send + Sync {
     /// Default port inside the container
     fn default_port(&self) -> u16;
 
-    /// Environment variables for container initialization
-    fn env_vars(&self, db_name: &str, user: &str, password: &str) -> Vec<(String, String)>;
+    /// Environment variables for container initialization. Takes validated
+    /// identifiers, not raw strings, since these are interpolated straight
+    /// into the container's env.
+    fn env_vars(&self, db_name: &DatabaseName, user: &DatabaseUser, password: &str) -> Vec<(String, String)>;
 
     /// Build the CLI command to execute a query inside the container
     /// Returns (executable, args) where args includes the query
     fn cli_command(&self, db_name: &str, user: &str, password: &str, query: &str) -> (String, Vec<String>);
 
+    /// Positional placeholder syntax this dialect's CLI accepts. Defaults to
+    /// `?`, overridden by dialects whose native client uses `$1`-style
+    /// placeholders.
+    fn placeholder_style(&self) -> PlaceholderStyle {
+        PlaceholderStyle::QuestionMark
+    }
+
     /// Parse CLI output into structured format
     /// Returns true if this line indicates an error
     fn is_error_line(&self, line: &str) -> bool;
@@ -46,6 +72,111 @@ pub trait Dialect: Send + Sync {
     fn cli_command_text(&self, db_name: &str, user: &str, password: &str, query: &str) -> (String, Vec<String>) {
         self.cli_command(db_name, user, password, query)
     }
+
+    /// Optional command run once after the container reports ready, before
+    /// the pool container is considered usable (e.g. SQL Server's database
+    /// creation, which can't happen via env vars like MySQL's)
+    fn post_startup_command(&self, _db_name: &str, _user: &str, _password: &str) -> Option<(String, Vec<String>)> {
+        None
+    }
+
+    /// Whether this dialect supports dump/restore-based backup
+    fn supports_backup(&self) -> bool {
+        false
+    }
+
+    /// Build the CLI command to dump a database (used for archive/snapshot)
+    fn dump_command(&self, db_name: &str, user: &str, password: &str) -> (String, Vec<String>);
+
+    /// Build the CLI command to restore a database from a dump on stdin
+    fn restore_command(&self, db_name: &str, user: &str, password: &str) -> (String, Vec<String>);
+
+    // Pool container methods
+
+    /// DDL to create a logical database inside a shared pool container
+    fn create_database_sql(&self, db_name: &DatabaseName) -> String;
+
+    /// DDL to drop a logical database from a shared pool container
+    fn drop_database_sql(&self, db_name: &DatabaseName) -> String;
+
+    /// DDL to create a user scoped to a single logical database
+    fn create_user_sql(&self, user: &DatabaseUser, password: &str, db_name: &DatabaseName) -> String;
+
+    /// DDL to drop a user created by `create_user_sql`
+    fn drop_user_sql(&self, user: &DatabaseUser) -> String;
+
+    /// Root/admin username for the pool container
+    fn root_user(&self) -> &str;
+
+    /// Env var the pool container reads its root password from
+    fn root_password_env(&self) -> &str;
+
+    /// Environment variables for the shared pool container itself (as
+    /// opposed to a single logical database's `env_vars`)
+    fn pool_env_vars(&self, root_password: &str) -> Vec<(String, String)>;
+
+    /// Build the CLI command to run arbitrary admin SQL against the pool
+    /// container as root (used for DDL from `create_database_sql` and friends)
+    fn exec_sql_command(&self, root_password: &str, sql: &str) -> (String, Vec<String>);
+
+    /// Whether this dialect has a native (non-CLI) pooled connection
+    /// backend registered with `db::native_pool::ConnectionPoolManager`.
+    /// Defaults to `false`; only the Postgres dialect overrides it - see that
+    /// module for why MySQL and SQL Server don't. `QueryExecutor` falls back to
+    /// spawning a `docker exec` CLI process per statement whenever this is
+    /// `false`.
+    fn supports_native_pool(&self) -> bool {
+        false
+    }
+
+    /// Build the CLI command for CSV result output (quoted, with a header
+    /// row), used by `QueryExecutor::execute` instead of `cli_command`'s
+    /// tab-separated output.
+    fn cli_command_csv(&self, db_name: &str, user: &str, password: &str, query: &str) -> (String, Vec<String>);
+
+    /// Field delimiter `cli_command_csv`'s output uses. Defaults to `,`
+    /// (true CSV); dialects whose CLI has no comma-mode override this.
+    fn csv_delimiter(&self) -> u8 {
+        b','
+    }
+
+    // Schema migrations (see `InstanceManager::run_migrations`)
+
+    /// DDL to create the ledger table `run_migrations` records applied
+    /// migration versions in, inside the instance's own database. No
+    /// default: every dialect needs its own `IF NOT EXISTS` idiom (SQL
+    /// Server has no native one for `CREATE TABLE` pre-2016).
+    fn create_schema_migrations_table_sql(&self) -> String;
+
+    /// Query returning the single `version` column of every applied
+    /// migration, oldest first. Defaults to plain ANSI SQL valid against
+    /// all three dialects.
+    fn select_applied_migration_versions_sql(&self) -> String {
+        "SELECT version FROM schema_migrations ORDER BY version;".to_string()
+    }
+
+    /// DML recording that a migration was applied. `applied_at` is an
+    /// RFC 3339 timestamp computed by the caller rather than a DB-side
+    /// clock function, since those aren't portable across dialects.
+    fn record_migration_sql(&self, version: u32, name: &str, applied_at: &str) -> String {
+        format!(
+            "INSERT INTO schema_migrations (version, name, applied_at) VALUES ({}, '{}', '{}');",
+            version,
+            name.replace('\'', "''"),
+            applied_at
+        )
+    }
+
+    /// Statement opening a transaction around a single migration file.
+    /// Defaults to standard SQL; SQL Server spells this differently.
+    fn begin_transaction_sql(&self) -> &'static str {
+        "BEGIN;"
+    }
+
+    /// Statement committing the transaction opened by `begin_transaction_sql`.
+    fn commit_transaction_sql(&self) -> &'static str {
+        "COMMIT;"
+    }
 }
 
 /// Get a dialect implementation by name
@@ -53,11 +184,12 @@ pub fn get_dialect(name: &str) -> Result<Box<dyn Dialect>> {
     match name.to_lowercase().as_str() {
         "mysql" | "mariadb" => Ok(Box::new(MySqlDialect)),
         "sqlserver" | "mssql" => Ok(Box::new(SqlServerDialect)),
+        "postgres" | "postgresql" => Ok(Box::new(PostgresDialect)),
         _ => Err(AppError::DialectUnsupported(name.to_string())),
     }
 }
 
 /// List of supported dialect names
 pub fn supported_dialects() -> Vec<&'static str> {
-    vec!["mysql", "sqlserver"]
+    vec!["mysql", "sqlserver", "postgres"]
 }
@@ -1,3 +1,4 @@
+use super::super::identifiers::{DatabaseName, DatabaseUser};
 use super::Dialect;
 
 pub struct MySqlDialect;
@@ -15,7 +16,7 @@ impl Dialect for MySqlDialect {
         3306
     }
 
-    fn env_vars(&self, db_name: &str, user: &str, password: &str) -> Vec<(String, String)> {
+    fn env_vars(&self, db_name: &DatabaseName, user: &DatabaseUser, password: &str) -> Vec<(String, String)> {
         vec![
             ("MYSQL_ROOT_PASSWORD".to_string(), password.to_string()),
             ("MYSQL_DATABASE".to_string(), db_name.to_string()),
@@ -41,6 +42,36 @@ impl Dialect for MySqlDialect {
         )
     }
 
+    fn cli_command_csv(&self, db_name: &str, user: &str, _password: &str, query: &str) -> (String, Vec<String>) {
+        // Password is passed via MYSQL_PWD env var to avoid CLI warning.
+        // Same as cli_command but without --raw, so mysql's batch-mode
+        // backslash-escaping of embedded tabs/newlines/backslashes stays on.
+        (
+            "mysql".to_string(),
+            vec![
+                "-u".to_string(),
+                user.to_string(),
+                db_name.to_string(),
+                "-e".to_string(),
+                query.to_string(),
+                "--batch".to_string(),
+            ],
+        )
+    }
+
+    fn csv_delimiter(&self) -> u8 {
+        b'\t'
+    }
+
+    fn create_schema_migrations_table_sql(&self) -> String {
+        "CREATE TABLE IF NOT EXISTS schema_migrations (\
+            version INT PRIMARY KEY, \
+            name VARCHAR(255) NOT NULL, \
+            applied_at VARCHAR(32) NOT NULL\
+        );"
+        .to_string()
+    }
+
     fn cli_env_vars(&self, _db_name: &str, _user: &str, password: &str) -> Vec<(String, String)> {
         vec![("MYSQL_PWD".to_string(), password.to_string())]
     }
@@ -110,22 +141,22 @@ impl Dialect for MySqlDialect {
 
     // Pool container methods
 
-    fn create_database_sql(&self, db_name: &str) -> String {
+    fn create_database_sql(&self, db_name: &DatabaseName) -> String {
         format!("CREATE DATABASE `{}`", db_name)
     }
 
-    fn drop_database_sql(&self, db_name: &str) -> String {
+    fn drop_database_sql(&self, db_name: &DatabaseName) -> String {
         format!("DROP DATABASE IF EXISTS `{}`", db_name)
     }
 
-    fn create_user_sql(&self, user: &str, password: &str, db_name: &str) -> String {
+    fn create_user_sql(&self, user: &DatabaseUser, password: &str, db_name: &DatabaseName) -> String {
         format!(
             "CREATE USER '{}'@'%' IDENTIFIED BY '{}'; GRANT ALL PRIVILEGES ON `{}`.* TO '{}'@'%'; FLUSH PRIVILEGES;",
             user, password, db_name, user
         )
     }
 
-    fn drop_user_sql(&self, user: &str) -> String {
+    fn drop_user_sql(&self, user: &DatabaseUser) -> String {
         format!("DROP USER IF EXISTS '{}'@'%'", user)
     }
 
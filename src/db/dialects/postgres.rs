@@ -0,0 +1,183 @@
+use super::super::identifiers::{DatabaseName, DatabaseUser};
+use super::{Dialect, PlaceholderStyle};
+
+pub struct PostgresDialect;
+
+impl Dialect for PostgresDialect {
+    fn name(&self) -> &'static str {
+        "postgres"
+    }
+
+    fn placeholder_style(&self) -> PlaceholderStyle {
+        PlaceholderStyle::Dollar
+    }
+
+    fn supports_native_pool(&self) -> bool {
+        true
+    }
+
+    fn docker_image(&self) -> &'static str {
+        "postgres:16"
+    }
+
+    fn default_port(&self) -> u16 {
+        5432
+    }
+
+    fn env_vars(&self, db_name: &DatabaseName, user: &DatabaseUser, password: &str) -> Vec<(String, String)> {
+        vec![
+            ("POSTGRES_DB".to_string(), db_name.to_string()),
+            ("POSTGRES_USER".to_string(), user.to_string()),
+            ("POSTGRES_PASSWORD".to_string(), password.to_string()),
+        ]
+    }
+
+    fn cli_command(&self, db_name: &str, user: &str, _password: &str, query: &str) -> (String, Vec<String>) {
+        // Password is passed via PGPASSWORD env var to avoid a prompt
+        (
+            "psql".to_string(),
+            vec![
+                "-U".to_string(),
+                user.to_string(),
+                "-d".to_string(),
+                db_name.to_string(),
+                "-c".to_string(),
+                query.to_string(),
+                "--tuples-only".to_string(),
+                "--csv".to_string(),
+            ],
+        )
+    }
+
+    fn cli_command_csv(&self, db_name: &str, user: &str, _password: &str, query: &str) -> (String, Vec<String>) {
+        // Password is passed via PGPASSWORD env var to avoid a prompt.
+        // Same as cli_command but without --tuples-only, so the header row
+        // (needed for column names) comes through.
+        (
+            "psql".to_string(),
+            vec![
+                "-U".to_string(),
+                user.to_string(),
+                "-d".to_string(),
+                db_name.to_string(),
+                "-c".to_string(),
+                query.to_string(),
+                "--csv".to_string(),
+            ],
+        )
+    }
+
+    fn cli_env_vars(&self, _db_name: &str, _user: &str, password: &str) -> Vec<(String, String)> {
+        vec![("PGPASSWORD".to_string(), password.to_string())]
+    }
+
+    fn is_error_line(&self, line: &str) -> bool {
+        line.starts_with("psql:") || line.contains("ERROR:")
+    }
+
+    fn health_check_command(&self, db_name: &str, user: &str, _password: &str) -> (String, Vec<String>) {
+        // Password is passed via PGPASSWORD env var
+        (
+            "pg_isready".to_string(),
+            vec![
+                "-U".to_string(),
+                user.to_string(),
+                "-d".to_string(),
+                db_name.to_string(),
+            ],
+        )
+    }
+
+    fn cli_command_text(&self, db_name: &str, user: &str, _password: &str, query: &str) -> (String, Vec<String>) {
+        // Password is passed via PGPASSWORD env var
+        // Default aligned output (no --tuples-only/--csv) for pretty tables
+        (
+            "psql".to_string(),
+            vec![
+                "-U".to_string(),
+                user.to_string(),
+                "-d".to_string(),
+                db_name.to_string(),
+                "-c".to_string(),
+                query.to_string(),
+            ],
+        )
+    }
+
+    fn supports_backup(&self) -> bool {
+        true
+    }
+
+    fn dump_command(&self, db_name: &str, user: &str, _password: &str) -> (String, Vec<String>) {
+        // Password is passed via PGPASSWORD env var
+        (
+            "pg_dump".to_string(),
+            vec!["-U".to_string(), user.to_string(), db_name.to_string()],
+        )
+    }
+
+    fn restore_command(&self, db_name: &str, user: &str, _password: &str) -> (String, Vec<String>) {
+        // Password is passed via PGPASSWORD env var
+        // Reads SQL from stdin
+        (
+            "psql".to_string(),
+            vec!["-U".to_string(), user.to_string(), "-d".to_string(), db_name.to_string()],
+        )
+    }
+
+    fn create_schema_migrations_table_sql(&self) -> String {
+        "CREATE TABLE IF NOT EXISTS schema_migrations (\
+            version INTEGER PRIMARY KEY, \
+            name VARCHAR(255) NOT NULL, \
+            applied_at VARCHAR(32) NOT NULL\
+        );"
+        .to_string()
+    }
+
+    // Pool container methods
+
+    fn create_database_sql(&self, db_name: &DatabaseName) -> String {
+        format!(r#"CREATE DATABASE "{}""#, db_name)
+    }
+
+    fn drop_database_sql(&self, db_name: &DatabaseName) -> String {
+        format!(r#"DROP DATABASE IF EXISTS "{}""#, db_name)
+    }
+
+    fn create_user_sql(&self, user: &DatabaseUser, password: &str, db_name: &DatabaseName) -> String {
+        format!(
+            r#"CREATE USER "{}" WITH PASSWORD '{}'; GRANT ALL PRIVILEGES ON DATABASE "{}" TO "{}";"#,
+            user, password, db_name, user
+        )
+    }
+
+    fn drop_user_sql(&self, user: &DatabaseUser) -> String {
+        format!(r#"DROP USER IF EXISTS "{}""#, user)
+    }
+
+    fn root_user(&self) -> &str {
+        "postgres"
+    }
+
+    fn root_password_env(&self) -> &str {
+        "POSTGRES_PASSWORD"
+    }
+
+    fn pool_env_vars(&self, root_password: &str) -> Vec<(String, String)> {
+        vec![("POSTGRES_PASSWORD".to_string(), root_password.to_string())]
+    }
+
+    fn exec_sql_command(&self, root_password: &str, sql: &str) -> (String, Vec<String>) {
+        // psql has no inline password flag like sqlcmd's -P, so the
+        // password travels in a connection URI instead - this call site
+        // runs with no extra env (see cli_env_vars for the query path)
+        (
+            "psql".to_string(),
+            vec![
+                format!("postgresql://postgres:{}@localhost:5432/postgres", root_password),
+                "-c".to_string(),
+                sql.to_string(),
+            ],
+        )
+    }
+}
@@ -1,3 +1,4 @@
+use super::super::identifiers::{DatabaseName, DatabaseUser};
 use super::Dialect;
 
 /// SQL Server dialect
@@ -20,7 +21,7 @@ impl Dialect for SqlServerDialect {
         1433
     }
 
-    fn env_vars(&self, _db_name: &str, _user: &str, password: &str) -> Vec<(String, String)> {
+    fn env_vars(&self, _db_name: &DatabaseName, _user: &DatabaseUser, password: &str) -> Vec<(String, String)> {
         // SQL Server requires SA password and EULA acceptance
         // Database and user are created after startup via sqlcmd
         vec![
@@ -52,6 +53,39 @@ impl Dialect for SqlServerDialect {
         )
     }
 
+    fn cli_command_csv(&self, db_name: &str, user: &str, password: &str, query: &str) -> (String, Vec<String>) {
+        // sqlcmd has no quoting/escaping mechanism for embedded delimiters
+        // in its output - a real limitation of the tool, not something this
+        // dialect can paper over short of abandoning sqlcmd entirely. Data
+        // containing a literal tab or newline is genuinely unsafe to
+        // represent faithfully here, so this is identical to cli_command.
+        self.cli_command(db_name, user, password, query)
+    }
+
+    fn csv_delimiter(&self) -> u8 {
+        b'\t'
+    }
+
+    fn create_schema_migrations_table_sql(&self) -> String {
+        // No native CREATE TABLE IF NOT EXISTS pre-2016, same sys.* guard
+        // used by create_database_sql/create_user_sql below.
+        "IF NOT EXISTS (SELECT name FROM sys.tables WHERE name = 'schema_migrations') \
+         CREATE TABLE schema_migrations (\
+             version INT PRIMARY KEY, \
+             name VARCHAR(255) NOT NULL, \
+             applied_at VARCHAR(32) NOT NULL\
+         );"
+        .to_string()
+    }
+
+    fn begin_transaction_sql(&self) -> &'static str {
+        "BEGIN TRANSACTION;"
+    }
+
+    fn commit_transaction_sql(&self) -> &'static str {
+        "COMMIT TRANSACTION;"
+    }
+
     fn cli_env_vars(&self, _db_name: &str, _user: &str, password: &str) -> Vec<(String, String)> {
         vec![("SQLCMDPASSWORD".to_string(), password.to_string())]
     }
@@ -103,21 +137,21 @@ impl Dialect for SqlServerDialect {
 
     // Pool container methods
 
-    fn create_database_sql(&self, db_name: &str) -> String {
+    fn create_database_sql(&self, db_name: &DatabaseName) -> String {
         format!(
             "IF NOT EXISTS (SELECT name FROM sys.databases WHERE name = '{}') CREATE DATABASE [{}]",
             db_name, db_name
         )
     }
 
-    fn drop_database_sql(&self, db_name: &str) -> String {
+    fn drop_database_sql(&self, db_name: &DatabaseName) -> String {
         format!(
             "IF EXISTS (SELECT name FROM sys.databases WHERE name = '{}') DROP DATABASE [{}]",
             db_name, db_name
         )
     }
 
-    fn create_user_sql(&self, user: &str, password: &str, db_name: &str) -> String {
+    fn create_user_sql(&self, user: &DatabaseUser, password: &str, db_name: &DatabaseName) -> String {
         // SQL Server requires: create login, then use the database, create user, grant permissions
         format!(
             "IF NOT EXISTS (SELECT name FROM sys.server_principals WHERE name = '{}') \
@@ -130,7 +164,7 @@ impl Dialect for SqlServerDialect {
         )
     }
 
-    fn drop_user_sql(&self, user: &str) -> String {
+    fn drop_user_sql(&self, user: &DatabaseUser) -> String {
         format!(
             "IF EXISTS (SELECT name FROM sys.server_principals WHERE name = '{}') \
              DROP LOGIN [{}]",
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Health state of a single user database instance, tracked by the
+/// background watchdog in `InstanceManager::start_instance_health_task`.
+/// Distinct from `pool::PoolHealthStatus`, which tracks the shared pool
+/// *container* an instance happens to live on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum InstanceHealthStatus {
+    Healthy,
+    Unhealthy,
+}
+
+#[derive(Debug, Clone)]
+pub struct InstanceHealthEntry {
+    pub status: InstanceHealthStatus,
+    pub last_checked_at: DateTime<Utc>,
+    consecutive_failures: u32,
+    restart_attempts: u32,
+}
+
+impl InstanceHealthEntry {
+    fn new() -> Self {
+        Self {
+            status: InstanceHealthStatus::Healthy,
+            last_checked_at: Utc::now(),
+            consecutive_failures: 0,
+            restart_attempts: 0,
+        }
+    }
+}
+
+/// What the watchdog should do after a failed check, once `record_failure`
+/// has updated the instance's tracked state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureOutcome {
+    /// Below `fail_threshold` consecutive failures so far; kept tracking but
+    /// the instance isn't marked `Unhealthy` yet.
+    BelowThreshold,
+    /// Reached `fail_threshold`; the instance is now `Unhealthy` and this is
+    /// restart attempt number `n` of `max_restarts`.
+    Restart(u32),
+    /// Reached `fail_threshold` again after exhausting `max_restarts`; the
+    /// watchdog should give up and evict the instance.
+    Exhausted,
+}
+
+/// Tracks liveness of every actively-checked user database instance, keyed
+/// by instance id.
+#[derive(Default)]
+pub struct InstanceHealthTracker {
+    entries: RwLock<HashMap<Uuid, InstanceHealthEntry>>,
+}
+
+impl InstanceHealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a passing health check, resetting all failure/restart
+    /// tracking back to healthy.
+    pub async fn record_success(&self, id: Uuid) {
+        self.entries.write().await.insert(id, InstanceHealthEntry::new());
+    }
+
+    /// Record a failing health check and return what the watchdog should do
+    /// next. See `FailureOutcome`.
+    pub async fn record_failure(
+        &self,
+        id: Uuid,
+        fail_threshold: u32,
+        max_restarts: u32,
+    ) -> FailureOutcome {
+        let mut entries = self.entries.write().await;
+        let entry = entries.entry(id).or_insert_with(InstanceHealthEntry::new);
+
+        entry.consecutive_failures += 1;
+        entry.last_checked_at = Utc::now();
+
+        if entry.consecutive_failures < fail_threshold {
+            return FailureOutcome::BelowThreshold;
+        }
+
+        entry.status = InstanceHealthStatus::Unhealthy;
+        entry.consecutive_failures = 0;
+
+        if entry.restart_attempts >= max_restarts {
+            return FailureOutcome::Exhausted;
+        }
+
+        entry.restart_attempts += 1;
+        FailureOutcome::Restart(entry.restart_attempts)
+    }
+
+    /// Drop tracking for an instance that's been evicted/archived/destroyed
+    pub async fn forget(&self, id: Uuid) {
+        self.entries.write().await.remove(&id);
+    }
+
+    /// Latest observed state for an instance, if the watchdog has checked it
+    /// at least once since startup
+    pub async fn snapshot(&self, id: Uuid) -> Option<InstanceHealthEntry> {
+        self.entries.read().await.get(&id).cloned()
+    }
+}
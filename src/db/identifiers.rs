@@ -0,0 +1,82 @@
+use crate::error::{AppError, Result};
+
+/// MySQL's identifier length limit, the tightest of the dialects we support
+const MAX_DB_NAME_LEN: usize = 64;
+/// MySQL's username length limit
+const MAX_USER_LEN: usize = 32;
+
+/// A validated database name, safe to interpolate into dialect DDL without
+/// quoting games: constructed where the name is minted
+/// (`InstanceManager::create_instance_with_id`), and again wherever a stored
+/// name is read back from metadata for DDL, so a malformed or hostile value
+/// can never reach `format!`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DatabaseName(String);
+
+/// A validated database username. See `DatabaseName` for the validation rule.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DatabaseUser(String);
+
+impl DatabaseName {
+    pub fn new(value: &str) -> Result<Self> {
+        validate_identifier(value, MAX_DB_NAME_LEN)?;
+        Ok(Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl DatabaseUser {
+    pub fn new(value: &str) -> Result<Self> {
+        validate_identifier(value, MAX_USER_LEN)?;
+        Ok(Self(value.to_string()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for DatabaseName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::fmt::Display for DatabaseUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Shared validation behind both newtypes: a leading alphabetic character
+/// followed by `[A-Za-z0-9_]`, within `max_len` characters. Anything outside
+/// that charset could otherwise escape the quoting in dialect DDL.
+fn validate_identifier(value: &str, max_len: usize) -> Result<()> {
+    if value.is_empty() || value.len() > max_len {
+        return Err(AppError::InvalidIdentifier(format!(
+            "must be 1-{} characters, got {:?}",
+            max_len, value
+        )));
+    }
+
+    let mut chars = value.chars();
+    let first = chars.next().unwrap();
+    if !first.is_ascii_alphabetic() {
+        return Err(AppError::InvalidIdentifier(format!(
+            "must start with a letter: {:?}",
+            value
+        )));
+    }
+
+    if !chars.all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(AppError::InvalidIdentifier(format!(
+            "must contain only letters, digits, and underscores: {:?}",
+            value
+        )));
+    }
+
+    Ok(())
+}
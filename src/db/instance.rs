@@ -2,13 +2,16 @@ use chrono::{DateTime, Utc};
 use serde::Serialize;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum InstanceStatus {
     Starting,
     Running,
     Stopped,
     Destroyed,
+    /// Failed its last `instance_health_failure_threshold` consecutive
+    /// watchdog checks (see `InstanceManager::start_instance_health_task`)
+    Unhealthy,
 }
 
 #[derive(Debug, Clone)]
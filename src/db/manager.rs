@@ -1,26 +1,55 @@
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::RwLock;
+use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio::time::interval;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
-use crate::config::Config;
-use crate::docker::DockerManager;
+use crate::config::{Config, MigrationFile};
+use crate::docker::{ContainerStats, DockerManager, WaitStrategy};
 use crate::error::{AppError, Result};
-use crate::storage::{BackupManager, InstanceState, MetadataStore, PoolContainer, StoredInstance};
+use crate::storage::{
+    BackupStore, InstanceState, MetadataBackend, PoolContainer, StoredInstance, StoredSnapshot,
+};
 
+use super::cache::InstanceCache;
 use super::dialects::{get_dialect, Dialect};
+use super::health::{FailureOutcome, InstanceHealthEntry, InstanceHealthTracker};
+use super::identifiers::{DatabaseName, DatabaseUser};
 use super::instance::{DbInstance, InstanceStatus};
+use super::pool::{PoolActivityTracker, PoolHealthEntry, PoolHealthTracker};
 
 pub struct InstanceManager {
-    /// In-memory cache for active instances (fast access)
-    instances: Arc<RwLock<HashMap<Uuid, DbInstance>>>,
-    /// Persistent metadata store (SQLite)
-    metadata: Arc<MetadataStore>,
-    /// Optional backup manager (R2)
-    backup: Option<Arc<BackupManager>>,
+    /// Bounded, size-limited cache for active instances (fast access);
+    /// evicted entries are transparently rehydrated from metadata on demand
+    instances: Arc<InstanceCache>,
+    /// All pool containers known for each dialect, allowing more than one
+    /// backend per dialect once scale-out kicks in
+    pools: Arc<RwLock<HashMap<String, Vec<PoolContainer>>>>,
+    /// Liveness tracking for pool containers, updated by the background monitor
+    pool_health: Arc<PoolHealthTracker>,
+    /// When each pool container was last handed out, used by the memory-
+    /// pressure eviction task to pick the least-recently-active idle ones first
+    pool_activity: Arc<PoolActivityTracker>,
+    /// Per-pool-container capacity semaphore, keyed by container id, enforcing
+    /// `max_instances_per_pool`
+    pool_semaphores: Arc<RwLock<HashMap<String, Arc<Semaphore>>>>,
+    /// Capacity permits held by instances created/restored by this process,
+    /// keyed by instance id. Instances recovered from a prior process have no
+    /// entry here; their capacity is accounted for in the semaphore's initial
+    /// permit count instead (see `ensure_capacity_semaphore`)
+    capacity_permits: Arc<RwLock<HashMap<Uuid, OwnedSemaphorePermit>>>,
+    /// Liveness tracking for individual user database instances, updated by
+    /// the background watchdog (see `start_instance_health_task`)
+    instance_health: Arc<InstanceHealthTracker>,
+    /// Persistent metadata store (SQLite or Postgres, selected at startup)
+    metadata: Arc<dyn MetadataBackend>,
+    /// Optional backup store (R2, local filesystem, or whatever else
+    /// implements `BackupStore`), selected at startup from `Config`
+    backup: Option<Arc<dyn BackupStore>>,
     docker: Arc<DockerManager>,
     config: Config,
 }
@@ -28,14 +57,20 @@ pub struct InstanceManager {
 impl InstanceManager {
     pub fn new(
         docker: DockerManager,
-        metadata: MetadataStore,
-        backup: Option<BackupManager>,
+        metadata: Arc<dyn MetadataBackend>,
+        backup: Option<Arc<dyn BackupStore>>,
         config: Config,
     ) -> Self {
         Self {
-            instances: Arc::new(RwLock::new(HashMap::new())),
-            metadata: Arc::new(metadata),
-            backup: backup.map(Arc::new),
+            instances: Arc::new(InstanceCache::new(config.instance_cache_capacity as usize)),
+            pools: Arc::new(RwLock::new(HashMap::new())),
+            pool_health: Arc::new(PoolHealthTracker::new()),
+            pool_activity: Arc::new(PoolActivityTracker::new()),
+            pool_semaphores: Arc::new(RwLock::new(HashMap::new())),
+            capacity_permits: Arc::new(RwLock::new(HashMap::new())),
+            instance_health: Arc::new(InstanceHealthTracker::new()),
+            metadata,
+            backup,
             docker: Arc::new(docker),
             config,
         }
@@ -45,31 +80,211 @@ impl InstanceManager {
         self.docker.clone()
     }
 
-    pub fn metadata(&self) -> Arc<MetadataStore> {
+    pub fn metadata(&self) -> Arc<dyn MetadataBackend> {
         self.metadata.clone()
     }
 
+    /// The configured backup store, if any (see `Config::backup_backend`).
+    pub fn backup(&self) -> Option<Arc<dyn BackupStore>> {
+        self.backup.clone()
+    }
+
+    /// Select a pool container for the given dialect with a free capacity
+    /// slot for `instance_id`, creating or scaling out pool containers as
+    /// needed. Among healthy containers with spare capacity, picks the one
+    /// currently hosting the fewest instances (least-loaded), mirroring
+    /// qorb's "select among healthy backends" behavior. If every known
+    /// container is full, provisions an additional one for the dialect
+    /// (elastic scale-out); if that isn't possible, waits up to
+    /// `pool_wait_timeout_secs` for a slot to free up before giving up with
+    /// `PoolExhausted`.
+    async fn select_pool_container(&self, dialect: &dyn Dialect, instance_id: Uuid) -> Result<PoolContainer> {
+        let dialect_name = dialect.name();
+
+        let healthy_ids = self.pool_health.healthy_containers(dialect_name).await;
+        let mut candidates: Vec<PoolContainer> = if healthy_ids.is_empty() {
+            Vec::new()
+        } else {
+            let pools = self.pools.read().await;
+            pools
+                .get(dialect_name)
+                .map(|containers| {
+                    containers
+                        .iter()
+                        .filter(|c| healthy_ids.contains(&c.container_id))
+                        .cloned()
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        if !candidates.is_empty() {
+            let counts = self.instances.count_by_container().await;
+            candidates.sort_by_key(|c| counts.get(c.container_id.as_str()).copied().unwrap_or(0));
+        }
+
+        // Try the least-loaded healthy containers first, in order, for a free slot
+        for candidate in &candidates {
+            if self.reserve_capacity(candidate, instance_id).await {
+                self.pool_activity.touch(&candidate.container_id).await;
+                return Ok(candidate.clone());
+            }
+        }
+
+        if candidates.is_empty() {
+            // No healthy container known at all yet for this dialect - look
+            // it up in metadata or create the first one
+            let pool = self.get_or_create_pool_container(dialect).await?;
+            if self.reserve_capacity(&pool, instance_id).await {
+                self.pool_activity.touch(&pool.container_id).await;
+                return Ok(pool);
+            }
+            candidates.push(pool);
+        } else {
+            // Every known container is at capacity - scale out
+            match self.provision_pool_container(dialect).await {
+                Ok(pool) => {
+                    if self.reserve_capacity(&pool, instance_id).await {
+                        self.pool_activity.touch(&pool.container_id).await;
+                        return Ok(pool);
+                    }
+                    candidates.push(pool);
+                }
+                Err(e) => {
+                    debug!(
+                        "Could not scale out pool for {}: {}, waiting for capacity instead",
+                        dialect_name, e
+                    );
+                }
+            }
+        }
+
+        // No spare capacity anywhere and scale-out wasn't possible - wait for
+        // a slot to free up, bounded by pool_wait_timeout_secs
+        let pool = candidates.into_iter().next().ok_or_else(|| {
+            AppError::Internal(format!("No pool container available for {}", dialect_name))
+        })?;
+        self.reserve_capacity_waiting(&pool, instance_id).await?;
+        self.pool_activity.touch(&pool.container_id).await;
+        Ok(pool)
+    }
+
+    /// Capacity for a pool container; `max_instances == 0` means unlimited
+    fn effective_capacity(max_instances: u32) -> usize {
+        if max_instances == 0 {
+            usize::MAX / 2
+        } else {
+            max_instances as usize
+        }
+    }
+
+    /// Get or create the semaphore tracking spare capacity for a pool
+    /// container, seeding its available permits from the persisted instance
+    /// count so scale-out/back-pressure decisions survive a restart.
+    async fn ensure_capacity_semaphore(&self, pool: &PoolContainer) -> Arc<Semaphore> {
+        let mut semaphores = self.pool_semaphores.write().await;
+        semaphores
+            .entry(pool.container_id.clone())
+            .or_insert_with(|| {
+                let capacity = Self::effective_capacity(pool.max_instances);
+                let available = capacity.saturating_sub(pool.instance_count as usize);
+                Arc::new(Semaphore::new(available))
+            })
+            .clone()
+    }
+
+    /// Try to claim one slot of capacity in `pool` for `instance_id` without
+    /// blocking. On success, persists the incremented instance count and
+    /// holds the permit until `release_capacity` is called.
+    async fn reserve_capacity(&self, pool: &PoolContainer, instance_id: Uuid) -> bool {
+        let semaphore = self.ensure_capacity_semaphore(pool).await;
+        match semaphore.try_acquire_owned() {
+            Ok(permit) => {
+                let _ = self.metadata.adjust_pool_instance_count(&pool.container_id, 1).await;
+                self.capacity_permits.write().await.insert(instance_id, permit);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Block until a slot of capacity frees up in `pool`, bounded by
+    /// `pool_wait_timeout_secs`, returning `PoolExhausted` on timeout.
+    async fn reserve_capacity_waiting(&self, pool: &PoolContainer, instance_id: Uuid) -> Result<()> {
+        let semaphore = self.ensure_capacity_semaphore(pool).await;
+        let wait = Duration::from_secs(self.config.pool_wait_timeout_secs);
+
+        let permit = tokio::time::timeout(wait, semaphore.acquire_owned())
+            .await
+            .map_err(|_| AppError::PoolExhausted)?
+            .map_err(|_| AppError::PoolExhausted)?;
+
+        let _ = self.metadata.adjust_pool_instance_count(&pool.container_id, 1).await;
+        self.capacity_permits.write().await.insert(instance_id, permit);
+        Ok(())
+    }
+
+    /// Release the capacity slot held for `instance_id`, if any. Instances
+    /// recovered from a prior process have no in-memory permit, since their
+    /// capacity was instead accounted for in the semaphore's starting permit
+    /// count, so they fall back to adding a permit back directly.
+    async fn release_capacity(&self, container_id: &str, instance_id: Uuid) {
+        let _ = self.metadata.adjust_pool_instance_count(container_id, -1).await;
+
+        let permit = self.capacity_permits.write().await.remove(&instance_id);
+        match permit {
+            Some(permit) => drop(permit),
+            None => {
+                let semaphores = self.pool_semaphores.read().await;
+                if let Some(semaphore) = semaphores.get(container_id) {
+                    semaphore.add_permits(1);
+                }
+            }
+        }
+    }
+
     /// Get or create a pool container for the given dialect
     async fn get_or_create_pool_container(&self, dialect: &dyn Dialect) -> Result<PoolContainer> {
         let dialect_name = dialect.name();
 
-        // Check if we have a pool container in metadata
-        if let Some(pool) = self.metadata.get_pool_container(dialect_name)? {
+        for pool in self.metadata.list_pool_containers_for_dialect(dialect_name).await? {
             // Verify container is still running
             if self.docker.is_running(&pool.container_id).await.unwrap_or(false) {
                 debug!("Using existing pool container for {}: {}", dialect_name, pool.container_id);
+                self.register_pool(&pool).await;
                 return Ok(pool);
             }
             // Container died, remove stale record
-            info!("Pool container {} for {} is not running, creating new one", pool.container_id, dialect_name);
-            self.metadata.delete_pool_container(dialect_name)?;
+            info!("Pool container {} for {} is not running, removing", pool.container_id, dialect_name);
+            self.metadata.delete_pool_container(&pool.container_id).await?;
+            self.pool_health.forget(&pool.container_id).await;
         }
 
-        // Create new pool container
+        self.provision_pool_container(dialect).await
+    }
+
+    /// Always create and register a brand-new pool container for `dialect`.
+    /// Used both for a dialect's first container and for elastic scale-out
+    /// once existing containers are at capacity.
+    async fn provision_pool_container(&self, dialect: &dyn Dialect) -> Result<PoolContainer> {
+        let dialect_name = dialect.name();
+
         info!("Creating new pool container for {}", dialect_name);
         let root_password = generate_password();
         let env_vars = dialect.pool_env_vars(&root_password);
 
+        // Check using root credentials, since no app user/db exists yet
+        let (cmd, args) = dialect.exec_sql_command(&root_password, "SELECT 1");
+        let timeout = Duration::from_secs(dialect.startup_timeout_secs());
+        let wait_strategy = WaitStrategy::ExecSucceeds {
+            cmd,
+            args,
+            interval: Duration::from_secs(1),
+            retries: (timeout.as_secs().max(1)) as u32,
+        };
+
+        info!("Waiting for pool container {} to be ready...", dialect_name);
+
         let (container_id, host_port) = self
             .docker
             .create_pool_container(
@@ -78,24 +293,16 @@ impl InstanceManager {
                 env_vars,
                 dialect.default_port(),
                 self.config.container_memory_mb,
+                Some(wait_strategy),
             )
-            .await?;
-
-        // Wait for database to be ready (using a simple health check)
-        let timeout = Duration::from_secs(dialect.startup_timeout_secs());
-        info!("Waiting for pool container {} to be ready...", dialect_name);
-
-        let ready = self
-            .wait_for_pool_ready(&container_id, dialect, &root_password, timeout)
-            .await;
-
-        if !ready {
-            warn!("Pool container {} failed to become ready, cleaning up", dialect_name);
-            let _ = self.docker.destroy_container(&container_id).await;
-            return Err(AppError::Internal(
-                format!("Pool container for {} failed to start within timeout", dialect_name),
-            ));
-        }
+            .await
+            .map_err(|e| {
+                warn!("Pool container for {} failed to become ready: {}", dialect_name, e);
+                AppError::Internal(format!(
+                    "Pool container for {} failed to start within timeout",
+                    dialect_name
+                ))
+            })?;
 
         let pool = PoolContainer {
             dialect: dialect_name.to_string(),
@@ -104,64 +311,31 @@ impl InstanceManager {
             root_password,
             created_at: chrono::Utc::now(),
             status: "running".to_string(),
+            max_instances: self.config.max_instances_per_pool,
+            instance_count: 0,
         };
 
-        self.metadata.upsert_pool_container(&pool)?;
+        self.metadata.upsert_pool_container(&pool).await?;
+        self.register_pool(&pool).await;
         info!("Pool container for {} ready on port {}", dialect_name, host_port);
 
         Ok(pool)
     }
 
-    /// Wait for pool container to be ready
-    async fn wait_for_pool_ready(
-        &self,
-        container_id: &str,
-        dialect: &dyn Dialect,
-        root_password: &str,
-        timeout: Duration,
-    ) -> bool {
-        use std::time::Instant;
-
-        let start = Instant::now();
-        let check_interval = Duration::from_millis(1000);
-
-        // For pool container, we check using root credentials
-        let (cmd, args) = dialect.exec_sql_command(root_password, "SELECT 1");
-
-        while start.elapsed() < timeout {
-            // Check if container is still running
-            match self.docker.is_running(container_id).await {
-                Ok(true) => {}
-                Ok(false) => {
-                    warn!("Pool container {} is not running", container_id);
-                    return false;
-                }
-                Err(e) => {
-                    debug!("Error checking container status: {}", e);
-                }
-            }
-
-            // Try the health check
-            match self.docker.exec(container_id, &cmd, &args, &[]).await {
-                Ok(output) => {
-                    if output.exit_code == Some(0) {
-                        debug!("Pool container health check passed");
-                        return true;
-                    }
-                    debug!(
-                        "Pool health check failed with exit code {:?}: {}",
-                        output.exit_code, output.stderr
-                    );
-                }
-                Err(e) => {
-                    debug!("Pool health check exec failed: {}", e);
-                }
+    /// Track a pool container in the in-memory registry, seed its capacity
+    /// semaphore, and mark it healthy
+    async fn register_pool(&self, pool: &PoolContainer) {
+        {
+            let mut pools = self.pools.write().await;
+            let containers = pools.entry(pool.dialect.clone()).or_default();
+            if !containers.iter().any(|c| c.container_id == pool.container_id) {
+                containers.push(pool.clone());
             }
-
-            tokio::time::sleep(check_interval).await;
         }
-
-        false
+        self.ensure_capacity_semaphore(pool).await;
+        self.pool_health
+            .record_success(&pool.container_id, &pool.dialect)
+            .await;
     }
 
     /// Create a new database instance
@@ -170,30 +344,31 @@ impl InstanceManager {
     }
 
     /// Create or restore a database instance
-    /// If db_id is provided and exists as archived, restore it
+    /// If db_id is provided and exists as archived, restore it. `restore_at`
+    /// optionally pins the restore to the most recent snapshot at or before
+    /// that point in time instead of the latest archive-time backup.
     pub async fn get_or_create_instance(
         &self,
         dialect_name: &str,
         db_id: Option<Uuid>,
+        restore_at: Option<DateTime<Utc>>,
     ) -> Result<(DbInstance, bool)> {
         // Check if we should restore an existing instance
         if let Some(id) = db_id {
             // Check metadata store for this ID
-            if let Some(stored) = self.metadata.get_instance(id)? {
+            if let Some(stored) = self.metadata.get_instance(id).await? {
                 match stored.status {
                     InstanceState::Active => {
-                        // Already active, return from cache
-                        let instances = self.instances.read().await;
-                        if let Some(instance) = instances.get(&id) {
-                            return Ok((instance.clone(), false));
+                        // Already active, return from cache (or rehydrate it
+                        // if it's been evicted/the process just restarted)
+                        if let Some(instance) = self.instances.get(id).await {
+                            return Ok((instance, false));
                         }
-                        // Not in cache but marked active - inconsistent state, try to recover
-                        drop(instances);
                         return self.recover_single_instance(&stored).await.map(|i| (i, false));
                     }
                     InstanceState::Archived => {
                         // Restore from backup
-                        let instance = self.restore_instance(&stored).await?;
+                        let instance = self.restore_instance(&stored, restore_at).await?;
                         return Ok((instance, true));
                     }
                     InstanceState::Restoring => {
@@ -220,9 +395,10 @@ impl InstanceManager {
         let dialect = get_dialect(dialect_name)?;
         let id = specified_id.unwrap_or_else(Uuid::new_v4);
 
-        // Generate unique credentials for this instance
-        let db_name = format!("db_{}", id.simple());
-        let db_user = format!("user_{}", &id.simple().to_string()[..8]);
+        // Generate unique credentials for this instance, validated once here
+        // so every dialect DDL call downstream gets a provably safe identifier
+        let db_name = DatabaseName::new(&format!("db_{}", id.simple()))?;
+        let db_user = DatabaseUser::new(&format!("user_{}", &id.simple().to_string()[..8]))?;
         let db_password = generate_password();
 
         info!(
@@ -230,17 +406,25 @@ impl InstanceManager {
             dialect_name, id, db_name
         );
 
-        // Get or create pool container for this dialect
-        let pool = self.get_or_create_pool_container(dialect.as_ref()).await?;
+        // Get or create pool container for this dialect, reserving a
+        // capacity slot for this instance
+        let pool = self.select_pool_container(dialect.as_ref(), id).await?;
 
         // Create database inside the pool container
         let create_db_sql = dialect.create_database_sql(&db_name);
         let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_db_sql);
 
         debug!("Creating database {} in pool container", db_name);
-        let output = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await?;
+        let output = match self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
+            Ok(output) => output,
+            Err(e) => {
+                self.release_capacity(&pool.container_id, id).await;
+                return Err(e);
+            }
+        };
 
         if output.exit_code != Some(0) {
+            self.release_capacity(&pool.container_id, id).await;
             warn!(
                 "Failed to create database {}: {}",
                 db_name, output.stderr
@@ -256,13 +440,23 @@ impl InstanceManager {
         let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_user_sql);
 
         debug!("Creating user {} for database {}", db_user, db_name);
-        let output = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await?;
+        let output = match self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
+            Ok(output) => output,
+            Err(e) => {
+                let drop_db_sql = dialect.drop_database_sql(&db_name);
+                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+                let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+                self.release_capacity(&pool.container_id, id).await;
+                return Err(e);
+            }
+        };
 
         if output.exit_code != Some(0) {
             // Cleanup: drop the database we just created
             let drop_db_sql = dialect.drop_database_sql(&db_name);
             let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
             let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+            self.release_capacity(&pool.container_id, id).await;
 
             warn!(
                 "Failed to create user {}: {}",
@@ -279,8 +473,8 @@ impl InstanceManager {
             dialect_name.to_string(),
             pool.container_id.clone(),
             pool.host_port,
-            db_name.clone(),
-            db_user.clone(),
+            db_name.to_string(),
+            db_user.to_string(),
             db_password.clone(),
         );
         instance.status = InstanceStatus::Running;
@@ -292,8 +486,8 @@ impl InstanceManager {
         let stored = StoredInstance {
             db_id: id,
             dialect: dialect_name.to_string(),
-            db_name: db_name.clone(),
-            db_user: db_user.clone(),
+            db_name: db_name.to_string(),
+            db_user: db_user.to_string(),
             db_password: db_password.clone(),
             status: InstanceState::Active,
             container_id: Some(pool.container_id.clone()),
@@ -303,18 +497,138 @@ impl InstanceManager {
             archived_at: None,
             backup_key: None,
             backup_size_bytes: None,
+            schema_version: 0,
+            lease_expires_at: if self.config.lease_ttl_secs > 0 {
+                Some(now + chrono::Duration::seconds(self.config.lease_ttl_secs as i64))
+            } else {
+                None
+            },
         };
-        self.metadata.insert_instance(&stored)?;
+        self.metadata.insert_instance(&stored).await?;
+
+        // Apply any migrations configured for this dialect. A non-zero exit
+        // from any migration is treated the same as a failed user creation:
+        // tear down the half-built database and fail the request.
+        if let Some(migrations) = self.config.migrations.get(dialect_name) {
+            match self
+                .run_migrations(
+                    &pool.container_id,
+                    dialect.as_ref(),
+                    db_name.as_str(),
+                    db_user.as_str(),
+                    &db_password,
+                    migrations,
+                )
+                .await
+            {
+                Ok(_) => {
+                    self.metadata
+                        .update_schema_version(id, migrations.len() as u32)
+                        .await?;
+                }
+                Err(e) => {
+                    let drop_user_sql = dialect.drop_user_sql(&db_user);
+                    let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
+                    let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+                    let drop_db_sql = dialect.drop_database_sql(&db_name);
+                    let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+                    let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+                    self.release_capacity(&pool.container_id, id).await;
+                    let _ = self.metadata.delete_instance(id).await;
+                    return Err(e);
+                }
+            }
+        }
 
         // Store in cache (fast access)
-        {
-            let mut instances = self.instances.write().await;
-            instances.insert(id, instance.clone());
-        }
+        self.instances.insert(id, instance.clone()).await;
 
         Ok(instance)
     }
 
+    /// Run every migration in `migrations` not yet recorded in the
+    /// database's own `schema_migrations` ledger (created on first call),
+    /// in version order, each inside its own transaction. Stops and returns
+    /// an error on the first failure, leaving every migration before it
+    /// applied and committed. Returns the versions newly applied.
+    ///
+    /// Runs each migration as a single direct `docker.exec`, not via
+    /// `QueryExecutor::execute` - that method deliberately opens a fresh
+    /// CLI invocation per statement, which would apply the migration and
+    /// its ledger insert as two separate, non-atomic sessions instead of
+    /// one transaction.
+    async fn run_migrations(
+        &self,
+        container_id: &str,
+        dialect: &dyn Dialect,
+        db_name: &str,
+        db_user: &str,
+        db_password: &str,
+        migrations: &[MigrationFile],
+    ) -> Result<Vec<u32>> {
+        let env = dialect.cli_env_vars(db_name, db_user, db_password);
+
+        let (cmd, args) = dialect.cli_command(db_name, db_user, db_password, &dialect.create_schema_migrations_table_sql());
+        let output = self.docker.exec(container_id, &cmd, &args, &env).await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Internal(format!(
+                "Failed to create schema_migrations table: {}",
+                output.stderr
+            )));
+        }
+
+        let (cmd, args) = dialect.cli_command_csv(
+            db_name,
+            db_user,
+            db_password,
+            &dialect.select_applied_migration_versions_sql(),
+        );
+        let output = self.docker.exec(container_id, &cmd, &args, &env).await?;
+        if output.exit_code != Some(0) {
+            return Err(AppError::Internal(format!(
+                "Failed to read schema_migrations table: {}",
+                output.stderr
+            )));
+        }
+        let applied = parse_version_column(&output.stdout, dialect.csv_delimiter());
+
+        let mut newly_applied = Vec::new();
+
+        for migration in migrations.iter().filter(|m| !applied.contains(&m.version)) {
+            debug!(
+                "Applying migration {} ({}) to database {}",
+                migration.version, migration.name, db_name
+            );
+
+            let applied_at = Utc::now().to_rfc3339();
+            let script = format!(
+                "{} {} {} {}",
+                dialect.begin_transaction_sql(),
+                migration.sql,
+                dialect.record_migration_sql(migration.version, &migration.name, &applied_at),
+                dialect.commit_transaction_sql(),
+            );
+
+            let (cmd, args) = dialect.cli_command(db_name, db_user, db_password, &script);
+            let output = self.docker.exec(container_id, &cmd, &args, &env).await?;
+
+            if output.exit_code != Some(0) {
+                warn!(
+                    "Migration {} failed for database {}: {}",
+                    migration.name, db_name, output.stderr
+                );
+                return Err(AppError::Internal(format!(
+                    "Migration {} failed: {}",
+                    migration.name, output.stderr
+                )));
+            }
+
+            newly_applied.push(migration.version);
+        }
+
+        Ok(newly_applied)
+    }
+
     async fn wait_for_db_ready(
         &self,
         container_id: &str,
@@ -370,15 +684,12 @@ impl InstanceManager {
 
     pub async fn get_instance(&self, id: Uuid) -> Result<DbInstance> {
         // First check cache
-        {
-            let instances = self.instances.read().await;
-            if let Some(instance) = instances.get(&id) {
-                return Ok(instance.clone());
-            }
+        if let Some(instance) = self.instances.get(id).await {
+            return Ok(instance);
         }
 
         // Check metadata - might be archived
-        if let Some(stored) = self.metadata.get_instance(id)? {
+        if let Some(stored) = self.metadata.get_instance(id).await? {
             match stored.status {
                 InstanceState::Active => {
                     // Should be in cache but isn't - try to recover
@@ -398,25 +709,40 @@ impl InstanceManager {
         Err(AppError::DbNotFound)
     }
 
+    /// Resource usage for the container currently hosting `id`, via
+    /// `DockerManager::stats`. Instances share a pool container with other
+    /// databases of the same dialect, so this reflects the whole
+    /// container's usage, not `id` in isolation - the same granularity
+    /// `check_memory_pressure` already acts on.
+    pub async fn instance_stats(&self, id: Uuid) -> Result<ContainerStats> {
+        let instance = self.get_instance(id).await?;
+        self.docker.stats(&instance.container_id).await
+    }
+
     /// Get stored instance metadata (includes archived instances)
-    pub fn get_stored_instance(&self, id: Uuid) -> Result<Option<StoredInstance>> {
-        self.metadata.get_instance(id)
+    pub async fn get_stored_instance(&self, id: Uuid) -> Result<Option<StoredInstance>> {
+        self.metadata.get_instance(id).await
+    }
+
+    /// Full lifecycle history for an instance (created, recovered, archived,
+    /// destroyed, ...), newest first, optionally capped at `limit` rows
+    pub async fn instance_history(
+        &self,
+        id: Uuid,
+        limit: Option<u32>,
+    ) -> Result<Vec<crate::storage::InstanceEvent>> {
+        self.metadata.list_instance_events(id, limit).await
     }
 
     pub async fn touch_instance(&self, id: Uuid) -> Result<()> {
         // Update cache
-        {
-            let mut instances = self.instances.write().await;
-            if let Some(instance) = instances.get_mut(&id) {
-                instance.touch();
-            }
-        }
+        self.instances.mutate(id, |instance| instance.touch()).await;
         // Update metadata
-        self.metadata.touch_activity(id)?;
+        self.metadata.touch_activity(id).await?;
         Ok(())
     }
 
-    /// Archive an instance: dump database, upload to R2, drop database from pool
+    /// Archive an instance: dump database, upload to the backup store, drop database from pool
     pub async fn archive_instance(&self, id: Uuid) -> Result<()> {
         let backup = match &self.backup {
             Some(b) => b,
@@ -428,7 +754,8 @@ impl InstanceManager {
 
         let stored = self
             .metadata
-            .get_instance(id)?
+            .get_instance(id)
+            .await?
             .ok_or(AppError::DbNotFound)?;
 
         let dialect = get_dialect(&stored.dialect)?;
@@ -442,58 +769,61 @@ impl InstanceManager {
             return self.destroy_instance(id).await;
         }
 
-        // Get pool container for this dialect
+        // Get the pool container this instance actually lives in
+        let container_id = stored
+            .container_id
+            .clone()
+            .ok_or_else(|| AppError::Internal("Instance has no pool container assigned".to_string()))?;
         let pool = self
             .metadata
-            .get_pool_container(&stored.dialect)?
-            .ok_or_else(|| AppError::Internal("No pool container for dialect".to_string()))?;
+            .get_pool_container(&container_id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Pool container for instance not found".to_string()))?;
 
         info!("Archiving instance {} (dialect: {})", id, stored.dialect);
 
-        // 1. Dump database (using user credentials)
+        // 1. Dump database (using user credentials), streaming stdout
+        // straight into the backup store upload so we never hold the full
+        // dump in memory.
         let (cmd, args) = dialect.dump_command(&stored.db_name, &stored.db_user, &stored.db_password);
         let env = dialect.cli_env_vars(&stored.db_name, &stored.db_user, &stored.db_password);
 
-        let output = self.docker.exec(&pool.container_id, &cmd, &args, &env).await?;
-
-        if output.exit_code != Some(0) {
-            warn!(
-                "Database dump failed for {}: {}",
-                id, output.stderr
-            );
-            // Still drop the database even if dump fails
-            let _ = self.destroy_instance(id).await;
-            return Err(AppError::BackupFailed(format!(
-                "Dump failed: {}",
-                output.stderr
-            )));
-        }
-
-        // 2. Upload to R2 (compression is handled by BackupManager)
-        let (key, size) = backup
-            .upload_backup(id, output.stdout.as_bytes())
+        let dump_stream = self
+            .docker
+            .exec_stdout_stream(&pool.container_id, &cmd, &args, &env)
             .await?;
 
+        // 2. Upload to the backup store while the dump is still streaming
+        // (compression is handled by the BackupStore implementation)
+        let (key, size) = match backup.upload_backup_stream(id, Box::pin(dump_stream)).await {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("Database dump failed for {}: {}", id, e);
+                // Still drop the database even if dump/upload fails
+                let _ = self.destroy_instance(id).await;
+                return Err(AppError::BackupFailed(format!("Dump failed: {}", e)));
+            }
+        };
+
         info!(
             "Uploaded backup for {} to {} ({} bytes)",
             id, key, size
         );
 
         // 3. Update metadata
-        self.metadata.mark_archived(id, &key, size)?;
+        self.metadata.mark_archived(id, &key, size).await?;
 
-        // 4. Remove from cache
-        {
-            let mut instances = self.instances.write().await;
-            instances.remove(&id);
-        }
+        // 4. Remove from cache and release the capacity slot
+        self.instances.remove(id).await;
+        self.instance_health.forget(id).await;
+        self.release_capacity(&pool.container_id, id).await;
 
         // 5. Drop user and database from pool (not destroy container)
-        let drop_user_sql = dialect.drop_user_sql(&stored.db_user);
+        let drop_user_sql = dialect.drop_user_sql(&DatabaseUser::new(&stored.db_user)?);
         let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
         let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
 
-        let drop_db_sql = dialect.drop_database_sql(&stored.db_name);
+        let drop_db_sql = dialect.drop_database_sql(&DatabaseName::new(&stored.db_name)?);
         let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
         let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
 
@@ -502,113 +832,292 @@ impl InstanceManager {
         Ok(())
     }
 
-    /// Restore an instance from backup
-    async fn restore_instance(&self, stored: &StoredInstance) -> Result<DbInstance> {
-        let backup = self
-            .backup
-            .as_ref()
-            .ok_or_else(|| AppError::RestoreFailed("Backup not configured".to_string()))?;
-
-        let backup_key = stored
-            .backup_key
-            .as_ref()
-            .ok_or(AppError::BackupNotFound)?;
-
-        info!("Restoring instance {} from {}", stored.db_id, backup_key);
-
-        // 1. Mark as restoring
-        self.metadata
-            .update_status(stored.db_id, InstanceState::Restoring)?;
+    /// Background task: periodically snapshots every still-active instance
+    /// to the backup store and prunes old snapshots according to the configured retention
+    /// policy. No-op when snapshots or backups aren't configured.
+    pub fn start_snapshot_task(self: Arc<Self>) {
+        if self.backup.is_none() || self.config.snapshot_interval_secs == 0 {
+            info!("Periodic snapshots disabled (no backup configured or interval is 0)");
+            return;
+        }
 
-        let dialect = get_dialect(&stored.dialect)?;
+        let manager = self.clone();
+        let check_interval = Duration::from_secs(self.config.snapshot_interval_secs);
 
-        // 2. Get or create pool container (fast if already exists)
-        let pool = self.get_or_create_pool_container(dialect.as_ref()).await
-            .map_err(|e| {
-                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-                e
-            })?;
+        tokio::spawn(async move {
+            let mut ticker = interval(check_interval);
 
-        // 3. Create database in pool container
-        let create_db_sql = dialect.create_database_sql(&stored.db_name);
-        let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_db_sql);
+            loop {
+                ticker.tick().await;
+                manager.snapshot_active_instances().await;
+            }
+        });
+    }
 
-        let output = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await
-            .map_err(|e| {
-                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-                AppError::RestoreFailed(format!("Failed to create database: {}", e))
-            })?;
+    async fn snapshot_active_instances(&self) {
+        let ids: Vec<Uuid> = match self.metadata.list_active_instances().await {
+            Ok(instances) => instances.into_iter().map(|s| s.db_id).collect(),
+            Err(e) => {
+                warn!("Failed to list active instances for snapshotting: {}", e);
+                return;
+            }
+        };
 
-        if output.exit_code != Some(0) {
-            let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-            return Err(AppError::RestoreFailed(format!(
-                "Failed to create database: {}",
-                output.stderr
-            )));
+        for id in ids {
+            if let Err(e) = self.snapshot_instance(id).await {
+                warn!("Failed to snapshot instance {}: {}", id, e);
+            }
         }
+    }
 
-        // 4. Create user with permissions
-        let create_user_sql = dialect.create_user_sql(&stored.db_user, &stored.db_password, &stored.db_name);
-        let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_user_sql);
+    /// Dump a still-active instance to the backup store as a versioned snapshot (distinct
+    /// from the single archive-time backup), then enforce the configured
+    /// retention policy for that instance.
+    pub async fn snapshot_instance(&self, id: Uuid) -> Result<StoredSnapshot> {
+        let backup = self
+            .backup
+            .as_ref()
+            .ok_or_else(|| AppError::BackupFailed("Backup not configured".to_string()))?;
 
-        let output = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await
-            .map_err(|e| {
-                // Cleanup: drop the database
-                let drop_db_sql = dialect.drop_database_sql(&stored.db_name);
-                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
-                let _ = futures::executor::block_on(self.docker.exec(&pool.container_id, &cmd, &args, &[]));
-                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-                AppError::RestoreFailed(format!("Failed to create user: {}", e))
-            })?;
+        let stored = self
+            .metadata
+            .get_instance(id)
+            .await?
+            .ok_or(AppError::DbNotFound)?;
 
-        if output.exit_code != Some(0) {
-            // Cleanup: drop the database
-            let drop_db_sql = dialect.drop_database_sql(&stored.db_name);
-            let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
-            let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
-            let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-            return Err(AppError::RestoreFailed(format!(
-                "Failed to create user: {}",
-                output.stderr
+        let dialect = get_dialect(&stored.dialect)?;
+        if !dialect.supports_backup() {
+            return Err(AppError::BackupFailed(format!(
+                "Dialect {} does not support backup",
+                stored.dialect
             )));
         }
 
-        // 5. Download and restore backup
-        let sql_data = backup.download_backup(backup_key).await?;
+        let container_id = stored
+            .container_id
+            .clone()
+            .ok_or_else(|| AppError::BackupFailed("Instance has no pool container assigned".to_string()))?;
+        let pool = self
+            .metadata
+            .get_pool_container(&container_id)
+            .await?
+            .ok_or_else(|| AppError::Internal("Pool container for instance not found".to_string()))?;
 
-        let (cmd, args) =
-            dialect.restore_command(&stored.db_name, &stored.db_user, &stored.db_password);
+        debug!("Snapshotting instance {} (dialect: {})", id, stored.dialect);
+
+        let (cmd, args) = dialect.dump_command(&stored.db_name, &stored.db_user, &stored.db_password);
         let env = dialect.cli_env_vars(&stored.db_name, &stored.db_user, &stored.db_password);
 
-        let output = self
+        let dump_stream = self
             .docker
-            .exec_with_stdin(&pool.container_id, &cmd, &args, &env, &sql_data)
-            .await
-            .map_err(|e| {
-                // Cleanup: drop user and database
-                let drop_user_sql = dialect.drop_user_sql(&stored.db_user);
-                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
-                let _ = futures::executor::block_on(self.docker.exec(&pool.container_id, &cmd, &args, &[]));
-                let drop_db_sql = dialect.drop_database_sql(&stored.db_name);
-                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
-                let _ = futures::executor::block_on(self.docker.exec(&pool.container_id, &cmd, &args, &[]));
-                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-                AppError::RestoreFailed(format!("Restore exec failed: {}", e))
-            })?;
+            .exec_stdout_stream(&pool.container_id, &cmd, &args, &env)
+            .await?;
 
-        if output.exit_code != Some(0) {
-            warn!(
+        let (key, size) = backup.upload_backup_stream(id, Box::pin(dump_stream)).await?;
+
+        let snapshot = StoredSnapshot {
+            id: Uuid::new_v4(),
+            db_id: id,
+            backup_key: key.clone(),
+            size_bytes: size,
+            created_at: Utc::now(),
+        };
+        self.metadata.insert_snapshot(&snapshot).await?;
+
+        info!(
+            "Snapshotted instance {} to {} ({} bytes)",
+            id, key, size
+        );
+
+        self.prune_snapshots(id).await;
+
+        Ok(snapshot)
+    }
+
+    /// Apply the configured retention policy to an instance's snapshots,
+    /// deleting both the metadata rows and the backing backup-store objects for
+    /// anything pruned.
+    async fn prune_snapshots(&self, db_id: Uuid) {
+        let snapshots = match self.metadata.list_snapshots(db_id).await {
+            Ok(s) => s,
+            Err(e) => {
+                warn!("Failed to list snapshots for {}: {}", db_id, e);
+                return;
+            }
+        };
+
+        let mut to_prune = Vec::new();
+
+        if self.config.snapshot_retention_count > 0 {
+            let keep = self.config.snapshot_retention_count as usize;
+            to_prune.extend(snapshots.iter().skip(keep).cloned());
+        }
+
+        if self.config.snapshot_retention_days > 0 {
+            let cutoff = Utc::now() - chrono::Duration::days(self.config.snapshot_retention_days as i64);
+            for snapshot in &snapshots {
+                if snapshot.created_at < cutoff && !to_prune.iter().any(|s| s.id == snapshot.id) {
+                    to_prune.push(snapshot.clone());
+                }
+            }
+        }
+
+        for snapshot in to_prune {
+            if let Some(backup) = &self.backup {
+                if let Err(e) = backup.delete_backup(&snapshot.backup_key).await {
+                    warn!("Failed to delete pruned snapshot object {}: {}", snapshot.backup_key, e);
+                    continue;
+                }
+            }
+            if let Err(e) = self.metadata.delete_snapshot(snapshot.id).await {
+                warn!("Failed to delete pruned snapshot row {}: {}", snapshot.id, e);
+            }
+        }
+    }
+
+    /// Restore an instance from backup. When `restore_at` is given, restores
+    /// the most recent periodic snapshot at or before that time instead of
+    /// the latest archive-time backup.
+    async fn restore_instance(
+        &self,
+        stored: &StoredInstance,
+        restore_at: Option<DateTime<Utc>>,
+    ) -> Result<DbInstance> {
+        let backup = self
+            .backup
+            .as_ref()
+            .ok_or_else(|| AppError::RestoreFailed("Backup not configured".to_string()))?;
+
+        let backup_key = match restore_at {
+            Some(at) => self
+                .metadata
+                .get_snapshot_at_or_before(stored.db_id, at)
+                .await?
+                .map(|s| s.backup_key)
+                .ok_or(AppError::BackupNotFound)?,
+            None => stored
+                .backup_key
+                .clone()
+                .ok_or(AppError::BackupNotFound)?,
+        };
+        let backup_key = &backup_key;
+
+        info!("Restoring instance {} from {}", stored.db_id, backup_key);
+
+        // 1. Mark as restoring
+        self.metadata
+            .update_status(stored.db_id, InstanceState::Restoring)
+            .await?;
+
+        let dialect = get_dialect(&stored.dialect)?;
+        let db_name = DatabaseName::new(&stored.db_name)?;
+        let db_user = DatabaseUser::new(&stored.db_user)?;
+
+        // 2. Get or create pool container (fast if already exists), reserving
+        // a capacity slot for this instance
+        let pool = match self.select_pool_container(dialect.as_ref(), stored.db_id).await {
+            Ok(pool) => pool,
+            Err(e) => {
+                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
+                return Err(e);
+            }
+        };
+
+        // 3. Create database in pool container
+        let create_db_sql = dialect.create_database_sql(&db_name);
+        let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_db_sql);
+
+        let output = match self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
+            Ok(output) => output,
+            Err(e) => {
+                self.release_capacity(&pool.container_id, stored.db_id).await;
+                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
+                return Err(AppError::RestoreFailed(format!("Failed to create database: {}", e)));
+            }
+        };
+
+        if output.exit_code != Some(0) {
+            self.release_capacity(&pool.container_id, stored.db_id).await;
+            let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
+            return Err(AppError::RestoreFailed(format!(
+                "Failed to create database: {}",
+                output.stderr
+            )));
+        }
+
+        // 4. Create user with permissions
+        let create_user_sql = dialect.create_user_sql(&db_user, &stored.db_password, &db_name);
+        let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_user_sql);
+
+        let output = match self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
+            Ok(output) => output,
+            Err(e) => {
+                // Cleanup: drop the database
+                let drop_db_sql = dialect.drop_database_sql(&db_name);
+                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+                let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+                self.release_capacity(&pool.container_id, stored.db_id).await;
+                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
+                return Err(AppError::RestoreFailed(format!("Failed to create user: {}", e)));
+            }
+        };
+
+        if output.exit_code != Some(0) {
+            // Cleanup: drop the database
+            let drop_db_sql = dialect.drop_database_sql(&db_name);
+            let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+            let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+            self.release_capacity(&pool.container_id, stored.db_id).await;
+            let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
+            return Err(AppError::RestoreFailed(format!(
+                "Failed to create user: {}",
+                output.stderr
+            )));
+        }
+
+        // 5. Stream the backup straight from the backup store into the
+        // restore command's stdin so memory use stays bounded regardless of
+        // database size.
+        let backup_stream = backup.download_backup_stream(backup_key).await?;
+
+        let (cmd, args) =
+            dialect.restore_command(&stored.db_name, &stored.db_user, &stored.db_password);
+        let env = dialect.cli_env_vars(&stored.db_name, &stored.db_user, &stored.db_password);
+
+        let output = match self
+            .docker
+            .exec_with_stdin_stream(&pool.container_id, &cmd, &args, &env, backup_stream)
+            .await
+        {
+            Ok(output) => output,
+            Err(e) => {
+                // Cleanup: drop user and database
+                let drop_user_sql = dialect.drop_user_sql(&db_user);
+                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
+                let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+                let drop_db_sql = dialect.drop_database_sql(&db_name);
+                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+                let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+                self.release_capacity(&pool.container_id, stored.db_id).await;
+                let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
+                return Err(AppError::RestoreFailed(format!("Restore exec failed: {}", e)));
+            }
+        };
+
+        if output.exit_code != Some(0) {
+            warn!(
                 "Database restore failed for {}: {}",
                 stored.db_id, output.stderr
             );
             // Cleanup: drop user and database
-            let drop_user_sql = dialect.drop_user_sql(&stored.db_user);
+            let drop_user_sql = dialect.drop_user_sql(&db_user);
             let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
             let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
-            let drop_db_sql = dialect.drop_database_sql(&stored.db_name);
+            let drop_db_sql = dialect.drop_database_sql(&db_name);
             let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
             let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
-            let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
+            self.release_capacity(&pool.container_id, stored.db_id).await;
+            let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived).await;
             return Err(AppError::RestoreFailed(format!(
                 "Restore failed: {}",
                 output.stderr
@@ -617,7 +1126,39 @@ impl InstanceManager {
 
         // 6. Update metadata
         self.metadata
-            .mark_active(stored.db_id, &pool.container_id, pool.host_port)?;
+            .mark_active(stored.db_id, &pool.container_id, pool.host_port)
+            .await?;
+
+        // 6b. Bring the restored schema up to date. `run_migrations` reads
+        // the restored database's own `schema_migrations` ledger to find
+        // what's still pending, so the full configured set is passed
+        // unfiltered. Unlike creation, a migration failure here doesn't fail
+        // the restore (the instance is already usable) — it's logged and
+        // left for a future retry.
+        if let Some(migrations) = self.config.migrations.get(&stored.dialect) {
+            match self
+                .run_migrations(
+                    &pool.container_id,
+                    dialect.as_ref(),
+                    &stored.db_name,
+                    &stored.db_user,
+                    &stored.db_password,
+                    migrations,
+                )
+                .await
+            {
+                Ok(_) => {
+                    let version = migrations.iter().map(|m| m.version).max().unwrap_or(0);
+                    self.metadata.update_schema_version(stored.db_id, version).await?;
+                }
+                Err(e) => {
+                    warn!(
+                        "Schema migration after restore failed for {}: {}",
+                        stored.db_id, e
+                    );
+                }
+            }
+        }
 
         // 7. Create instance and add to cache
         let mut instance = DbInstance::new(
@@ -630,11 +1171,7 @@ impl InstanceManager {
             stored.db_password.clone(),
         );
         instance.status = InstanceStatus::Running;
-
-        {
-            let mut instances = self.instances.write().await;
-            instances.insert(stored.db_id, instance.clone());
-        }
+        self.instances.insert(stored.db_id, instance.clone()).await;
 
         info!("Instance {} restored successfully", stored.db_id);
 
@@ -652,52 +1189,65 @@ impl InstanceManager {
             stored.db_user.clone(),
             stored.db_password.clone(),
         );
+        self.instances.insert(stored.db_id, instance.clone()).await;
 
-        let mut instances = self.instances.write().await;
-        instances.insert(stored.db_id, instance.clone());
+        // The row itself isn't touched on recovery, so the instances
+        // triggers never fire — log it explicitly for the audit trail.
+        let status = stored.status.as_str();
+        let _ = self
+            .metadata
+            .record_event(stored.db_id, &stored.dialect, Some(stored.status), status, "recovered")
+            .await;
 
         Ok(instance)
     }
 
     pub async fn destroy_instance(&self, id: Uuid) -> Result<()> {
         // Get instance info from cache or metadata
-        let stored = self.metadata.get_instance(id)?.ok_or(AppError::DbNotFound)?;
+        let stored = self.metadata.get_instance(id).await?.ok_or(AppError::DbNotFound)?;
 
         // Remove from cache
-        {
-            let mut instances = self.instances.write().await;
-            instances.remove(&id);
-        }
+        self.instances.remove(id).await;
+        self.instance_health.forget(id).await;
 
-        // Get pool container for this dialect
+        // Get the pool container this instance actually lives in
         let dialect = get_dialect(&stored.dialect)?;
-        if let Some(pool) = self.metadata.get_pool_container(&stored.dialect)? {
-            // Drop user first
-            let drop_user_sql = dialect.drop_user_sql(&stored.db_user);
-            let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
+        if let Some(container_id) = &stored.container_id {
+            if let Some(pool) = self.metadata.get_pool_container(container_id).await? {
+                // Drop user first
+                let drop_user_sql = dialect.drop_user_sql(&DatabaseUser::new(&stored.db_user)?);
+                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_user_sql);
 
-            debug!("Dropping user {} for instance {}", stored.db_user, id);
-            if let Err(e) = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
-                warn!("Failed to drop user {}: {}", stored.db_user, e);
-            }
+                debug!("Dropping user {} for instance {}", stored.db_user, id);
+                if let Err(e) = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
+                    warn!("Failed to drop user {}: {}", stored.db_user, e);
+                }
 
-            // Drop database
-            let drop_db_sql = dialect.drop_database_sql(&stored.db_name);
-            let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+                // Drop database
+                let drop_db_sql = dialect.drop_database_sql(&DatabaseName::new(&stored.db_name)?);
+                let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+
+                debug!("Dropping database {} for instance {}", stored.db_name, id);
+                if let Err(e) = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
+                    warn!("Failed to drop database {}: {}", stored.db_name, e);
+                }
 
-            debug!("Dropping database {} for instance {}", stored.db_name, id);
-            if let Err(e) = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await {
-                warn!("Failed to drop database {}: {}", stored.db_name, e);
+                info!("Instance {} destroyed (database dropped)", id);
+            } else {
+                // Pool container vanished from metadata - this might be a
+                // legacy instance or the pool died
+                warn!("Pool container {} not found, can't drop database for instance {}", container_id, id);
             }
 
-            info!("Instance {} destroyed (database dropped)", id);
+            // Always release the capacity slot, whether or not the pool
+            // container record was still around to drop the database from
+            self.release_capacity(container_id, id).await;
         } else {
-            // No pool container found - this might be a legacy instance or pool died
-            warn!("No pool container found for dialect {}, can't drop database", stored.dialect);
+            warn!("Instance {} has no pool container assigned, nothing to drop", id);
         }
 
         // Remove from metadata
-        self.metadata.delete_instance(id)?;
+        self.metadata.delete_instance(id).await?;
 
         Ok(())
     }
@@ -712,13 +1262,545 @@ impl InstanceManager {
             loop {
                 ticker.tick().await;
                 manager.cleanup_inactive(timeout).await;
+                manager.cleanup_lease_expired().await;
             }
         });
     }
 
+    /// Background monitor: periodically health-checks every known pool
+    /// container with an actual SQL liveness probe (not just
+    /// `docker.is_running`) and ejects any that fails
+    /// `pool_eject_after_failures` consecutive checks - the threshold
+    /// itself is what keeps a briefly-restarting container from triggering
+    /// reconciliation. See `eject_pool_container` for what happens to the
+    /// instances it was hosting.
+    pub fn start_pool_monitor_task(self: Arc<Self>) {
+        let manager = self.clone();
+        let check_interval = Duration::from_secs(self.config.pool_health_check_interval_secs);
+        let eject_after = self.config.pool_eject_after_failures;
+
+        tokio::spawn(async move {
+            let mut ticker = interval(check_interval);
+
+            loop {
+                ticker.tick().await;
+                manager.check_pool_health(eject_after).await;
+            }
+        });
+    }
+
+    async fn check_pool_health(&self, eject_after: u32) {
+        let containers: Vec<PoolContainer> = {
+            let pools = self.pools.read().await;
+            pools.values().flatten().cloned().collect()
+        };
+
+        for pool in containers {
+            let dialect = match get_dialect(&pool.dialect) {
+                Ok(d) => d,
+                Err(e) => {
+                    warn!("Unknown dialect {} for pool health check: {}", pool.dialect, e);
+                    continue;
+                }
+            };
+
+            let (cmd, args) = dialect.exec_sql_command(&pool.root_password, "SELECT 1");
+            let passed = self
+                .docker
+                .exec(&pool.container_id, &cmd, &args, &[])
+                .await
+                .map(|output| output.exit_code == Some(0))
+                .unwrap_or(false);
+
+            if passed {
+                self.pool_health
+                    .record_success(&pool.container_id, &pool.dialect)
+                    .await;
+                continue;
+            }
+
+            let dead = self
+                .pool_health
+                .record_failure(&pool.container_id, &pool.dialect, eject_after)
+                .await;
+
+            if dead {
+                warn!(
+                    "Pool container {} for {} failed {} consecutive health checks, ejecting",
+                    pool.container_id, pool.dialect, eject_after
+                );
+                self.eject_pool_container(&pool).await;
+            }
+        }
+    }
+
+    /// Background watchdog: periodically runs each running instance's
+    /// dialect-specific `health_check_command` against the pool container it
+    /// lives on, with a per-check timeout. On reaching
+    /// `instance_health_failure_threshold` consecutive failures the instance
+    /// is marked `InstanceStatus::Unhealthy` and a bounded number of
+    /// restarts (rehydrating it from metadata, in case its cached
+    /// container/port drifted) are attempted; once
+    /// `instance_health_max_restarts` is exhausted the instance is archived
+    /// (or destroyed, if no backup store is configured). A no-op when
+    /// `instance_health_check_interval_secs` is 0.
+    pub fn start_instance_health_task(self: Arc<Self>) {
+        let interval_secs = self.config.instance_health_check_interval_secs;
+        if interval_secs == 0 {
+            info!("Instance health watchdog disabled (check interval is 0)");
+            return;
+        }
+
+        let manager = self.clone();
+        let check_interval = Duration::from_secs(interval_secs);
+        let check_timeout = Duration::from_secs(self.config.instance_health_check_timeout_secs);
+        let fail_threshold = self.config.instance_health_failure_threshold;
+        let max_restarts = self.config.instance_health_max_restarts;
+
+        tokio::spawn(async move {
+            let mut ticker = interval(check_interval);
+
+            loop {
+                ticker.tick().await;
+                manager
+                    .check_instance_health(fail_threshold, max_restarts, check_timeout)
+                    .await;
+            }
+        });
+    }
+
+    async fn check_instance_health(&self, fail_threshold: u32, max_restarts: u32, check_timeout: Duration) {
+        let instances = self.instances.values().await;
+
+        for instance in instances {
+            // Recovered instances stay `Starting` in the cache (see
+            // `recover_single_instance`), so they're checked too
+            if !matches!(
+                instance.status,
+                InstanceStatus::Running | InstanceStatus::Starting | InstanceStatus::Unhealthy
+            ) {
+                continue;
+            }
+
+            let dialect = match get_dialect(&instance.dialect) {
+                Ok(d) => d,
+                Err(e) => {
+                    warn!("Unknown dialect {} for instance health check: {}", instance.dialect, e);
+                    continue;
+                }
+            };
+
+            let (cmd, args) =
+                dialect.health_check_command(&instance.db_name, &instance.db_user, &instance.db_password);
+            let env = dialect.cli_env_vars(&instance.db_name, &instance.db_user, &instance.db_password);
+
+            let passed = self
+                .docker
+                .exec_with_timeout(&instance.container_id, &cmd, &args, &env, check_timeout)
+                .await
+                .map(|output| output.exit_code == Some(0))
+                .unwrap_or(false);
+
+            if passed {
+                self.instance_health.record_success(instance.id).await;
+                if instance.status == InstanceStatus::Unhealthy {
+                    info!("Instance {} recovered, marking running again", instance.id);
+                    self.instances
+                        .mutate(instance.id, |i| i.status = InstanceStatus::Running)
+                        .await;
+                }
+                continue;
+            }
+
+            match self
+                .instance_health
+                .record_failure(instance.id, fail_threshold, max_restarts)
+                .await
+            {
+                FailureOutcome::BelowThreshold => {}
+                FailureOutcome::Restart(attempt) => {
+                    warn!(
+                        "Instance {} failed {} consecutive health checks, attempting restart {}/{}",
+                        instance.id, fail_threshold, attempt, max_restarts
+                    );
+                    match self.metadata.get_instance(instance.id).await {
+                        Ok(Some(stored)) => {
+                            if let Err(e) = self.recover_single_instance(&stored).await {
+                                warn!("Failed to restart instance {}: {}", instance.id, e);
+                            }
+                        }
+                        Ok(None) => warn!("Instance {} vanished from metadata mid-restart", instance.id),
+                        Err(e) => warn!("Failed to load instance {} for restart: {}", instance.id, e),
+                    }
+                    // Marked after the restart, which re-inserts the instance
+                    // as `Starting`
+                    self.instances
+                        .mutate(instance.id, |i| i.status = InstanceStatus::Unhealthy)
+                        .await;
+                }
+                FailureOutcome::Exhausted => {
+                    warn!(
+                        "Instance {} exhausted {} restart attempts, archiving",
+                        instance.id, max_restarts
+                    );
+                    self.instances
+                        .mutate(instance.id, |i| i.status = InstanceStatus::Unhealthy)
+                        .await;
+                    self.instance_health.forget(instance.id).await;
+                    if let Err(e) = self.archive_instance(instance.id).await {
+                        warn!("Failed to archive unhealthy instance {}: {}", instance.id, e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Latest watchdog-observed health state for an instance, if it's been
+    /// checked at least once since startup
+    pub async fn instance_health(&self, id: Uuid) -> Option<InstanceHealthEntry> {
+        self.instance_health.snapshot(id).await
+    }
+
+    /// Background monitor: periodically samples resource stats for every
+    /// db-api/pool container and, when their aggregate memory usage exceeds
+    /// `container_memory_budget_mb`, stops idle pool containers (those
+    /// hosting zero instances) starting with the least-recently-active,
+    /// until back under budget or there's nothing left to evict. If that's
+    /// still not enough, and `memory_pressure_idle_grace_secs` is non-zero,
+    /// falls back to archiving instances idle past that (shorter) grace
+    /// period - see `archive_idle_under_pressure` - so pressure is relieved
+    /// by actual resource consumption, not only by the full idle timeout. A
+    /// no-op when `stats_sample_interval_secs` or `container_memory_budget_mb`
+    /// is 0.
+    pub fn start_memory_pressure_task(self: Arc<Self>) {
+        let sample_interval = self.config.stats_sample_interval_secs;
+        if sample_interval == 0 {
+            return;
+        }
+
+        let manager = self.clone();
+        let budget_bytes = self.config.container_memory_budget_mb * 1024 * 1024;
+
+        let idle_grace = Duration::from_secs(self.config.memory_pressure_idle_grace_secs);
+
+        tokio::spawn(async move {
+            let mut ticker = interval(Duration::from_secs(sample_interval));
+
+            loop {
+                ticker.tick().await;
+                manager.check_memory_pressure(budget_bytes, idle_grace).await;
+            }
+        });
+    }
+
+    async fn check_memory_pressure(&self, budget_bytes: u64, idle_grace: Duration) {
+        let snapshot = match self.docker.stats_snapshot().await {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                warn!("Failed to sample container stats: {}", e);
+                return;
+            }
+        };
+
+        let total_bytes: u64 = snapshot
+            .iter()
+            .filter(|e| e.is_running)
+            .map(|e| e.stats.memory_usage_bytes)
+            .sum();
+
+        if budget_bytes == 0 || total_bytes <= budget_bytes {
+            return;
+        }
+
+        warn!(
+            "Container memory usage ({} MB) exceeds budget ({} MB), evicting idle pool containers",
+            total_bytes / (1024 * 1024),
+            budget_bytes / (1024 * 1024)
+        );
+
+        // Only the metadata store's `instance_count` is kept current as
+        // instances come and go, so idleness is judged from there
+        let idle_pool_ids: Vec<String> = match self.metadata.list_pool_containers().await {
+            Ok(pools) => pools
+                .into_iter()
+                .filter(|p| p.instance_count == 0)
+                .map(|p| p.container_id)
+                .collect(),
+            Err(e) => {
+                warn!("Failed to list pool containers for eviction: {}", e);
+                Vec::new()
+            }
+        };
+
+        let eviction_order = self.pool_activity.oldest_first(&idle_pool_ids).await;
+        let mut freed_bytes = 0u64;
+
+        for container_id in eviction_order {
+            if total_bytes.saturating_sub(freed_bytes) <= budget_bytes {
+                break;
+            }
+
+            let pool = {
+                let pools = self.pools.read().await;
+                pools
+                    .values()
+                    .flatten()
+                    .find(|p| p.container_id == container_id)
+                    .cloned()
+            };
+            let Some(pool) = pool else { continue };
+
+            let used = snapshot
+                .iter()
+                .find(|e| e.container_id == container_id)
+                .map(|e| e.stats.memory_usage_bytes)
+                .unwrap_or(0);
+
+            info!("Evicting idle pool container {} under memory pressure", container_id);
+            if self.evict_idle_pool_container(&pool).await {
+                freed_bytes += used;
+            }
+        }
+
+        // Out of idle pool containers to stop but still over budget: archive
+        // (not destroy) the longest-idle instance across all containers,
+        // ahead of its normal `inactivity_timeout`, instead of leaving memory
+        // pressure unaddressed until then. One instance per sample tick.
+        if idle_grace > Duration::ZERO && total_bytes.saturating_sub(freed_bytes) > budget_bytes {
+            self.archive_idle_under_pressure(idle_grace).await;
+        }
+    }
+
+    /// Archive the single longest-idle instance among those idle for at
+    /// least `grace` (shorter than the configured `inactivity_timeout`), as a fallback
+    /// eviction path for `check_memory_pressure` once idle pool containers
+    /// are exhausted. Uses `archive_instance`, the same safe (backup-then-
+    /// stop) path `cleanup_inactive` already uses for the normal idle sweep.
+    async fn archive_idle_under_pressure(&self, grace: Duration) {
+        let mut candidates = match self.metadata.get_expired_instances(grace).await {
+            Ok(instances) => instances,
+            Err(e) => {
+                warn!("Failed to list idle instances under memory pressure: {}", e);
+                return;
+            }
+        };
+
+        candidates.sort_by_key(|s| s.last_activity);
+
+        if let Some(stored) = candidates.first() {
+            info!(
+                "Archiving instance {} (idle {:?}) under sustained memory pressure",
+                stored.db_id, grace
+            );
+            if let Err(e) = self.archive_instance(stored.db_id).await {
+                warn!("Failed to archive instance {} under memory pressure: {}", stored.db_id, e);
+            }
+        }
+    }
+
+    /// Stop (not destroy) an idle pool container evicted under memory
+    /// pressure, dropping it from tracking so it's no longer a selection
+    /// candidate. Safe only for containers with zero hosted instances -
+    /// unlike `eject_pool_container`, there's nothing to reconcile.
+    ///
+    /// Stops the container *before* untracking it: a container is only
+    /// dropped from `pools`/`pool_health`/`pool_activity`/`pool_semaphores`
+    /// and metadata once `stop_container` actually succeeds. Untracking
+    /// first and stopping after, the way this used to work, left a
+    /// container that failed to stop running and completely forgotten -
+    /// not in any tracking structure, so nothing could ever retry or
+    /// reconcile it again. Returns whether the container was stopped (and
+    /// therefore untracked), so callers can account memory actually freed.
+    async fn evict_idle_pool_container(&self, pool: &PoolContainer) -> bool {
+        if let Err(e) = self.docker.stop_container(&pool.container_id).await {
+            warn!(
+                "Failed to stop idle pool container {} under memory pressure, leaving it tracked for retry: {}",
+                pool.container_id, e
+            );
+            return false;
+        }
+
+        {
+            let mut pools = self.pools.write().await;
+            if let Some(containers) = pools.get_mut(&pool.dialect) {
+                containers.retain(|c| c.container_id != pool.container_id);
+            }
+        }
+        self.pool_health.forget(&pool.container_id).await;
+        self.pool_activity.forget(&pool.container_id).await;
+        self.pool_semaphores.write().await.remove(&pool.container_id);
+
+        let _ = self.metadata.delete_pool_container(&pool.container_id).await;
+
+        true
+    }
+
+    /// Remove a dead pool container from tracking and destroy it, then
+    /// reconcile every instance it was hosting: instances with an existing
+    /// backup are archived against that backup rather than dumped from a
+    /// container that just failed its liveness check; instances with no
+    /// backup are flagged in the audit log and re-pointed at a freshly
+    /// provisioned (or already-healthy) pool container for the dialect,
+    /// since that's the only way to keep them reachable at all.
+    async fn eject_pool_container(&self, pool: &PoolContainer) {
+        {
+            let mut pools = self.pools.write().await;
+            if let Some(containers) = pools.get_mut(&pool.dialect) {
+                containers.retain(|c| c.container_id != pool.container_id);
+            }
+        }
+        self.pool_health.forget(&pool.container_id).await;
+        self.pool_activity.forget(&pool.container_id).await;
+        self.pool_semaphores.write().await.remove(&pool.container_id);
+
+        let _ = self.metadata.delete_pool_container(&pool.container_id).await;
+
+        // Sourced from metadata, not the (now size-bounded) cache, so an
+        // instance evicted from the cache still gets reconciled
+        let stranded: Vec<Uuid> = match self.metadata.list_active_instances().await {
+            Ok(instances) => instances
+                .into_iter()
+                .filter(|s| s.container_id.as_deref() == Some(pool.container_id.as_str()))
+                .map(|s| s.db_id)
+                .collect(),
+            Err(e) => {
+                warn!("Failed to list active instances while ejecting pool {}: {}", pool.container_id, e);
+                Vec::new()
+            }
+        };
+
+        for db_id in stranded {
+            warn!("Instance {} stranded by ejected pool container {}", db_id, pool.container_id);
+            let _ = self
+                .metadata
+                .record_event(db_id, &pool.dialect, Some(InstanceState::Active), "active", "pool-unhealthy")
+                .await;
+
+            let stored = match self.metadata.get_instance(db_id).await {
+                Ok(Some(stored)) => stored,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!("Failed to load stranded instance {}: {}", db_id, e);
+                    continue;
+                }
+            };
+
+            if stored.backup_key.is_some() {
+                // A backup already exists - archive against it rather than
+                // guess at a fresh, empty re-point. The operator can restore
+                // it explicitly once they've looked into the ejection.
+                if let Err(e) = self.metadata.update_status(db_id, InstanceState::Archived).await {
+                    warn!("Failed to archive stranded instance {}: {}", db_id, e);
+                }
+                self.instances.remove(db_id).await;
+                self.release_capacity(&pool.container_id, db_id).await;
+                continue;
+            }
+
+            match self.repoint_instance(&stored).await {
+                Ok(()) => info!("Instance {} re-pointed to a fresh pool container after ejection", db_id),
+                Err(e) => warn!("Failed to re-point instance {} after pool ejection: {}", db_id, e),
+            }
+        }
+
+        let _ = self.docker.destroy_container(&pool.container_id).await;
+    }
+
+    /// Recreate a stranded instance's database and user on a freshly
+    /// selected pool container for its dialect (provisioning one if every
+    /// existing container is already at capacity or unhealthy), then
+    /// re-point its metadata and cache entry at the new `container_id`/
+    /// `host_port`. The prior container's data is gone by the time this
+    /// runs, so migrations are replayed from scratch rather than resumed
+    /// from `schema_version`.
+    async fn repoint_instance(&self, stored: &StoredInstance) -> Result<()> {
+        let dialect = get_dialect(&stored.dialect)?;
+        let db_name = DatabaseName::new(&stored.db_name)?;
+        let db_user = DatabaseUser::new(&stored.db_user)?;
+        let pool = self.select_pool_container(dialect.as_ref(), stored.db_id).await?;
+
+        let create_db_sql = dialect.create_database_sql(&db_name);
+        let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_db_sql);
+        let output = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await?;
+        if output.exit_code != Some(0) {
+            self.release_capacity(&pool.container_id, stored.db_id).await;
+            return Err(AppError::Internal(format!(
+                "Failed to recreate database {}: {}",
+                stored.db_name, output.stderr
+            )));
+        }
+
+        let create_user_sql = dialect.create_user_sql(&db_user, &stored.db_password, &db_name);
+        let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &create_user_sql);
+        let output = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await?;
+        if output.exit_code != Some(0) {
+            let drop_db_sql = dialect.drop_database_sql(&db_name);
+            let (cmd, args) = dialect.exec_sql_command(&pool.root_password, &drop_db_sql);
+            let _ = self.docker.exec(&pool.container_id, &cmd, &args, &[]).await;
+            self.release_capacity(&pool.container_id, stored.db_id).await;
+            return Err(AppError::Internal(format!(
+                "Failed to recreate user {}: {}",
+                stored.db_user, output.stderr
+            )));
+        }
+
+        self.metadata
+            .mark_active(stored.db_id, &pool.container_id, pool.host_port)
+            .await?;
+        self.metadata.update_schema_version(stored.db_id, 0).await?;
+
+        if let Some(migrations) = self.config.migrations.get(&stored.dialect) {
+            match self
+                .run_migrations(
+                    &pool.container_id,
+                    dialect.as_ref(),
+                    &stored.db_name,
+                    &stored.db_user,
+                    &stored.db_password,
+                    migrations,
+                )
+                .await
+            {
+                Ok(_) => {
+                    self.metadata
+                        .update_schema_version(stored.db_id, migrations.len() as u32)
+                        .await?;
+                }
+                Err(e) => warn!(
+                    "Migration replay after re-point failed for {}: {}",
+                    stored.db_id, e
+                ),
+            }
+        }
+
+        let mut instance = DbInstance::new(
+            stored.db_id,
+            stored.dialect.clone(),
+            pool.container_id.clone(),
+            pool.host_port,
+            stored.db_name.clone(),
+            stored.db_user.clone(),
+            stored.db_password.clone(),
+        );
+        instance.status = InstanceStatus::Running;
+        self.instances.insert(stored.db_id, instance).await;
+
+        let _ = self
+            .metadata
+            .record_event(stored.db_id, &stored.dialect, Some(InstanceState::Active), "active", "pool-recreated")
+            .await;
+
+        Ok(())
+    }
+
+    /// Snapshot of current pool container health, for the API layer
+    pub async fn pool_health_snapshot(&self) -> HashMap<String, PoolHealthEntry> {
+        self.pool_health.snapshot().await
+    }
+
     async fn cleanup_inactive(&self, timeout: Duration) {
         // Get expired instances from metadata
-        let expired = match self.metadata.get_expired_instances(timeout) {
+        let expired = match self.metadata.get_expired_instances(timeout).await {
             Ok(e) => e,
             Err(e) => {
                 warn!("Failed to get expired instances: {}", e);
@@ -732,6 +1814,19 @@ impl InstanceManager {
                 stored.db_id
             );
 
+            // The trigger-recorded event for the archive itself is a plain
+            // "status-changed"; note the actual reason here too.
+            let _ = self
+                .metadata
+                .record_event(
+                    stored.db_id,
+                    &stored.dialect,
+                    Some(stored.status),
+                    stored.status.as_str(),
+                    "cleanup-expired",
+                )
+                .await;
+
             // Archive instead of destroy (will fallback to destroy if backup not configured)
             if let Err(e) = self.archive_instance(stored.db_id).await {
                 warn!("Failed to archive instance {}: {}", stored.db_id, e);
@@ -741,15 +1836,71 @@ impl InstanceManager {
         }
     }
 
+    /// Reap instances whose absolute lease (`lease_ttl_secs` from creation,
+    /// plus `lease_grace_secs` of buffer) has passed, independent of the
+    /// idle-timeout check in `cleanup_inactive` — an instance that's still
+    /// actively used is torn down anyway once its hard TTL expires.
+    async fn cleanup_lease_expired(&self) {
+        if self.config.lease_ttl_secs == 0 {
+            return;
+        }
+
+        let grace = Duration::from_secs(self.config.lease_grace_secs);
+        let expired = match self.metadata.get_lease_expired_instances(grace).await {
+            Ok(e) => e,
+            Err(e) => {
+                warn!("Failed to get lease-expired instances: {}", e);
+                return;
+            }
+        };
+
+        for stored in expired {
+            info!(
+                "Instance {}'s lease has expired, archiving",
+                stored.db_id
+            );
+
+            let _ = self
+                .metadata
+                .record_event(
+                    stored.db_id,
+                    &stored.dialect,
+                    Some(stored.status),
+                    stored.status.as_str(),
+                    "lease-expired",
+                )
+                .await;
+
+            if let Err(e) = self.archive_instance(stored.db_id).await {
+                warn!("Failed to archive instance {}: {}", stored.db_id, e);
+                let _ = self.destroy_instance(stored.db_id).await;
+            }
+        }
+    }
+
+    /// Number of instances currently held in the in-memory cache. With a
+    /// bounded cache this can be lower than `total_instance_count`; use that
+    /// instead when the full active count (cached or not) is what matters.
     pub async fn instance_count(&self) -> usize {
-        self.instances.read().await.len()
+        self.instances.len().await
+    }
+
+    /// Total number of active instances known to the metadata store,
+    /// regardless of whether they're currently cached
+    pub async fn total_instance_count(&self) -> Result<usize> {
+        Ok(self.metadata.list_active_instances().await?.len())
+    }
+
+    /// Snapshot of all currently cached (active) instances
+    pub async fn active_instances(&self) -> Vec<DbInstance> {
+        self.instances.values().await
     }
 
     /// Recover existing database containers on startup
     /// Now reconciles Docker state with SQLite metadata for pool containers
     pub async fn recover_existing_instances(&self) -> Result<usize> {
         // First, recover pool containers
-        let stored_pools = self.metadata.list_pool_containers()?;
+        let stored_pools = self.metadata.list_pool_containers().await?;
         for pool in stored_pools {
             // Check if pool container still exists and is running
             match self.docker.is_running(&pool.container_id).await {
@@ -758,6 +1909,7 @@ impl InstanceManager {
                         "Pool container for {} recovered on port {}",
                         pool.dialect, pool.host_port
                     );
+                    self.register_pool(&pool).await;
                 }
                 _ => {
                     // Pool container died - remove from metadata
@@ -766,7 +1918,7 @@ impl InstanceManager {
                         "Pool container for {} not running, removing from metadata",
                         pool.dialect
                     );
-                    let _ = self.metadata.delete_pool_container(&pool.dialect);
+                    let _ = self.metadata.delete_pool_container(&pool.container_id).await;
                 }
             }
         }
@@ -774,7 +1926,7 @@ impl InstanceManager {
         // Also check for running pool containers not in metadata (e.g., API restarted but containers persisted)
         let running_pools = self.docker.list_pool_containers().await?;
         for pool in running_pools {
-            if self.metadata.get_pool_container(&pool.dialect)?.is_none() {
+            if self.metadata.get_pool_container(&pool.container_id).await?.is_none() {
                 // Pool container exists but not in metadata - we can't use it
                 // because we don't know the root password. Destroy it.
                 warn!(
@@ -785,56 +1937,77 @@ impl InstanceManager {
             }
         }
 
-        // Load all active instances from metadata
-        let stored_instances = self.metadata.list_active_instances()?;
-        let mut recovered = 0;
-
-        for stored in stored_instances {
-            // Check if the pool container for this dialect is running
-            if let Some(pool) = self.metadata.get_pool_container(&stored.dialect)? {
-                if self.docker.is_running(&pool.container_id).await.unwrap_or(false) {
-                    // Pool is running - add instance to cache
-                    let instance = DbInstance::new(
-                        stored.db_id,
-                        stored.dialect.clone(),
-                        pool.container_id.clone(),
-                        pool.host_port,
-                        stored.db_name.clone(),
-                        stored.db_user.clone(),
-                        stored.db_password.clone(),
-                    );
+        // Load all active instances from metadata and probe their Docker
+        // liveness concurrently - sequential round-trips here make startup
+        // slow once there are hundreds of instances to recover
+        enum RecoveryOutcome {
+            Recovered(DbInstance),
+            Orphaned { db_id: Uuid, has_backup: bool },
+        }
 
-                    info!(
-                        "Recovered instance {} ({}) on port {}",
-                        stored.db_id, stored.dialect, pool.host_port
-                    );
+        let stored_instances = self.metadata.list_active_instances().await?;
+        let outcomes: Vec<RecoveryOutcome> = stored_instances
+            .into_iter()
+            .map(|stored| async move {
+                let pool = match &stored.container_id {
+                    Some(container_id) => self.metadata.get_pool_container(container_id).await.ok().flatten(),
+                    None => None,
+                };
+
+                match pool {
+                    Some(pool) if self.docker.is_running(&pool.container_id).await.unwrap_or(false) => {
+                        let instance = DbInstance::new(
+                            stored.db_id,
+                            stored.dialect.clone(),
+                            pool.container_id.clone(),
+                            pool.host_port,
+                            stored.db_name.clone(),
+                            stored.db_user.clone(),
+                            stored.db_password.clone(),
+                        );
+                        info!(
+                            "Recovered instance {} ({}) on port {}",
+                            stored.db_id, stored.dialect, pool.host_port
+                        );
+                        RecoveryOutcome::Recovered(instance)
+                    }
+                    Some(_) => {
+                        warn!("Instance {} pool container not running", stored.db_id);
+                        RecoveryOutcome::Orphaned {
+                            db_id: stored.db_id,
+                            has_backup: stored.backup_key.is_some(),
+                        }
+                    }
+                    None => {
+                        warn!(
+                            "No pool container for instance {} ({})",
+                            stored.db_id, stored.dialect
+                        );
+                        RecoveryOutcome::Orphaned {
+                            db_id: stored.db_id,
+                            has_backup: stored.backup_key.is_some(),
+                        }
+                    }
+                }
+            })
+            .collect::<futures::stream::FuturesUnordered<_>>()
+            .collect()
+            .await;
 
-                    let mut instances = self.instances.write().await;
-                    instances.insert(stored.db_id, instance);
+        let mut recovered = 0;
+        for outcome in outcomes {
+            match outcome {
+                RecoveryOutcome::Recovered(instance) => {
+                    self.instances.insert(instance.id, instance).await;
                     recovered += 1;
-                } else {
-                    // Pool not running - mark instance as orphaned
-                    warn!(
-                        "Instance {} pool container not running",
-                        stored.db_id
-                    );
-                    if stored.backup_key.is_some() {
-                        let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
+                }
+                RecoveryOutcome::Orphaned { db_id, has_backup } => {
+                    if has_backup {
+                        let _ = self.metadata.update_status(db_id, InstanceState::Archived).await;
                     } else {
-                        let _ = self.metadata.delete_instance(stored.db_id);
+                        let _ = self.metadata.delete_instance(db_id).await;
                     }
                 }
-            } else {
-                // No pool container for this dialect
-                warn!(
-                    "No pool container for instance {} ({})",
-                    stored.db_id, stored.dialect
-                );
-                if stored.backup_key.is_some() {
-                    let _ = self.metadata.update_status(stored.db_id, InstanceState::Archived);
-                } else {
-                    let _ = self.metadata.delete_instance(stored.db_id);
-                }
             }
         }
 
@@ -850,8 +2023,8 @@ impl InstanceManager {
                 let _ = self.docker.destroy_container(&container.container_id).await;
             }
             // Clean up metadata if present
-            if self.metadata.get_instance(container.db_id)?.is_some() {
-                let _ = self.metadata.delete_instance(container.db_id);
+            if self.metadata.get_instance(container.db_id).await?.is_some() {
+                let _ = self.metadata.delete_instance(container.db_id).await;
             }
         }
 
@@ -859,6 +2032,21 @@ impl InstanceManager {
     }
 }
 
+/// Parse the single `version` column out of `cli_command_csv`-style output
+/// (a header row followed by one value per line), ignoring anything that
+/// doesn't parse as a `u32`. Deliberately narrower than `query::CsvRecordReader`
+/// - it only ever has to read back this one integer column this code wrote
+/// itself, not arbitrary user query results.
+fn parse_version_column(stdout: &str, delimiter: u8) -> Vec<u32> {
+    let delimiter = delimiter as char;
+    stdout
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split(delimiter).next())
+        .filter_map(|field| field.trim().trim_matches('"').parse::<u32>().ok())
+        .collect()
+}
+
 /// Generate a strong password for database access
 fn generate_password() -> String {
     // SQL Server requires strong passwords: uppercase, lowercase, numbers, and special chars
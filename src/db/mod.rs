@@ -1,7 +1,15 @@
+pub mod cache;
 pub mod dialects;
+pub mod health;
+pub mod identifiers;
 pub mod instance;
 pub mod manager;
+pub mod native_pool;
+pub mod params;
+pub mod pool;
 pub mod query;
+pub mod sql_split;
 
+pub use identifiers::{DatabaseName, DatabaseUser};
 pub use manager::InstanceManager;
 pub use query::RawQueryOutput;
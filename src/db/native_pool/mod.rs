@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::error::Result;
+
+use super::dialects::Dialect;
+use super::query::QueryEvent;
+
+mod postgres;
+
+pub use postgres::PostgresNativeConnector;
+
+/// A native (non-CLI) connection to a single `DbInstance`, opened fresh by
+/// `NativeConnector::connect` on every `ConnectionPoolManager::acquire`;
+/// connections are not recycled between queries.
+///
+/// `postgres::PostgresNativeConnection` is the one implementation in this
+/// tree today - see `ConnectionPoolManager` for why the others aren't.
+#[async_trait]
+pub trait NativeConnection: Send {
+    /// Run one statement and collect its events. Unlike the CLI path, this
+    /// talks the wire protocol directly instead of spawning a `docker exec`
+    /// CLI process per statement.
+    async fn execute_statement(&mut self, statement: &str, statement_index: usize, is_last: bool) -> Result<Vec<QueryEvent>>;
+
+    /// Cheap liveness probe. `ConnectionPoolManager::acquire` does not call
+    /// it, since every acquire opens a fresh connection
+    async fn ping(&mut self) -> bool;
+}
+
+/// Per-dialect factory for `NativeConnection`s, connecting over
+/// `DbInstance::host_port` instead of `docker exec`. Registered per dialect
+/// with `ConnectionPoolManager::register`, and used only for dialects whose
+/// `Dialect::supports_native_pool` returns `true`.
+#[async_trait]
+pub trait NativeConnector: Send + Sync {
+    async fn connect(&self, host_port: u16, db_name: &str, user: &str, password: &str) -> Result<Box<dyn NativeConnection>>;
+}
+
+/// Keyed by `DbInstance` id, this is the seam `QueryExecutor` checks before
+/// falling back to spawning a `docker exec` CLI process per statement. It's
+/// intentionally a thin registry rather than a pool implementation itself:
+/// actually recycling/sizing/health-checking connections is deadpool's job.
+/// `postgres::PostgresNativeConnector` is registered for the `postgres`
+/// dialect (see `main.rs`), connecting for real over `tokio_postgres` - a
+/// dependency this tree already has via `storage::postgres`. MySQL and SQL
+/// Server stay on the CLI path: this tree has no package manifest to add
+/// `mysql_async`/`tiberius` to, so nothing is registered for them and their
+/// `Dialect::supports_native_pool` stays at the `false` default, the same
+/// way Postgres's did before this connector existed.
+#[derive(Default)]
+pub struct ConnectionPoolManager {
+    connectors: RwLock<HashMap<String, Arc<dyn NativeConnector>>>,
+}
+
+impl ConnectionPoolManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a dialect's native connector, making `acquire` return pooled
+    /// connections for that dialect instead of falling back to the CLI path
+    pub async fn register(&self, dialect_name: &str, connector: Arc<dyn NativeConnector>) {
+        self.connectors.write().await.insert(dialect_name.to_string(), connector);
+    }
+
+    /// Get a native connection for `instance`, if its dialect has a
+    /// registered connector and opts in via `Dialect::supports_native_pool`.
+    /// `None` means the caller should fall back to the CLI exec path.
+    pub async fn acquire(
+        &self,
+        dialect: &dyn Dialect,
+        host_port: u16,
+        db_name: &str,
+        user: &str,
+        password: &str,
+    ) -> Option<Box<dyn NativeConnection>> {
+        if !dialect.supports_native_pool() {
+            return None;
+        }
+
+        let connector = self.connectors.read().await.get(dialect.name()).cloned()?;
+        connector.connect(host_port, db_name, user, password).await.ok()
+    }
+}
@@ -0,0 +1,122 @@
+use async_trait::async_trait;
+use serde_json::Value as JsonValue;
+use tokio_postgres::{Client, NoTls, SimpleQueryMessage};
+
+use crate::error::{AppError, Result};
+
+use super::{NativeConnection, NativeConnector};
+use crate::db::query::QueryEvent;
+
+/// `NativeConnector` for the `postgres` dialect, talking the wire protocol
+/// directly via `tokio_postgres` instead of spawning `psql` per statement.
+/// The only dialect with one today: `tokio_postgres` is already a
+/// dependency of this tree (see `storage::postgres::PostgresMetadataStore`),
+/// whereas MySQL/SQL Server would need `mysql_async`/`tiberius`, neither of
+/// which this tree depends on.
+///
+/// Connects fresh on every `connect` rather than recycling a warm pool of
+/// connections - actually sizing/idle-reaping/health-checking a pool is
+/// `deadpool-postgres`'s job once this dialect takes on that dependency.
+/// This gets a real wire-protocol connection behind the seam without it.
+#[derive(Default)]
+pub struct PostgresNativeConnector;
+
+#[async_trait]
+impl NativeConnector for PostgresNativeConnector {
+    async fn connect(&self, host_port: u16, db_name: &str, user: &str, password: &str) -> Result<Box<dyn NativeConnection>> {
+        let conn_string = format!(
+            "host=127.0.0.1 port={} dbname={} user={} password={} connect_timeout=5",
+            host_port, db_name, user, password,
+        );
+        let (client, connection) = tokio_postgres::connect(&conn_string, NoTls)
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to open native Postgres connection: {}", e)))?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!("Native Postgres connection closed with error: {}", e);
+            }
+        });
+
+        Ok(Box::new(PostgresNativeConnection { client }))
+    }
+}
+
+struct PostgresNativeConnection {
+    client: Client,
+}
+
+#[async_trait]
+impl NativeConnection for PostgresNativeConnection {
+    /// Runs `statement` via `simple_query` rather than the prepared-statement
+    /// API: callers hand over raw, already-split SQL text with no bind
+    /// parameters (same contract the CLI path has with `psql -c`), and
+    /// `simple_query` is what lets arbitrary multi-form SQL - DDL, DML,
+    /// `SELECT` - run without a caller-supplied parameter list.
+    ///
+    /// `SimpleQueryRow` hands back every column as `Option<&str>`, same as
+    /// reading `psql`'s text output - there's no per-column type metadata to
+    /// go on, so numeric coercion uses the same parse-and-fall-back-to-string
+    /// heuristic as the CLI/CSV path's `csv_field_to_value`.
+    async fn execute_statement(&mut self, statement: &str, statement_index: usize, is_last: bool) -> Result<Vec<QueryEvent>> {
+        let messages = match self.client.simple_query(statement).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                return Ok(vec![
+                    QueryEvent::Error { message: e.to_string() },
+                    QueryEvent::Done { statement_index, affected_rows: None, is_last },
+                ]);
+            }
+        };
+
+        let mut events = Vec::new();
+        let mut affected_rows = None;
+        let mut columns: Option<Vec<String>> = None;
+
+        for message in messages {
+            match message {
+                SimpleQueryMessage::Row(row) => {
+                    let cols = columns
+                        .get_or_insert_with(|| row.columns().iter().map(|c| c.name().to_string()).collect())
+                        .clone();
+                    let values = (0..cols.len()).map(|i| simple_query_value_to_json(row.get(i))).collect();
+                    events.push(QueryEvent::Record {
+                        statement_index,
+                        columns: cols,
+                        row: values,
+                    });
+                }
+                SimpleQueryMessage::CommandComplete(n) => {
+                    affected_rows = Some(n);
+                }
+                _ => {}
+            }
+        }
+
+        events.push(QueryEvent::Done { statement_index, affected_rows, is_last });
+        Ok(events)
+    }
+
+    async fn ping(&mut self) -> bool {
+        self.client.simple_query("SELECT 1;").await.is_ok()
+    }
+}
+
+/// Convert one `SimpleQueryRow` field to JSON, matching `csv_field_to_value`'s
+/// number-then-string heuristic since `simple_query` gives no type metadata.
+fn simple_query_value_to_json(value: Option<&str>) -> JsonValue {
+    match value {
+        None => JsonValue::Null,
+        Some(s) => {
+            if let Ok(n) = s.parse::<i64>() {
+                return JsonValue::Number(n.into());
+            }
+            if let Ok(n) = s.parse::<f64>() {
+                if let Some(num) = serde_json::Number::from_f64(n) {
+                    return JsonValue::Number(num);
+                }
+            }
+            JsonValue::String(s.to_string())
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use serde_json::Value as JsonValue;
+
+use crate::error::{AppError, Result};
+
+use super::dialects::{Dialect, PlaceholderStyle};
+use super::sql_split::dollar_tag_at;
+
+/// Substitute positional placeholders in `query` with `params`, rendered as
+/// dialect-appropriate SQL literals.
+///
+/// Because queries run through a CLI client rather than a protocol-level
+/// prepared statement, substitution happens here, server-side, instead of
+/// being handed off to the database. Placeholders inside quoted strings and
+/// comments are left untouched, following the same state machine as
+/// `sql_split::split_statements`.
+pub fn bind_params(dialect: &dyn Dialect, query: &str, params: &[JsonValue]) -> Result<String> {
+    match dialect.placeholder_style() {
+        PlaceholderStyle::QuestionMark => bind_question_mark(query, params),
+        PlaceholderStyle::Dollar => bind_dollar(query, params),
+    }
+}
+
+/// `?` placeholders, consumed left-to-right, one per occurrence.
+fn bind_question_mark(query: &str, params: &[JsonValue]) -> Result<String> {
+    enum State {
+        Normal,
+        SingleQuote,
+        DoubleQuote,
+        LineComment,
+        BlockComment,
+    }
+
+    let chars: Vec<char> = query.chars().collect();
+    let mut state = State::Normal;
+    let mut out = String::with_capacity(query.len());
+    let mut param_index = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match state {
+            State::Normal => match c {
+                '\'' => {
+                    out.push(c);
+                    state = State::SingleQuote;
+                }
+                '"' => {
+                    out.push(c);
+                    state = State::DoubleQuote;
+                }
+                '-' if chars.get(i + 1) == Some(&'-') => {
+                    out.push(c);
+                    state = State::LineComment;
+                }
+                '/' if chars.get(i + 1) == Some(&'*') => {
+                    out.push(c);
+                    state = State::BlockComment;
+                }
+                '?' => {
+                    let value = params.get(param_index).ok_or_else(|| {
+                        AppError::InvalidQueryParams(format!(
+                            "Query references more than {} `?` placeholder(s)",
+                            params.len()
+                        ))
+                    })?;
+                    out.push_str(&render_literal(value)?);
+                    param_index += 1;
+                }
+                _ => out.push(c),
+            },
+            State::SingleQuote => {
+                out.push(c);
+                if c == '\'' {
+                    state = State::Normal;
+                }
+            }
+            State::DoubleQuote => {
+                out.push(c);
+                if c == '"' {
+                    state = State::Normal;
+                }
+            }
+            State::LineComment => {
+                out.push(c);
+                if c == '\n' {
+                    state = State::Normal;
+                }
+            }
+            State::BlockComment => {
+                out.push(c);
+                if c == '/' && out.ends_with("*/") {
+                    state = State::Normal;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    if param_index != params.len() {
+        return Err(AppError::InvalidQueryParams(format!(
+            "Query references {} `?` placeholder(s) but {} parameter(s) were supplied",
+            param_index,
+            params.len()
+        )));
+    }
+
+    Ok(out)
+}
+
+/// `$1`, `$2`, ... placeholders, looked up by index so the same parameter
+/// can be referenced more than once.
+fn bind_dollar(query: &str, params: &[JsonValue]) -> Result<String> {
+    enum State {
+        Normal,
+        SingleQuote,
+        DoubleQuote,
+        LineComment,
+        BlockComment,
+        DollarQuote,
+    }
+
+    let chars: Vec<char> = query.chars().collect();
+    let mut state = State::Normal;
+    let mut dollar_tag = String::new();
+    let mut out = String::with_capacity(query.len());
+    let mut used_indices = std::collections::HashSet::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match state {
+            State::Normal => match c {
+                '\'' => {
+                    out.push(c);
+                    state = State::SingleQuote;
+                }
+                '"' => {
+                    out.push(c);
+                    state = State::DoubleQuote;
+                }
+                '-' if chars.get(i + 1) == Some(&'-') => {
+                    out.push(c);
+                    state = State::LineComment;
+                }
+                '/' if chars.get(i + 1) == Some(&'*') => {
+                    out.push(c);
+                    state = State::BlockComment;
+                }
+                '$' if chars.get(i + 1).map(|d| d.is_ascii_digit()).unwrap_or(false) => {
+                    let mut j = i + 1;
+                    while j < chars.len() && chars[j].is_ascii_digit() {
+                        j += 1;
+                    }
+                    let digits: String = chars[i + 1..j].iter().collect();
+                    let index: usize = digits.parse().map_err(|_| {
+                        AppError::InvalidQueryParams(format!("Invalid placeholder index: ${}", digits))
+                    })?;
+                    let value = params.get(index - 1).ok_or_else(|| {
+                        AppError::InvalidQueryParams(format!(
+                            "Query references ${} but only {} parameter(s) were supplied",
+                            index,
+                            params.len()
+                        ))
+                    })?;
+                    out.push_str(&render_literal(value)?);
+                    used_indices.insert(index);
+                    i = j - 1;
+                }
+                '$' => {
+                    if let Some(tag) = dollar_tag_at(&chars, i) {
+                        out.push_str(&tag);
+                        i += tag.chars().count() - 1;
+                        dollar_tag = tag;
+                        state = State::DollarQuote;
+                    } else {
+                        out.push(c);
+                    }
+                }
+                _ => out.push(c),
+            },
+            State::SingleQuote => {
+                out.push(c);
+                if c == '\'' {
+                    state = State::Normal;
+                }
+            }
+            State::DoubleQuote => {
+                out.push(c);
+                if c == '"' {
+                    state = State::Normal;
+                }
+            }
+            State::LineComment => {
+                out.push(c);
+                if c == '\n' {
+                    state = State::Normal;
+                }
+            }
+            State::BlockComment => {
+                out.push(c);
+                if c == '/' && out.ends_with("*/") {
+                    state = State::Normal;
+                }
+            }
+            State::DollarQuote => {
+                out.push(c);
+                if c == '$' && out.ends_with(dollar_tag.as_str()) {
+                    state = State::Normal;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let all_referenced = (1..=params.len()).all(|n| used_indices.contains(&n));
+    if used_indices.len() != params.len() || !all_referenced {
+        return Err(AppError::InvalidQueryParams(format!(
+            "Query references {} distinct placeholder(s) but {} parameter(s) were supplied",
+            used_indices.len(),
+            params.len()
+        )));
+    }
+
+    Ok(out)
+}
+
+/// Render a JSON parameter value as a SQL literal. Numbers and booleans are
+/// rendered literally, `null` as `NULL`, and strings single-quoted with
+/// embedded quotes doubled.
+fn render_literal(value: &JsonValue) -> Result<String> {
+    match value {
+        JsonValue::Null => Ok("NULL".to_string()),
+        JsonValue::Bool(b) => Ok(if *b { "TRUE".to_string() } else { "FALSE".to_string() }),
+        JsonValue::Number(n) => Ok(n.to_string()),
+        JsonValue::String(s) => quote_string(s),
+        JsonValue::Array(_) | JsonValue::Object(_) => Err(AppError::InvalidQueryParams(
+            "Query parameters must be strings, numbers, booleans, or null".to_string(),
+        )),
+    }
+}
+
+/// Single-quote a string literal, doubling embedded single quotes.
+/// Backslashes and NUL bytes are rejected outright rather than escaped,
+/// since some dialects (MySQL, without NO_BACKSLASH_ESCAPES) treat a
+/// backslash in a string literal specially, which could let a crafted value
+/// break out of the quotes that doubling alone wouldn't catch.
+fn quote_string(s: &str) -> Result<String> {
+    if s.contains('\\') || s.contains('\0') {
+        return Err(AppError::InvalidQueryParams(
+            "Query parameter strings may not contain backslashes or NUL bytes".to_string(),
+        ));
+    }
+
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('\'');
+    for c in s.chars() {
+        if c == '\'' {
+            out.push('\'');
+        }
+        out.push(c);
+    }
+    out.push('\'');
+    Ok(out)
+}
@@ -0,0 +1,128 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Health state of a single pool container, tracked by the background
+/// monitor in `InstanceManager::start_pool_monitor_task`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PoolHealthStatus {
+    Healthy,
+    Degraded,
+    Dead,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PoolHealthEntry {
+    pub dialect: String,
+    pub status: PoolHealthStatus,
+    pub consecutive_failures: u32,
+}
+
+/// Tracks liveness of every known pool container, keyed by `container_id`.
+///
+/// Mirrors the health-check/eject/select-among-healthy design of a
+/// connection-pool supervisor (qorb-style), but applied to Docker-hosted
+/// pool containers rather than raw TCP backends.
+#[derive(Default)]
+pub struct PoolHealthTracker {
+    entries: RwLock<HashMap<String, PoolHealthEntry>>,
+}
+
+impl PoolHealthTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a passing health check, resetting the failure counter
+    pub async fn record_success(&self, container_id: &str, dialect: &str) {
+        let mut entries = self.entries.write().await;
+        entries.insert(
+            container_id.to_string(),
+            PoolHealthEntry {
+                dialect: dialect.to_string(),
+                status: PoolHealthStatus::Healthy,
+                consecutive_failures: 0,
+            },
+        );
+    }
+
+    /// Record a failing health check. Returns `true` once the container has
+    /// reached `eject_after` consecutive failures and should be ejected.
+    pub async fn record_failure(&self, container_id: &str, dialect: &str, eject_after: u32) -> bool {
+        let mut entries = self.entries.write().await;
+        let entry = entries
+            .entry(container_id.to_string())
+            .or_insert_with(|| PoolHealthEntry {
+                dialect: dialect.to_string(),
+                status: PoolHealthStatus::Healthy,
+                consecutive_failures: 0,
+            });
+
+        entry.consecutive_failures += 1;
+        entry.status = if entry.consecutive_failures >= eject_after {
+            PoolHealthStatus::Dead
+        } else {
+            PoolHealthStatus::Degraded
+        };
+
+        entry.status == PoolHealthStatus::Dead
+    }
+
+    /// Drop tracking for a container that has been ejected and destroyed
+    pub async fn forget(&self, container_id: &str) {
+        self.entries.write().await.remove(container_id);
+    }
+
+    /// Snapshot of all tracked container health, for the API layer
+    pub async fn snapshot(&self) -> HashMap<String, PoolHealthEntry> {
+        self.entries.read().await.clone()
+    }
+
+    /// Container ids currently considered healthy for a given dialect
+    pub async fn healthy_containers(&self, dialect: &str) -> Vec<String> {
+        self.entries
+            .read()
+            .await
+            .iter()
+            .filter(|(_, e)| e.dialect == dialect && e.status != PoolHealthStatus::Dead)
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+}
+
+/// Tracks when each pool container was last handed out by
+/// `InstanceManager::select_pool_container`, so the memory-pressure eviction
+/// task can stop the least-recently-active idle ones first instead of
+/// guessing from container age.
+#[derive(Default)]
+pub struct PoolActivityTracker {
+    last_active: RwLock<HashMap<String, DateTime<Utc>>>,
+}
+
+impl PoolActivityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn touch(&self, container_id: &str) {
+        self.last_active
+            .write()
+            .await
+            .insert(container_id.to_string(), Utc::now());
+    }
+
+    pub async fn forget(&self, container_id: &str) {
+        self.last_active.write().await.remove(container_id);
+    }
+
+    /// Order `container_ids` oldest-touched-first; ids never touched sort
+    /// first, since they're the safest to treat as least recently active.
+    pub async fn oldest_first(&self, container_ids: &[String]) -> Vec<String> {
+        let last_active = self.last_active.read().await;
+        let mut ids = container_ids.to_vec();
+        ids.sort_by_key(|id| last_active.get(id).copied().unwrap_or(DateTime::<Utc>::MIN_UTC));
+        ids
+    }
+}
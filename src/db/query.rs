@@ -1,29 +1,44 @@
-use futures::stream;
+use futures::stream::{self, BoxStream, StreamExt};
 use serde::Serialize;
 use serde_json::Value as JsonValue;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tokio_stream::Stream;
+use std::time::{Duration, Instant};
 use tracing::debug;
 
-use crate::docker::DockerManager;
-use crate::error::Result;
+use crate::docker::{BoxedExecStream, DockerManager, ExecChunk};
+use crate::error::{AppError, Result};
 
-use super::dialects::get_dialect;
+use super::dialects::{get_dialect, Dialect};
 use super::instance::DbInstance;
+use super::native_pool::{ConnectionPoolManager, NativeConnector};
+use super::sql_split::split_statements;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum QueryEvent {
     Line { text: String },
-    Record { columns: Vec<String>, row: Vec<JsonValue> },
+    Record {
+        statement_index: usize,
+        columns: Vec<String>,
+        row: Vec<JsonValue>,
+    },
     Error { message: String },
-    Done { affected_rows: Option<u64> },
+    Done {
+        statement_index: usize,
+        affected_rows: Option<u64>,
+        /// Whether this was the last statement in the batch. The SSE
+        /// transport uses this to tell a mid-batch statement boundary apart
+        /// from overall completion.
+        is_last: bool,
+    },
 }
 
 pub struct QueryExecutor {
     docker: Arc<DockerManager>,
     query_timeout: Duration,
+    native_pool: Arc<ConnectionPoolManager>,
 }
 
 /// Raw query output (stdout + stderr)
@@ -38,25 +53,31 @@ impl QueryExecutor {
         Self {
             docker,
             query_timeout,
+            native_pool: Arc::new(ConnectionPoolManager::new()),
         }
     }
 
+    /// Register a dialect's native connector, so `execute` routes that
+    /// dialect's queries to it instead of falling back to the CLI exec path.
+    /// See `db::native_pool` for which dialects have one registered.
+    pub async fn register_native_connector(&self, dialect_name: &str, connector: Arc<dyn NativeConnector>) {
+        self.native_pool.register(dialect_name, connector).await;
+    }
+
     /// Execute query and return raw CLI output (for format=text)
     /// Uses pretty ASCII table format with borders
+    ///
+    /// The query is split into individual statements and each is run as its
+    /// own CLI invocation, so multi-statement scripts come back with a clean
+    /// separator between result sets instead of relying on the old
+    /// `+---+`-sniffing heuristic.
     pub async fn execute_raw(
         &self,
         instance: &DbInstance,
         sql: &str,
     ) -> Result<RawQueryOutput> {
         let dialect = get_dialect(&instance.dialect)?;
-
-        // Use text-formatted command for pretty output
-        let (cmd, args) = dialect.cli_command_text(
-            &instance.db_name,
-            &instance.db_user,
-            &instance.db_password,
-            sql,
-        );
+        let statements = statements_or_whole(sql);
 
         let env = dialect.cli_env_vars(
             &instance.db_name,
@@ -64,195 +85,614 @@ impl QueryExecutor {
             &instance.db_password,
         );
 
-        debug!("Executing query via CLI (text): {} {:?}", cmd, args);
-
-        let output = self
-            .docker
-            .exec_with_timeout(&instance.container_id, &cmd, &args, &env, self.query_timeout)
-            .await?;
+        let mut stdout_parts = Vec::new();
+        let mut stderr_parts = Vec::new();
+
+        for statement in &statements {
+            // Use text-formatted command for pretty output
+            let (cmd, args) = dialect.cli_command_text(
+                &instance.db_name,
+                &instance.db_user,
+                &instance.db_password,
+                statement,
+            );
+
+            debug!("Executing query via CLI (text): {} {:?}", cmd, args);
+
+            let start = Instant::now();
+            let output = self
+                .docker
+                .exec_with_timeout(&instance.container_id, &cmd, &args, &env, self.query_timeout)
+                .await?;
+            debug!(
+                db_id = %instance.id,
+                duration_ms = start.elapsed().as_millis() as u64,
+                "Query finished"
+            );
+
+            if !output.stdout.is_empty() {
+                stdout_parts.push(output.stdout);
+            }
+            if !output.stderr.is_empty() {
+                stderr_parts.push(output.stderr);
+            }
+        }
 
         Ok(RawQueryOutput {
-            stdout: output.stdout,
-            stderr: output.stderr,
+            stdout: stdout_parts.join("\n---\n"),
+            stderr: stderr_parts.join("\n"),
         })
     }
 
     /// Execute query and return parsed events (for format=json, jsonl)
+    ///
+    /// Following SQLPage's approach, the incoming `query` string is split
+    /// into individual statements that are executed one at a time, in order,
+    /// with results grouped per statement. On the CLI path this means each
+    /// statement is a separate CLI invocation (and, for most dialects, a
+    /// separate connection), so a session-scoped temp table created by one
+    /// statement won't be visible to the next. When a native connection is
+    /// acquired instead (see `ConnectionPoolManager::acquire`), every
+    /// statement runs on that one connection.
+    ///
+    /// Each statement's CLI output is parsed and forwarded incrementally as
+    /// it arrives over `DockerManager::exec_stream`, instead of buffering the
+    /// whole statement's stdout/stderr before parsing - a large `SELECT` no
+    /// longer has to finish and land in memory before the first row reaches
+    /// the client. Statements still run strictly one after another (the next
+    /// statement's `docker exec` isn't started until the previous one's
+    /// stream is fully drained), and a statement that produces an error still
+    /// stops the batch before the next one starts, matching the old
+    /// buffered behavior.
     pub async fn execute(
         &self,
         instance: &DbInstance,
         sql: &str,
-    ) -> Result<impl Stream<Item = QueryEvent>> {
+    ) -> Result<BoxStream<'static, QueryEvent>> {
         let dialect = get_dialect(&instance.dialect)?;
-
-        let (cmd, args) = dialect.cli_command(
-            &instance.db_name,
-            &instance.db_user,
-            &instance.db_password,
-            sql,
-        );
+        let statements = statements_or_whole(sql);
+        let last_index = statements.len() - 1;
+
+        if let Some(conn) = self
+            .native_pool
+            .acquire(
+                dialect.as_ref(),
+                instance.host_port,
+                &instance.db_name,
+                &instance.db_user,
+                &instance.db_password,
+            )
+            .await
+        {
+            return Ok(stream_native_pool(conn, statements, last_index, self.query_timeout).boxed());
+        }
 
         let env = dialect.cli_env_vars(
             &instance.db_name,
             &instance.db_user,
             &instance.db_password,
         );
+        let dialect_name = instance.dialect.clone();
+        let db_name = instance.db_name.clone();
+        let db_user = instance.db_user.clone();
+        let db_password = instance.db_password.clone();
+        let docker = self.docker.clone();
+        let container_id = instance.container_id.clone();
+        let query_timeout = self.query_timeout;
+        let error_flag = Arc::new(AtomicBool::new(false));
+
+        let stream = stream::iter(statements.into_iter().enumerate()).flat_map(move |(statement_index, statement)| {
+            let dialect = match get_dialect(&dialect_name) {
+                Ok(d) => d,
+                Err(e) => {
+                    return stream::once(async move { QueryEvent::Error { message: e.to_string() } }).boxed();
+                }
+            };
+            let (cmd, args) = dialect.cli_command_csv(&db_name, &db_user, &db_password, &statement);
+            let pending_exec = PendingStatementExec {
+                docker: docker.clone(),
+                container_id: container_id.clone(),
+                cmd,
+                args,
+                env: env.clone(),
+                dialect,
+                statement_index,
+                is_last: statement_index == last_index,
+                query_timeout,
+                error_flag: error_flag.clone(),
+            };
+            stream_statement(pending_exec).boxed()
+        });
+
+        Ok(stream.boxed())
+    }
+}
 
-        debug!("Executing query via CLI: {} {:?}", cmd, args);
-
-        let output = self
-            .docker
-            .exec_with_timeout(&instance.container_id, &cmd, &args, &env, self.query_timeout)
-            .await?;
+/// Stream a statement batch against an already-acquired native connection,
+/// one statement at a time, instead of collecting every statement's events
+/// into a `Vec` before the caller sees any of them - this is what gives the
+/// native-pool path the same "don't buffer the whole batch before the first
+/// row reaches the client" property `stream_statement` gives the CLI path.
+/// Each statement is also bounded by `query_timeout`, the same way
+/// `exec_with_timeout` bounds every CLI statement - a connection-level call
+/// has no process for the CLI path's timeout to kill, so this is the native
+/// path's equivalent backstop against a stuck query hanging the request.
+fn stream_native_pool(
+    conn: Box<dyn super::native_pool::NativeConnection>,
+    statements: Vec<String>,
+    last_index: usize,
+    query_timeout: Duration,
+) -> impl futures::Stream<Item = QueryEvent> {
+    struct State {
+        conn: Box<dyn super::native_pool::NativeConnection>,
+        statements: std::vec::IntoIter<String>,
+        next_index: usize,
+        last_index: usize,
+        query_timeout: Duration,
+        pending: VecDeque<QueryEvent>,
+        done: bool,
+    }
 
-        let events = parse_cli_output(&output.stdout, &output.stderr, dialect.as_ref());
+    stream::unfold(
+        State {
+            conn,
+            statements: statements.into_iter(),
+            next_index: 0,
+            last_index,
+            query_timeout,
+            pending: VecDeque::new(),
+            done: false,
+        },
+        |mut state| async move {
+            loop {
+                if let Some(event) = state.pending.pop_front() {
+                    return Some((event, state));
+                }
+                if state.done {
+                    return None;
+                }
+                let statement = state.statements.next()?;
+                let statement_index = state.next_index;
+                state.next_index += 1;
+                let is_last = statement_index == state.last_index;
+
+                match tokio::time::timeout(
+                    state.query_timeout,
+                    state.conn.execute_statement(&statement, statement_index, is_last),
+                )
+                .await
+                {
+                    Ok(Ok(statement_events)) => {
+                        let had_error = statement_events.iter().any(|e| matches!(e, QueryEvent::Error { .. }));
+                        state.pending.extend(statement_events);
+                        if had_error {
+                            state.done = true;
+                        }
+                    }
+                    Ok(Err(e)) => {
+                        state.pending.push_back(QueryEvent::Error { message: e.to_string() });
+                        state.pending.push_back(QueryEvent::Done { statement_index, affected_rows: None, is_last });
+                        state.done = true;
+                    }
+                    Err(_) => {
+                        state.pending.push_back(QueryEvent::Error { message: AppError::QueryTimeout.to_string() });
+                        state.pending.push_back(QueryEvent::Done { statement_index, affected_rows: None, is_last });
+                        state.done = true;
+                    }
+                }
+            }
+        },
+    )
+}
 
-        Ok(stream::iter(events))
+/// Split `sql` into statements, falling back to treating the whole string as
+/// a single statement if the splitter finds nothing (e.g. an empty query).
+fn statements_or_whole(sql: &str) -> Vec<String> {
+    let statements = split_statements(sql);
+    if statements.is_empty() {
+        vec![sql.to_string()]
+    } else {
+        statements
     }
 }
 
-/// Parse CLI output into QueryEvents
-fn parse_cli_output(
-    stdout: &str,
-    stderr: &str,
-    dialect: &dyn super::dialects::Dialect,
-) -> Vec<QueryEvent> {
-    let mut events = Vec::new();
-
-    // Handle stderr (errors/warnings)
-    for line in stderr.lines() {
-        let line = line.trim();
-        if line.is_empty() {
-            continue;
+/// Everything `stream_statement` needs to start (and, if an earlier statement
+/// in the batch already failed, skip) one statement's `docker exec`. Built up
+/// front per statement so that `stream::unfold`'s first poll - not the
+/// `flat_map` closure that constructs this - is what actually calls
+/// `exec_stream`, which is what gives later statements' execs their lazy,
+/// "only after the previous statement is fully drained" ordering.
+struct PendingStatementExec {
+    docker: Arc<DockerManager>,
+    container_id: String,
+    cmd: String,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    dialect: Box<dyn Dialect>,
+    statement_index: usize,
+    is_last: bool,
+    query_timeout: Duration,
+    error_flag: Arc<AtomicBool>,
+}
+
+struct ActiveStatementExec {
+    inner: BoxedExecStream,
+    deadline: tokio::time::Instant,
+    started_at: Instant,
+    dialect: Box<dyn Dialect>,
+    statement_index: usize,
+    is_last: bool,
+    error_flag: Arc<AtomicBool>,
+    stdout_csv: CsvRecordReader,
+    header: Option<Vec<String>>,
+    stderr_buf: LineBuffer,
+    pending: VecDeque<QueryEvent>,
+    exited: bool,
+}
+
+enum StatementStreamState {
+    Pending(PendingStatementExec),
+    Active(Box<ActiveStatementExec>),
+    Done,
+}
+
+/// Stream one statement's events, starting its `docker exec` lazily on the
+/// first poll and parsing its stdout/stderr incrementally as chunks arrive,
+/// instead of collecting the whole statement's output before parsing it.
+fn stream_statement(pending: PendingStatementExec) -> impl futures::Stream<Item = QueryEvent> {
+    stream::unfold(StatementStreamState::Pending(pending), move |mut state| async move {
+        loop {
+            match state {
+                StatementStreamState::Done => return None,
+
+                StatementStreamState::Pending(p) if p.error_flag.load(Ordering::SeqCst) => {
+                    // An earlier statement in this batch already failed; stop
+                    // before even starting this one, matching the old
+                    // `if had_error { break; }` behavior.
+                    let _ = p;
+                    return None;
+                }
+
+                StatementStreamState::Pending(p) => {
+                    debug!(
+                        statement_index = p.statement_index,
+                        "Executing statement via CLI: {} {:?}", p.cmd, p.args
+                    );
+                    match p.docker.exec_stream(&p.container_id, &p.cmd, &p.args, &p.env).await {
+                        Ok(inner) => {
+                            let stdout_csv = CsvRecordReader::new(p.dialect.csv_delimiter());
+                            state = StatementStreamState::Active(Box::new(ActiveStatementExec {
+                                inner,
+                                deadline: tokio::time::Instant::now() + p.query_timeout,
+                                started_at: Instant::now(),
+                                dialect: p.dialect,
+                                statement_index: p.statement_index,
+                                is_last: p.is_last,
+                                error_flag: p.error_flag,
+                                stdout_csv,
+                                header: None,
+                                stderr_buf: LineBuffer::default(),
+                                pending: VecDeque::new(),
+                                exited: false,
+                            }));
+                        }
+                        Err(e) => {
+                            p.error_flag.store(true, Ordering::SeqCst);
+                            return Some((QueryEvent::Error { message: e.to_string() }, StatementStreamState::Done));
+                        }
+                    }
+                }
+
+                StatementStreamState::Active(mut active) => {
+                    if let Some(event) = active.pending.pop_front() {
+                        return Some((event, StatementStreamState::Active(active)));
+                    }
+
+                    if active.exited {
+                        return None;
+                    }
+
+                    tokio::select! {
+                        _ = tokio::time::sleep_until(active.deadline) => {
+                            active.error_flag.store(true, Ordering::SeqCst);
+                            return Some((
+                                QueryEvent::Error { message: AppError::QueryTimeout.to_string() },
+                                StatementStreamState::Done,
+                            ));
+                        }
+                        chunk = active.inner.next() => {
+                            match chunk {
+                                Some(Ok(ExecChunk::Stdout(bytes))) => {
+                                    for record in active.stdout_csv.push(&bytes) {
+                                        feed_csv_record(&mut active.header, record, active.dialect.as_ref(), active.statement_index, &mut active.pending);
+                                    }
+                                }
+                                Some(Ok(ExecChunk::Stderr(bytes))) => {
+                                    for line in active.stderr_buf.push(&bytes) {
+                                        feed_stderr_line(&line, active.dialect.as_ref(), &mut active.pending);
+                                    }
+                                }
+                                Some(Ok(ExecChunk::Exit(_))) | None => {
+                                    if let Some(record) = active.stdout_csv.finish() {
+                                        feed_csv_record(&mut active.header, record, active.dialect.as_ref(), active.statement_index, &mut active.pending);
+                                    }
+                                    if let Some(line) = active.stderr_buf.finish() {
+                                        feed_stderr_line(&line, active.dialect.as_ref(), &mut active.pending);
+                                    }
+                                    if active.pending.iter().any(|e| matches!(e, QueryEvent::Error { .. })) {
+                                        active.error_flag.store(true, Ordering::SeqCst);
+                                    }
+                                    active.pending.push_back(QueryEvent::Done {
+                                        statement_index: active.statement_index,
+                                        affected_rows: None,
+                                        is_last: active.is_last,
+                                    });
+                                    active.exited = true;
+                                    debug!(
+                                        statement_index = active.statement_index,
+                                        duration_ms = active.started_at.elapsed().as_millis() as u64,
+                                        "Statement finished"
+                                    );
+                                }
+                                Some(Err(e)) => {
+                                    active.error_flag.store(true, Ordering::SeqCst);
+                                    active.pending.push_back(QueryEvent::Error { message: e.to_string() });
+                                    active.pending.push_back(QueryEvent::Done {
+                                        statement_index: active.statement_index,
+                                        affected_rows: None,
+                                        is_last: active.is_last,
+                                    });
+                                    active.exited = true;
+                                }
+                            }
+                        }
+                    }
+
+                    state = StatementStreamState::Active(active);
+                }
+            }
+        }
+    })
+}
+
+/// Incremental line buffer: splits newly-arrived bytes into complete lines,
+/// carrying any trailing partial line (no terminating `\n` yet) over to the
+/// next push - the streaming equivalent of calling `str::lines()` once the
+/// whole output is in hand.
+#[derive(Default)]
+struct LineBuffer {
+    partial: String,
+}
+
+impl LineBuffer {
+    fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.partial.push_str(&String::from_utf8_lossy(bytes));
+        let mut lines = Vec::new();
+        while let Some(pos) = self.partial.find('\n') {
+            lines.push(self.partial[..pos].to_string());
+            self.partial.drain(..=pos);
         }
-        if dialect.is_error_line(line) {
-            events.push(QueryEvent::Error {
-                message: line.to_string(),
-            });
+        lines
+    }
+
+    /// Flush a trailing line left with no terminating `\n`, once the exec
+    /// stream has ended.
+    fn finish(&mut self) -> Option<String> {
+        if self.partial.is_empty() {
+            None
         } else {
-            // Warnings or notices
-            events.push(QueryEvent::Line {
-                text: line.to_string(),
-            });
+            Some(std::mem::take(&mut self.partial))
         }
     }
+}
+
+/// One CSV field as produced by `cli_command_csv`'s output, keeping track of
+/// whether the source quoted it. Quoting is the only type signal this reader
+/// has to go on - there's no per-column type metadata available from the CLI
+/// tools short of an extra `information_schema`/`sys.columns` round trip per
+/// statement, which is out of scope here - so a quoted field is always
+/// treated as text (see `csv_field_to_value`), matching the intuition that
+/// the source decided to quote it rather than leave it as a bare number.
+#[derive(Debug, Clone)]
+struct CsvField {
+    value: String,
+    quoted: bool,
+}
 
-    // Handle stdout (results)
-    let lines: Vec<&str> = stdout.lines().collect();
+/// Hand-rolled RFC-4180-style incremental CSV reader for `cli_command_csv`'s
+/// stdout. This tree has no package manifest to add a genuinely new
+/// dependency like the `csv` crate to (the same constraint `db::native_pool`
+/// documents for driver crates), so this hand-rolls just the subset of RFC
+/// 4180 the three dialects' CLI tools actually produce: a single-byte
+/// delimiter, `"`-quoted fields, and `""` as an escaped quote inside one.
+/// Parses a character at a time as bytes arrive, rather than buffering the
+/// whole statement's output before parsing it.
+#[derive(Default)]
+struct CsvRecordReader {
+    delimiter: u8,
+    in_quotes: bool,
+    pending_quote: bool,
+    field_quoted: bool,
+    field: String,
+    record: Vec<CsvField>,
+    complete: VecDeque<Vec<CsvField>>,
+}
 
-    if lines.is_empty() {
-        events.push(QueryEvent::Done { affected_rows: None });
-        return events;
+impl CsvRecordReader {
+    fn new(delimiter: u8) -> Self {
+        Self {
+            delimiter,
+            ..Self::default()
+        }
     }
 
-    // Check if first line looks like headers (tab-separated column names)
-    let mut line_iter = lines.iter().peekable();
+    fn feed_char(&mut self, c: char) {
+        if self.pending_quote {
+            self.pending_quote = false;
+            if c == '"' {
+                // `""` inside a quoted field is an escaped literal quote.
+                self.field.push('"');
+                return;
+            }
+            // A single `"` ended the quoted run; fall through and process
+            // `c` as if we were no longer inside quotes.
+            self.in_quotes = false;
+        }
 
-    while let Some(line) = line_iter.next() {
-        let line = line.trim();
+        if self.in_quotes {
+            if c == '"' {
+                self.pending_quote = true;
+            } else {
+                self.field.push(c);
+            }
+            return;
+        }
 
-        if line.is_empty() {
-            continue;
+        let delimiter = self.delimiter as char;
+        if c == '"' && self.field.is_empty() && !self.field_quoted {
+            self.in_quotes = true;
+            self.field_quoted = true;
+        } else if c == delimiter {
+            self.finish_field();
+        } else if c == '\n' {
+            self.finish_field();
+            self.finish_record();
+        } else if c == '\r' {
+            // Swallowed; CRLF line endings are handled by the '\n' arm.
+        } else {
+            self.field.push(c);
         }
+    }
 
-        // Check for result messages
-        if line.starts_with("Query OK")
-            || line.starts_with("Rows matched")
-            || line.contains("row(s) affected")
-            || line.contains("rows affected")
-        {
-            events.push(QueryEvent::Line {
-                text: line.to_string(),
-            });
-            continue;
+    fn finish_field(&mut self) {
+        self.record.push(CsvField {
+            value: std::mem::take(&mut self.field),
+            quoted: self.field_quoted,
+        });
+        self.field_quoted = false;
+    }
+
+    fn finish_record(&mut self) {
+        let record = std::mem::take(&mut self.record);
+        // A record consisting of one empty, unquoted field is a blank line
+        // separating result blocks, not a real (empty) row - mirrors the old
+        // tab-guessing parser's blank-line-ends-block behavior.
+        if record.len() == 1 && !record[0].quoted && record[0].value.is_empty() {
+            return;
         }
+        self.complete.push_back(record);
+    }
 
-        // Check for error lines in stdout
-        if dialect.is_error_line(line) {
-            events.push(QueryEvent::Error {
-                message: line.to_string(),
-            });
-            continue;
+    /// Feed newly-arrived bytes and drain any records completed by them.
+    fn push(&mut self, bytes: &[u8]) -> Vec<Vec<CsvField>> {
+        for c in String::from_utf8_lossy(bytes).chars() {
+            self.feed_char(c);
         }
+        self.complete.drain(..).collect()
+    }
 
-        // Try to parse as tab-separated data
-        if line.contains('\t') {
-            // This could be a header row or a data row
-            let columns: Vec<String> = line.split('\t').map(|s| s.to_string()).collect();
-
-            // Peek at next line to see if this is a header
-            if let Some(next_line) = line_iter.peek() {
-                let next_line = next_line.trim();
-                if next_line.contains('\t') || next_line.is_empty() {
-                    // This is likely a header, emit subsequent rows as records
-                    let header = columns.clone();
-
-                    // Skip separator lines (e.g., "---\t---\t---")
-                    while let Some(data_line) = line_iter.next() {
-                        let data_line = data_line.trim();
-                        if data_line.is_empty() {
-                            break;
-                        }
-                        if data_line.chars().all(|c| c == '-' || c == '\t' || c == '+' || c == ' ') {
-                            continue;
-                        }
+    /// Flush a trailing record left with no terminating newline, once the
+    /// exec stream has ended.
+    fn finish(&mut self) -> Option<Vec<CsvField>> {
+        self.pending_quote = false;
+        self.in_quotes = false;
+        if self.field.is_empty() && !self.field_quoted && self.record.is_empty() {
+            return None;
+        }
+        self.finish_field();
+        let record = std::mem::take(&mut self.record);
+        if record.len() == 1 && !record[0].quoted && record[0].value.is_empty() {
+            return None;
+        }
+        Some(record)
+    }
+}
 
-                        let values: Vec<JsonValue> = data_line
-                            .split('\t')
-                            .map(|s| parse_value(s.trim()))
-                            .collect();
+/// Whether a lone, unquoted field is a status/footer line rather than a real
+/// header - the CSV analogue of the old tab-guessing parser's checks for
+/// `mysql`'s "Query OK"/"rows affected" lines and Postgres's `(N rows)`
+/// footer, neither of which is wrapped in quotes by any dialect's CLI.
+fn is_status_line(value: &str, dialect: &dyn Dialect) -> bool {
+    let trimmed = value.trim();
+    trimmed.is_empty()
+        || dialect.is_error_line(trimmed)
+        || trimmed.starts_with("Query OK")
+        || trimmed.starts_with("Rows matched")
+        || trimmed.contains("row(s) affected")
+        || trimmed.contains("rows affected")
+        || (trimmed.starts_with('(') && trimmed.ends_with(')') && trimmed.contains("row"))
+}
 
-                        events.push(QueryEvent::Record {
-                            columns: header.clone(),
-                            row: values,
-                        });
-                    }
-                    continue;
+/// Feed one complete CSV record, pushing whatever events it produces onto
+/// `pending`. The first record for a statement becomes the column header
+/// unless it's a status/footer line (see `is_status_line`), so a statement
+/// that only ever produces status lines never mistakes one for a header.
+fn feed_csv_record(
+    header: &mut Option<Vec<String>>,
+    record: Vec<CsvField>,
+    dialect: &dyn Dialect,
+    statement_index: usize,
+    pending: &mut VecDeque<QueryEvent>,
+) {
+    if header.is_none() {
+        if let [field] = record.as_slice() {
+            if !field.quoted && is_status_line(&field.value, dialect) {
+                let trimmed = field.value.trim().to_string();
+                if dialect.is_error_line(&trimmed) {
+                    pending.push_back(QueryEvent::Error { message: trimmed });
+                } else {
+                    pending.push_back(QueryEvent::Line { text: trimmed });
                 }
+                return;
             }
-
-            // Single row without header context
-            events.push(QueryEvent::Line {
-                text: line.to_string(),
-            });
-        } else {
-            // Plain text line
-            events.push(QueryEvent::Line {
-                text: line.to_string(),
-            });
         }
+        *header = Some(record.into_iter().map(|f| f.value).collect());
+        return;
     }
 
-    events.push(QueryEvent::Done { affected_rows: None });
-    events
+    let columns = header.as_ref().expect("header set above").clone();
+    let row: Vec<JsonValue> = record.into_iter().map(csv_field_to_value).collect();
+    pending.push_back(QueryEvent::Record {
+        statement_index,
+        columns,
+        row,
+    });
 }
 
-/// Parse a string value into a JSON value
-fn parse_value(s: &str) -> JsonValue {
-    if s.eq_ignore_ascii_case("null") || s.is_empty() {
+/// Convert one CSV field into a JSON value. A quoted field is always text
+/// (see `CsvField`); an unquoted field is coerced to a number when it parses
+/// as one, `null` when blank, and left as a string otherwise. Unlike the old
+/// tab-guessing parser's `parse_value`, this makes no attempt to coerce
+/// "0"/"1"/"true"/"false" to booleans - that heuristic misread any genuine
+/// numeric/text `0`/`1` value as a boolean, which this CSV path drops.
+fn csv_field_to_value(field: CsvField) -> JsonValue {
+    if field.quoted {
+        return JsonValue::String(field.value);
+    }
+    if field.value.is_empty() {
         return JsonValue::Null;
     }
-
-    // Try integer
-    if let Ok(n) = s.parse::<i64>() {
+    if let Ok(n) = field.value.parse::<i64>() {
         return JsonValue::Number(n.into());
     }
-
-    // Try float
-    if let Ok(n) = s.parse::<f64>() {
+    if let Ok(n) = field.value.parse::<f64>() {
         if let Some(num) = serde_json::Number::from_f64(n) {
             return JsonValue::Number(num);
         }
     }
+    JsonValue::String(field.value)
+}
 
-    // Try boolean
-    if s.eq_ignore_ascii_case("true") || s == "1" {
-        return JsonValue::Bool(true);
+/// Feed one complete stderr line, classifying it as an error or an
+/// informational line the same way the old buffered parser did.
+fn feed_stderr_line(raw_line: &str, dialect: &dyn Dialect, pending: &mut VecDeque<QueryEvent>) {
+    let line = raw_line.trim_end_matches('\r').trim();
+    if line.is_empty() {
+        return;
     }
-    if s.eq_ignore_ascii_case("false") || s == "0" {
-        return JsonValue::Bool(false);
+    if dialect.is_error_line(line) {
+        pending.push_back(QueryEvent::Error { message: line.to_string() });
+    } else {
+        pending.push_back(QueryEvent::Line { text: line.to_string() });
     }
-
-    // Default to string
-    JsonValue::String(s.to_string())
 }
@@ -0,0 +1,118 @@
+/// Split a SQL script into individual statements on top-level `;`.
+///
+/// Tracks enough lexer state to avoid splitting inside single/double-quoted
+/// strings, `--` line comments, `/* */` block comments, and Postgres
+/// `$tag$ ... $tag$` dollar-quoting, so a semicolon embedded in any of those
+/// doesn't end a statement early. Blank statements (stray `;`, trailing
+/// whitespace) are dropped from the result.
+pub fn split_statements(sql: &str) -> Vec<String> {
+    enum State {
+        Normal,
+        SingleQuote,
+        DoubleQuote,
+        LineComment,
+        BlockComment,
+        DollarQuote,
+    }
+
+    let chars: Vec<char> = sql.chars().collect();
+    let mut state = State::Normal;
+    let mut dollar_tag = String::new();
+    let mut current = String::new();
+    let mut statements = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match state {
+            State::Normal => match c {
+                '\'' => {
+                    current.push(c);
+                    state = State::SingleQuote;
+                }
+                '"' => {
+                    current.push(c);
+                    state = State::DoubleQuote;
+                }
+                '-' if chars.get(i + 1) == Some(&'-') => {
+                    current.push(c);
+                    state = State::LineComment;
+                }
+                '/' if chars.get(i + 1) == Some(&'*') => {
+                    current.push(c);
+                    state = State::BlockComment;
+                }
+                '$' => {
+                    if let Some(tag) = dollar_tag_at(&chars, i) {
+                        current.push_str(&tag);
+                        i += tag.chars().count() - 1;
+                        dollar_tag = tag;
+                        state = State::DollarQuote;
+                    } else {
+                        current.push(c);
+                    }
+                }
+                ';' => {
+                    let statement = current.trim().to_string();
+                    if !statement.is_empty() {
+                        statements.push(statement);
+                    }
+                    current.clear();
+                }
+                _ => current.push(c),
+            },
+            State::SingleQuote => {
+                current.push(c);
+                if c == '\'' {
+                    state = State::Normal;
+                }
+            }
+            State::DoubleQuote => {
+                current.push(c);
+                if c == '"' {
+                    state = State::Normal;
+                }
+            }
+            State::LineComment => {
+                current.push(c);
+                if c == '\n' {
+                    state = State::Normal;
+                }
+            }
+            State::BlockComment => {
+                current.push(c);
+                if c == '/' && current.ends_with("*/") {
+                    state = State::Normal;
+                }
+            }
+            State::DollarQuote => {
+                current.push(c);
+                if c == '$' && current.ends_with(dollar_tag.as_str()) {
+                    state = State::Normal;
+                }
+            }
+        }
+        i += 1;
+    }
+
+    let statement = current.trim().to_string();
+    if !statement.is_empty() {
+        statements.push(statement);
+    }
+
+    statements
+}
+
+/// If `chars[i]` starts a `$tag$`-style dollar-quote delimiter (including the
+/// bare `$$`), return the full delimiter; otherwise `None`.
+pub(super) fn dollar_tag_at(chars: &[char], i: usize) -> Option<String> {
+    let mut j = i + 1;
+    while j < chars.len() && (chars[j].is_alphanumeric() || chars[j] == '_') {
+        j += 1;
+    }
+    if j < chars.len() && chars[j] == '$' {
+        Some(chars[i..=j].iter().collect())
+    } else {
+        None
+    }
+}
@@ -1,21 +1,105 @@
 use bollard::container::{
-    Config, CreateContainerOptions, RemoveContainerOptions, StartContainerOptions,
-    StopContainerOptions,
+    Config, CreateContainerOptions, DownloadFromContainerOptions, LogsOptions,
+    RemoveContainerOptions, StartContainerOptions, StatsOptions, StopContainerOptions,
+    UploadToContainerOptions,
 };
 use bollard::exec::{CreateExecOptions, StartExecResults};
 use bollard::image::CreateImageOptions;
 use bollard::models::{HostConfig, PortBinding};
 use bollard::Docker;
-use futures::StreamExt;
+use bytes::{Bytes, BytesMut};
+use futures::{Stream, StreamExt};
+use regex::Regex;
 use std::collections::HashMap;
-use std::time::Duration;
+use std::env;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio::sync::RwLock;
 use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
 
+/// Default cap on concurrent containers for an endpoint that doesn't set
+/// one explicitly - effectively unbounded.
+const DEFAULT_MAX_JOBS: usize = usize::MAX;
+
+/// One Docker daemon db-api can schedule containers onto, following
+/// butido's `Endpoint` model: a connection plus the scheduling knobs
+/// (a concurrency cap and an optional network mode) needed to spread load
+/// across more than one host.
+pub struct Endpoint {
+    pub name: String,
+    pub docker: Docker,
+    pub num_max_jobs: usize,
+    pub network_mode: Option<String>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Endpoint {
+    fn new(name: impl Into<String>, docker: Docker, num_max_jobs: usize, network_mode: Option<String>) -> Self {
+        Self {
+            name: name.into(),
+            docker,
+            num_max_jobs,
+            network_mode,
+            in_flight: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn local() -> Result<Self> {
+        Ok(Self::new(
+            "local",
+            Docker::connect_with_local_defaults()?,
+            DEFAULT_MAX_JOBS,
+            None,
+        ))
+    }
+
+    fn load(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.load() < self.num_max_jobs
+    }
+}
+
+/// How to decide a freshly started container is actually accepting
+/// connections, rather than just that the Docker daemon reports it started.
+/// Passed into `create_container`/`create_pool_container` so every dialect
+/// gets a reliable "ready" contract instead of racing the database's boot.
+pub enum WaitStrategy {
+    /// Follow the container's stdout/stderr until `pattern` has matched at
+    /// least `occurrences` times, or `timeout` elapses.
+    LogMatch {
+        pattern: Regex,
+        occurrences: usize,
+        timeout: Duration,
+    },
+    /// Run `cmd`/`args` inside the container every `interval` until it exits
+    /// 0, or `retries` attempts are exhausted.
+    ExecSucceeds {
+        cmd: String,
+        args: Vec<String>,
+        interval: Duration,
+        retries: u32,
+    },
+    /// Poll a TCP connect to `127.0.0.1:<host_port>` until it succeeds, or
+    /// `timeout` elapses.
+    PortListening { timeout: Duration },
+}
+
 pub struct DockerManager {
-    docker: Docker,
+    endpoints: Vec<Endpoint>,
+    /// Which endpoint a known container lives on, keyed by container id.
+    /// Populated on creation and lazily backfilled by `docker_for` for
+    /// containers this process didn't create (recovered after a restart).
+    container_endpoints: RwLock<HashMap<String, usize>>,
 }
 
 /// Output from a docker exec command
@@ -26,6 +110,85 @@ pub struct ExecOutput {
     pub exit_code: Option<i64>,
 }
 
+/// A piece of a streaming exec's output, forwarded as it arrives rather than
+/// buffered into an `ExecOutput`. `Exit` is always the final item.
+#[derive(Debug)]
+pub enum ExecChunk {
+    Stdout(Bytes),
+    Stderr(Bytes),
+    Exit(Option<i64>),
+}
+
+pub type BoxedExecStream = std::pin::Pin<Box<dyn Stream<Item = Result<ExecChunk>> + Send>>;
+
+/// Boxed stream of decoded log lines, returned by `follow_logs`
+pub type BoxedLogStream = std::pin::Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
+/// Build an in-memory tar archive containing a single file named `file_name`
+/// with `contents`, suitable for `copy_into_container`. Used to drop a
+/// `.sql`/`.dump` file into a container's filesystem without going through
+/// stdin, so the native restore tool can be invoked against it directly.
+pub fn build_file_tar(file_name: &str, contents: &[u8]) -> Result<Vec<u8>> {
+    let mut builder = tar::Builder::new(Vec::new());
+    let mut header = tar::Header::new_gnu();
+    header.set_size(contents.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder
+        .append_data(&mut header, file_name, contents)
+        .map_err(|e| AppError::RestoreFailed(format!("Failed to build tar archive: {}", e)))?;
+    builder
+        .into_inner()
+        .map_err(|e| AppError::RestoreFailed(format!("Failed to finalize tar archive: {}", e)))
+}
+
+/// Extract the first regular file's contents from an in-memory tar archive,
+/// as returned by `copy_from_container`.
+pub fn extract_file_from_tar(tar_bytes: &[u8]) -> Result<Bytes> {
+    let mut archive = tar::Archive::new(tar_bytes);
+    let entries = archive
+        .entries()
+        .map_err(|e| AppError::RestoreFailed(format!("Failed to read tar archive: {}", e)))?;
+
+    for entry in entries {
+        let mut entry =
+            entry.map_err(|e| AppError::RestoreFailed(format!("Failed to read tar entry: {}", e)))?;
+        if entry.header().entry_type().is_file() {
+            let mut buf = Vec::new();
+            entry.read_to_end(&mut buf).map_err(|e| {
+                AppError::RestoreFailed(format!("Failed to read tar entry contents: {}", e))
+            })?;
+            return Ok(Bytes::from(buf));
+        }
+    }
+
+    Err(AppError::RestoreFailed(
+        "Tar archive contained no file entries".to_string(),
+    ))
+}
+
+/// One-shot resource usage snapshot for a container, returned by
+/// `container_stats`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ContainerStats {
+    pub cpu_percent: f64,
+    pub memory_usage_bytes: u64,
+    pub memory_limit_bytes: u64,
+    pub network_rx_bytes: u64,
+    pub network_tx_bytes: u64,
+}
+
+/// A container's stats tagged with enough identity for an eviction policy
+/// to act on it, as returned by `stats_snapshot`.
+#[derive(Debug, Clone)]
+pub struct ContainerStatsEntry {
+    pub container_id: String,
+    pub is_pool: bool,
+    pub dialect: String,
+    pub is_running: bool,
+    pub stats: ContainerStats,
+}
+
 /// Discovered container info for recovery
 #[derive(Debug)]
 pub struct DiscoveredContainer {
@@ -37,6 +200,8 @@ pub struct DiscoveredContainer {
     pub db_password: String,
     pub host_port: u16,
     pub is_running: bool,
+    /// Name of the endpoint this container was found on
+    pub endpoint_name: String,
 }
 
 /// Discovered pool container info
@@ -46,20 +211,123 @@ pub struct DiscoveredPoolContainer {
     pub dialect: String,
     pub host_port: u16,
     pub is_running: bool,
+    /// Name of the endpoint this container was found on
+    pub endpoint_name: String,
 }
 
 impl DockerManager {
     pub fn new() -> Result<Self> {
-        let docker = Docker::connect_with_local_defaults()?;
-        Ok(Self { docker })
+        Ok(Self {
+            endpoints: vec![Endpoint::local()?],
+            container_endpoints: RwLock::new(HashMap::new()),
+        })
     }
 
+    /// Build the endpoint pool from the environment. The local daemon is
+    /// always included (so there's somewhere to land containers even if a
+    /// remote is full or unreachable); a second, remote endpoint is added
+    /// when `DOCKER_HOST` is set, following bollard's own conventions -
+    /// connecting over HTTP, or over TLS when `DOCKER_CERT_PATH` is also
+    /// set (expected to contain `key.pem`/`cert.pem`/`ca.pem`).
+    pub fn from_env() -> Result<Self> {
+        let mut endpoints = vec![Endpoint::local()?];
+
+        if let Ok(host) = env::var("DOCKER_HOST") {
+            let num_max_jobs = env::var("DOCKER_ENDPOINT_MAX_JOBS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(DEFAULT_MAX_JOBS);
+            let network_mode = env::var("DOCKER_ENDPOINT_NETWORK_MODE").ok();
+
+            let docker = match env::var("DOCKER_CERT_PATH") {
+                Ok(cert_path) => {
+                    let cert_dir = PathBuf::from(cert_path);
+                    Docker::connect_with_ssl(
+                        &host,
+                        &cert_dir.join("key.pem"),
+                        &cert_dir.join("cert.pem"),
+                        &cert_dir.join("ca.pem"),
+                        120,
+                        bollard::API_DEFAULT_VERSION,
+                    )?
+                }
+                Err(_) => Docker::connect_with_http(&host, 120, bollard::API_DEFAULT_VERSION)?,
+            };
+
+            info!("Added remote Docker endpoint {} (max jobs: {})", host, num_max_jobs);
+            endpoints.push(Endpoint::new(host, docker, num_max_jobs, network_mode));
+        }
+
+        Ok(Self {
+            endpoints,
+            container_endpoints: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Reachable only if every configured endpoint responds to a ping
     pub async fn health_check(&self) -> Result<bool> {
-        self.docker.ping().await?;
+        for endpoint in &self.endpoints {
+            endpoint.docker.ping().await?;
+        }
         Ok(true)
     }
 
+    /// Pick the least-loaded endpoint among those still under their
+    /// `num_max_jobs` cap, for scheduling a new container. Falls back to the
+    /// least-loaded endpoint overall if every endpoint is at capacity.
+    fn schedule(&self) -> usize {
+        self.endpoints
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.has_capacity())
+            .min_by_key(|(_, e)| e.load())
+            .or_else(|| self.endpoints.iter().enumerate().min_by_key(|(_, e)| e.load()))
+            .map(|(idx, _)| idx)
+            .expect("at least one Docker endpoint is always configured")
+    }
+
+    /// Resolve the endpoint a known container lives on, caching the lookup.
+    /// Falls back to probing every endpoint for containers this process
+    /// didn't itself create (e.g. recovered after a restart), so recovery
+    /// can reconnect without tracking endpoints separately.
+    async fn docker_for(&self, container_id: &str) -> Result<Docker> {
+        if let Some(&idx) = self.container_endpoints.read().await.get(container_id) {
+            return Ok(self.endpoints[idx].docker.clone());
+        }
+
+        for (idx, endpoint) in self.endpoints.iter().enumerate() {
+            if endpoint.docker.inspect_container(container_id, None).await.is_ok() {
+                self.container_endpoints
+                    .write()
+                    .await
+                    .insert(container_id.to_string(), idx);
+                return Ok(endpoint.docker.clone());
+            }
+        }
+
+        Err(AppError::Internal(format!(
+            "Container {} not found on any Docker endpoint",
+            container_id
+        )))
+    }
+
+    async fn register_container(&self, container_id: &str, endpoint_idx: usize) {
+        self.container_endpoints
+            .write()
+            .await
+            .insert(container_id.to_string(), endpoint_idx);
+    }
+
     pub async fn pull_image(&self, image: &str) -> Result<()> {
+        for endpoint in &self.endpoints {
+            Self::ensure_image_pulled(&endpoint.docker, image).await?;
+        }
+        Ok(())
+    }
+
+    /// Pull `image` on a specific endpoint, used when provisioning a
+    /// container there so the image doesn't need to already be cached
+    async fn ensure_image_pulled(docker: &Docker, image: &str) -> Result<()> {
         info!("Pulling image: {}", image);
 
         let options = CreateImageOptions {
@@ -67,7 +335,7 @@ impl DockerManager {
             ..Default::default()
         };
 
-        let mut stream = self.docker.create_image(Some(options), None, None);
+        let mut stream = docker.create_image(Some(options), None, None);
 
         while let Some(result) = stream.next().await {
             match result {
@@ -86,6 +354,88 @@ impl DockerManager {
         Ok(())
     }
 
+    /// Block until `strategy` reports the container ready, destroying the
+    /// half-started container and returning `AppError::QueryTimeout` if it
+    /// never does.
+    async fn wait_until_ready(
+        &self,
+        container_id: &str,
+        host_port: u16,
+        strategy: &WaitStrategy,
+    ) -> Result<()> {
+        let ready = match strategy {
+            WaitStrategy::LogMatch {
+                pattern,
+                occurrences,
+                timeout,
+            } => match self.follow_logs(container_id, None).await {
+                Ok(mut stream) => {
+                    let wait = async {
+                        let mut seen = 0usize;
+                        while let Some(line) = stream.next().await {
+                            let line = line?;
+                            if pattern.is_match(&line) {
+                                seen += 1;
+                                if seen >= *occurrences {
+                                    return Ok(());
+                                }
+                            }
+                        }
+                        Err(AppError::QueryTimeout)
+                    };
+                    matches!(tokio::time::timeout(*timeout, wait).await, Ok(Ok(())))
+                }
+                Err(_) => false,
+            },
+            WaitStrategy::ExecSucceeds {
+                cmd,
+                args,
+                interval,
+                retries,
+            } => {
+                let mut ready = false;
+                for attempt in 0..*retries {
+                    match self.exec(container_id, cmd, args, &[]).await {
+                        Ok(output) if output.exit_code == Some(0) => {
+                            ready = true;
+                            break;
+                        }
+                        Ok(output) => debug!(
+                            "Readiness exec attempt {} failed with exit code {:?}: {}",
+                            attempt, output.exit_code, output.stderr
+                        ),
+                        Err(e) => debug!("Readiness exec attempt {} errored: {}", attempt, e),
+                    }
+                    tokio::time::sleep(*interval).await;
+                }
+                ready
+            }
+            WaitStrategy::PortListening { timeout } => {
+                let start = Instant::now();
+                let mut ready = false;
+                while start.elapsed() < *timeout {
+                    if TcpStream::connect(("127.0.0.1", host_port)).await.is_ok() {
+                        ready = true;
+                        break;
+                    }
+                    tokio::time::sleep(Duration::from_millis(200)).await;
+                }
+                ready
+            }
+        };
+
+        if ready {
+            Ok(())
+        } else {
+            warn!(
+                "Container {} did not become ready in time, destroying it",
+                container_id
+            );
+            let _ = self.destroy_container(container_id).await;
+            Err(AppError::QueryTimeout)
+        }
+    }
+
     pub async fn create_container(
         &self,
         db_id: Uuid,
@@ -94,12 +444,18 @@ impl DockerManager {
         container_port: u16,
         memory_limit_mb: u32,
         labels: HashMap<String, String>,
+        wait_strategy: Option<WaitStrategy>,
     ) -> Result<(String, u16)> {
         let container_name = format!("db-api-{}", db_id);
 
+        let endpoint_idx = self.schedule();
+        let endpoint = &self.endpoints[endpoint_idx];
+        let docker = endpoint.docker.clone();
+        info!("Scheduling container {} on endpoint {}", container_name, endpoint.name);
+
         // Check if image exists locally, pull if not
-        if self.docker.inspect_image(image).await.is_err() {
-            self.pull_image(image).await?;
+        if docker.inspect_image(image).await.is_err() {
+            Self::ensure_image_pulled(&docker, image).await?;
         }
 
         let env: Vec<String> = env_vars
@@ -140,20 +496,20 @@ impl DockerManager {
             platform: None,
         };
 
-        let response = self.docker.create_container(Some(options), config).await?;
+        let response = docker.create_container(Some(options), config).await?;
         let container_id = response.id;
 
         info!("Created container: {} ({})", container_name, container_id);
 
         // Start the container
-        self.docker
+        docker
             .start_container(&container_id, None::<StartContainerOptions<String>>)
             .await?;
 
         info!("Started container: {}", container_id);
 
         // Get the assigned host port
-        let inspect = self.docker.inspect_container(&container_id, None).await?;
+        let inspect = docker.inspect_container(&container_id, None).await?;
         let host_port = inspect
             .network_settings
             .and_then(|ns| ns.ports)
@@ -166,6 +522,13 @@ impl DockerManager {
 
         info!("Container {} mapped to host port {}", container_id, host_port);
 
+        endpoint.in_flight.fetch_add(1, Ordering::Relaxed);
+        self.register_container(&container_id, endpoint_idx).await;
+
+        if let Some(strategy) = &wait_strategy {
+            self.wait_until_ready(&container_id, host_port, strategy).await?;
+        }
+
         Ok((container_id, host_port))
     }
 
@@ -177,15 +540,156 @@ impl DockerManager {
         args: &[String],
         env: &[(String, String)],
     ) -> Result<ExecOutput> {
+        let mut stream = self.exec_stream(container_id, cmd, args, env).await?;
+
+        let mut stdout = String::new();
+        let mut stderr = String::new();
+        let mut exit_code = None;
+
+        while let Some(chunk) = stream.next().await {
+            match chunk? {
+                ExecChunk::Stdout(bytes) => stdout.push_str(&String::from_utf8_lossy(&bytes)),
+                ExecChunk::Stderr(bytes) => stderr.push_str(&String::from_utf8_lossy(&bytes)),
+                ExecChunk::Exit(code) => exit_code = code,
+            }
+        }
+
+        debug!(
+            "Exec completed with exit code {:?}, stdout len: {}, stderr len: {}",
+            exit_code,
+            stdout.len(),
+            stderr.len()
+        );
+
+        Ok(ExecOutput {
+            stdout,
+            stderr,
+            exit_code,
+        })
+    }
+
+    /// Execute a command and stream its output as it arrives instead of
+    /// buffering it, for long-running execs (large restores, big `SELECT`
+    /// dumps) that would otherwise hold the whole output in memory and give
+    /// no progress feedback. The stream's final item is always `ExecChunk::Exit`.
+    pub async fn exec_stream(
+        &self,
+        container_id: &str,
+        cmd: &str,
+        args: &[String],
+        env: &[(String, String)],
+    ) -> Result<BoxedExecStream> {
+        let mut full_cmd = vec![cmd.to_string()];
+        full_cmd.extend(args.iter().cloned());
+
+        debug!("Executing (streaming) in container {}: {:?}", container_id, full_cmd);
+
+        let env_vars: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+
+        let exec_options = CreateExecOptions {
+            cmd: Some(full_cmd),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            env: if env_vars.is_empty() {
+                None
+            } else {
+                Some(env_vars)
+            },
+            ..Default::default()
+        };
+
+        let docker = self.docker_for(container_id).await?;
+        let exec = docker.create_exec(container_id, exec_options).await?;
+        let start_result = docker.start_exec(&exec.id, None).await?;
+
+        let output = match start_result {
+            StartExecResults::Attached { output, .. } => output,
+            StartExecResults::Detached => {
+                return Ok(Box::pin(futures::stream::once(async {
+                    Ok(ExecChunk::Exit(None))
+                })));
+            }
+        };
+
+        let exec_id = exec.id.clone();
+        let state = (output, docker, exec_id, false);
+
+        Ok(Box::pin(futures::stream::unfold(
+            state,
+            |(mut output, docker, exec_id, exited)| async move {
+                if exited {
+                    return None;
+                }
+
+                loop {
+                    match output.next().await {
+                        Some(Ok(bollard::container::LogOutput::StdOut { message })) => {
+                            return Some((Ok(ExecChunk::Stdout(message)), (output, docker, exec_id, false)));
+                        }
+                        Some(Ok(bollard::container::LogOutput::StdErr { message })) => {
+                            return Some((Ok(ExecChunk::Stderr(message)), (output, docker, exec_id, false)));
+                        }
+                        Some(Ok(_)) => continue,
+                        Some(Err(e)) => {
+                            return Some((Err(AppError::Docker(e)), (output, docker, exec_id, false)));
+                        }
+                        None => {
+                            let exit_code = docker
+                                .inspect_exec(&exec_id)
+                                .await
+                                .ok()
+                                .and_then(|i| i.exit_code);
+                            return Some((Ok(ExecChunk::Exit(exit_code)), (output, docker, exec_id, true)));
+                        }
+                    }
+                }
+            },
+        )))
+    }
+
+    /// Execute a command with timeout
+    pub async fn exec_with_timeout(
+        &self,
+        container_id: &str,
+        cmd: &str,
+        args: &[String],
+        env: &[(String, String)],
+        timeout: Duration,
+    ) -> Result<ExecOutput> {
+        match tokio::time::timeout(timeout, self.exec(container_id, cmd, args, env)).await {
+            Ok(result) => result,
+            Err(_) => Err(AppError::QueryTimeout),
+        }
+    }
+
+    /// Execute a command with stdin data piped in
+    /// Used for database restore operations where SQL is piped to the client
+    pub async fn exec_with_stdin(
+        &self,
+        container_id: &str,
+        cmd: &str,
+        args: &[String],
+        env: &[(String, String)],
+        stdin_data: &[u8],
+    ) -> Result<ExecOutput> {
+        use bollard::exec::StartExecOptions;
+        use tokio::io::AsyncWriteExt;
+
         let mut full_cmd = vec![cmd.to_string()];
         full_cmd.extend(args.iter().cloned());
 
-        debug!("Executing in container {}: {:?}", container_id, full_cmd);
+        debug!(
+            "Executing with stdin in container {}: {:?} ({} bytes)",
+            container_id,
+            full_cmd,
+            stdin_data.len()
+        );
 
         let env_vars: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
 
         let exec_options = CreateExecOptions {
             cmd: Some(full_cmd),
+            attach_stdin: Some(true),
             attach_stdout: Some(true),
             attach_stderr: Some(true),
             env: if env_vars.is_empty() {
@@ -196,14 +700,33 @@ impl DockerManager {
             ..Default::default()
         };
 
-        let exec = self.docker.create_exec(container_id, exec_options).await?;
+        let docker = self.docker_for(container_id).await?;
+        let exec = docker.create_exec(container_id, exec_options).await?;
 
-        let start_result = self.docker.start_exec(&exec.id, None).await?;
+        let start_options = StartExecOptions {
+            detach: false,
+            ..Default::default()
+        };
+
+        let start_result = docker.start_exec(&exec.id, Some(start_options)).await?;
 
         let mut stdout = String::new();
         let mut stderr = String::new();
 
-        if let StartExecResults::Attached { mut output, .. } = start_result {
+        if let StartExecResults::Attached {
+            mut output,
+            mut input,
+        } = start_result
+        {
+            // Write stdin data
+            input.write_all(stdin_data).await.map_err(|e| {
+                AppError::RestoreFailed(format!("Failed to write stdin: {}", e))
+            })?;
+            input.shutdown().await.map_err(|e| {
+                AppError::RestoreFailed(format!("Failed to close stdin: {}", e))
+            })?;
+
+            // Read output
             while let Some(msg) = output.next().await {
                 match msg {
                     Ok(bollard::container::LogOutput::StdOut { message }) => {
@@ -221,11 +744,11 @@ impl DockerManager {
         }
 
         // Get exit code
-        let inspect = self.docker.inspect_exec(&exec.id).await?;
+        let inspect = docker.inspect_exec(&exec.id).await?;
         let exit_code = inspect.exit_code;
 
         debug!(
-            "Exec completed with exit code {:?}, stdout len: {}, stderr len: {}",
+            "Exec with stdin completed with exit code {:?}, stdout len: {}, stderr len: {}",
             exit_code,
             stdout.len(),
             stderr.len()
@@ -238,31 +761,82 @@ impl DockerManager {
         })
     }
 
-    /// Execute a command with timeout
-    pub async fn exec_with_timeout(
+    /// Execute a command and stream its stdout as a sequence of byte chunks
+    /// instead of buffering the whole output in memory. Stderr is logged and
+    /// discarded. Used for dump commands whose output is piped straight into
+    /// a backup upload.
+    pub async fn exec_stdout_stream(
         &self,
         container_id: &str,
         cmd: &str,
         args: &[String],
         env: &[(String, String)],
-        timeout: Duration,
-    ) -> Result<ExecOutput> {
-        match tokio::time::timeout(timeout, self.exec(container_id, cmd, args, env)).await {
-            Ok(result) => result,
-            Err(_) => Err(AppError::QueryTimeout),
-        }
+    ) -> Result<impl Stream<Item = Result<Bytes>>> {
+        let mut full_cmd = vec![cmd.to_string()];
+        full_cmd.extend(args.iter().cloned());
+
+        debug!(
+            "Executing (streaming) in container {}: {:?}",
+            container_id, full_cmd
+        );
+
+        let env_vars: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+
+        let exec_options = CreateExecOptions {
+            cmd: Some(full_cmd),
+            attach_stdout: Some(true),
+            attach_stderr: Some(true),
+            env: if env_vars.is_empty() {
+                None
+            } else {
+                Some(env_vars)
+            },
+            ..Default::default()
+        };
+
+        let docker = self.docker_for(container_id).await?;
+        let exec = docker.create_exec(container_id, exec_options).await?;
+        let start_result = docker.start_exec(&exec.id, None).await?;
+
+        let output = match start_result {
+            StartExecResults::Attached { output, .. } => output,
+            StartExecResults::Detached => {
+                return Err(AppError::Internal(
+                    "exec started detached unexpectedly".to_string(),
+                ));
+            }
+        };
+
+        Ok(output.filter_map(|msg| async move {
+            match msg {
+                Ok(bollard::container::LogOutput::StdOut { message }) => Some(Ok(message)),
+                Ok(bollard::container::LogOutput::StdErr { message }) => {
+                    warn!(
+                        "stderr during streamed exec: {}",
+                        String::from_utf8_lossy(&message)
+                    );
+                    None
+                }
+                Ok(_) => None,
+                Err(e) => Some(Err(AppError::Docker(e))),
+            }
+        }))
     }
 
-    /// Execute a command with stdin data piped in
-    /// Used for database restore operations where SQL is piped to the client
-    pub async fn exec_with_stdin(
+    /// Execute a command, writing stdin from a stream of byte chunks instead
+    /// of a single buffered slice. Used for restore commands fed directly
+    /// from a streaming backup download.
+    pub async fn exec_with_stdin_stream<S>(
         &self,
         container_id: &str,
         cmd: &str,
         args: &[String],
         env: &[(String, String)],
-        stdin_data: &[u8],
-    ) -> Result<ExecOutput> {
+        mut stdin: S,
+    ) -> Result<ExecOutput>
+    where
+        S: Stream<Item = Result<Bytes>> + Unpin,
+    {
         use bollard::exec::StartExecOptions;
         use tokio::io::AsyncWriteExt;
 
@@ -270,10 +844,8 @@ impl DockerManager {
         full_cmd.extend(args.iter().cloned());
 
         debug!(
-            "Executing with stdin in container {}: {:?} ({} bytes)",
-            container_id,
-            full_cmd,
-            stdin_data.len()
+            "Executing with streamed stdin in container {}: {:?}",
+            container_id, full_cmd
         );
 
         let env_vars: Vec<String> = env.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
@@ -291,17 +863,15 @@ impl DockerManager {
             ..Default::default()
         };
 
-        let exec = self.docker.create_exec(container_id, exec_options).await?;
+        let docker = self.docker_for(container_id).await?;
+        let exec = docker.create_exec(container_id, exec_options).await?;
 
         let start_options = StartExecOptions {
             detach: false,
             ..Default::default()
         };
 
-        let start_result = self
-            .docker
-            .start_exec(&exec.id, Some(start_options))
-            .await?;
+        let start_result = docker.start_exec(&exec.id, Some(start_options)).await?;
 
         let mut stdout = String::new();
         let mut stderr = String::new();
@@ -311,10 +881,13 @@ impl DockerManager {
             mut input,
         } = start_result
         {
-            // Write stdin data
-            input.write_all(stdin_data).await.map_err(|e| {
-                AppError::RestoreFailed(format!("Failed to write stdin: {}", e))
-            })?;
+            // Write stdin chunks as they arrive from the source stream
+            while let Some(chunk) = stdin.next().await {
+                let chunk = chunk?;
+                input.write_all(&chunk).await.map_err(|e| {
+                    AppError::RestoreFailed(format!("Failed to write stdin: {}", e))
+                })?;
+            }
             input.shutdown().await.map_err(|e| {
                 AppError::RestoreFailed(format!("Failed to close stdin: {}", e))
             })?;
@@ -337,11 +910,11 @@ impl DockerManager {
         }
 
         // Get exit code
-        let inspect = self.docker.inspect_exec(&exec.id).await?;
+        let inspect = docker.inspect_exec(&exec.id).await?;
         let exit_code = inspect.exit_code;
 
         debug!(
-            "Exec with stdin completed with exit code {:?}, stdout len: {}, stderr len: {}",
+            "Exec with streamed stdin completed with exit code {:?}, stdout len: {}, stderr len: {}",
             exit_code,
             stdout.len(),
             stderr.len()
@@ -357,9 +930,10 @@ impl DockerManager {
     pub async fn stop_container(&self, container_id: &str) -> Result<()> {
         info!("Stopping container: {}", container_id);
 
+        let docker = self.docker_for(container_id).await?;
         let options = StopContainerOptions { t: 10 };
 
-        match self.docker.stop_container(container_id, Some(options)).await {
+        match docker.stop_container(container_id, Some(options)).await {
             Ok(_) => {
                 info!("Stopped container: {}", container_id);
                 Ok(())
@@ -378,15 +952,14 @@ impl DockerManager {
     pub async fn remove_container(&self, container_id: &str) -> Result<()> {
         info!("Removing container: {}", container_id);
 
+        let docker = self.docker_for(container_id).await?;
         let options = RemoveContainerOptions {
             force: true,
             v: true, // Remove volumes
             ..Default::default()
         };
 
-        self.docker
-            .remove_container(container_id, Some(options))
-            .await?;
+        docker.remove_container(container_id, Some(options)).await?;
 
         info!("Removed container: {}", container_id);
         Ok(())
@@ -395,18 +968,222 @@ impl DockerManager {
     pub async fn destroy_container(&self, container_id: &str) -> Result<()> {
         // Stop first, then remove
         let _ = self.stop_container(container_id).await;
-        self.remove_container(container_id).await
+        let result = self.remove_container(container_id).await;
+
+        if let Some(idx) = self.container_endpoints.write().await.remove(container_id) {
+            self.endpoints[idx].in_flight.fetch_sub(1, Ordering::Relaxed);
+        }
+
+        result
     }
 
     /// Check if container is running
     pub async fn is_running(&self, container_id: &str) -> Result<bool> {
-        let inspect = self.docker.inspect_container(container_id, None).await?;
+        let docker = self.docker_for(container_id).await?;
+        let inspect = docker.inspect_container(container_id, None).await?;
         Ok(inspect.state.and_then(|s| s.running).unwrap_or(false))
     }
 
     /// Check if a container exists (running or not)
     pub async fn container_exists(&self, container_id: &str) -> bool {
-        self.docker.inspect_container(container_id, None).await.is_ok()
+        self.docker_for(container_id).await.is_ok()
+    }
+
+    /// Follow a container's stdout/stderr as a stream of decoded log lines
+    ///
+    /// When `tail` is `Some(n)`, the stream is seeded with the last `n` lines
+    /// before switching to live tailing.
+    pub async fn follow_logs(
+        &self,
+        container_id: &str,
+        tail: Option<usize>,
+    ) -> Result<BoxedLogStream> {
+        let docker = self.docker_for(container_id).await?;
+        let options = LogsOptions::<String> {
+            follow: true,
+            stdout: true,
+            stderr: true,
+            tail: tail.map(|n| n.to_string()).unwrap_or_else(|| "all".to_string()),
+            ..Default::default()
+        };
+
+        Ok(Box::pin(docker.logs(container_id, Some(options)).map(
+            |result| match result {
+                Ok(log) => Ok(log.to_string()),
+                Err(e) => Err(AppError::Docker(e)),
+            },
+        )))
+    }
+
+    /// One-shot resource usage for a running container, via bollard's
+    /// non-streaming stats endpoint. `cpu_percent` is best-effort: a
+    /// one-shot sample has no prior tick to delta against, so Docker
+    /// typically reports a zeroed `precpu_stats` and this comes back `0.0`.
+    /// Cheap enough to call once per container on every `/metrics` scrape;
+    /// see `stats` for an accurate single-container alternative.
+    pub async fn container_stats(&self, container_id: &str) -> Result<ContainerStats> {
+        let docker = self.docker_for(container_id).await?;
+        let options = StatsOptions {
+            stream: false,
+            one_shot: true,
+        };
+
+        let stats = docker
+            .stats(container_id, Some(options))
+            .next()
+            .await
+            .ok_or_else(|| AppError::Internal(format!("No stats returned for container {}", container_id)))??;
+
+        Ok(Self::decode_stats(&stats))
+    }
+
+    /// Resource usage for a single container via two ticks of Docker's
+    /// streaming stats endpoint. The stream's first frame always has a
+    /// zeroed `precpu_stats` (nothing to diff against yet, same limitation
+    /// `container_stats` has with one shot); taking the second frame
+    /// instead - whose `precpu_stats` carries over from the first - gives a
+    /// real `cpu_percent` at the cost of waiting for Docker's ~1s sampling
+    /// tick. Meant for on-demand single-instance lookups, not a sweep over
+    /// every container.
+    pub async fn stats(&self, container_id: &str) -> Result<ContainerStats> {
+        let docker = self.docker_for(container_id).await?;
+        let options = StatsOptions {
+            stream: true,
+            one_shot: false,
+        };
+
+        let mut stream = docker.stats(container_id, Some(options));
+
+        let _first = stream
+            .next()
+            .await
+            .ok_or_else(|| AppError::Internal(format!("No stats returned for container {}", container_id)))??;
+        let second = stream
+            .next()
+            .await
+            .ok_or_else(|| AppError::Internal(format!("No second stats frame for container {}", container_id)))??;
+
+        Ok(Self::decode_stats(&second))
+    }
+
+    /// Shared CPU/memory/network decode for a single bollard stats frame,
+    /// used by both `container_stats` (one-shot) and `stats` (streaming).
+    fn decode_stats(stats: &bollard::container::Stats) -> ContainerStats {
+        let cpu_delta = stats
+            .cpu_stats
+            .cpu_usage
+            .total_usage
+            .saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+        let system_delta = stats
+            .cpu_stats
+            .system_cpu_usage
+            .unwrap_or(0)
+            .saturating_sub(stats.precpu_stats.system_cpu_usage.unwrap_or(0));
+        let online_cpus = (stats.cpu_stats.online_cpus.unwrap_or(1).max(1)) as f64;
+
+        let cpu_percent = if system_delta > 0 && cpu_delta > 0 {
+            (cpu_delta as f64 / system_delta as f64) * online_cpus * 100.0
+        } else {
+            0.0
+        };
+
+        let (network_rx_bytes, network_tx_bytes) = stats
+            .networks
+            .clone()
+            .unwrap_or_default()
+            .values()
+            .fold((0u64, 0u64), |(rx, tx), n| (rx + n.rx_bytes, tx + n.tx_bytes));
+
+        ContainerStats {
+            cpu_percent,
+            memory_usage_bytes: stats.memory_stats.usage.unwrap_or(0),
+            memory_limit_bytes: stats.memory_stats.limit.unwrap_or(0),
+            network_rx_bytes,
+            network_tx_bytes,
+        }
+    }
+
+    /// Resource usage for every known db-api and pool container across all
+    /// endpoints, for admission control / eviction policies upstream to act
+    /// on before calling `create_container`. Stopped containers are
+    /// included with zeroed stats so callers see the full fleet, not just
+    /// what's currently running.
+    pub async fn stats_snapshot(&self) -> Result<Vec<ContainerStatsEntry>> {
+        let db_containers = self.list_db_containers().await?;
+        let pool_containers = self.list_pool_containers().await?;
+
+        let mut entries = Vec::with_capacity(db_containers.len() + pool_containers.len());
+
+        for c in db_containers {
+            let stats = if c.is_running {
+                self.container_stats(&c.container_id).await.unwrap_or_default()
+            } else {
+                ContainerStats::default()
+            };
+            entries.push(ContainerStatsEntry {
+                container_id: c.container_id,
+                is_pool: false,
+                dialect: c.dialect,
+                is_running: c.is_running,
+                stats,
+            });
+        }
+
+        for c in pool_containers {
+            let stats = if c.is_running {
+                self.container_stats(&c.container_id).await.unwrap_or_default()
+            } else {
+                ContainerStats::default()
+            };
+            entries.push(ContainerStatsEntry {
+                container_id: c.container_id,
+                is_pool: true,
+                dialect: c.dialect,
+                is_running: c.is_running,
+                stats,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Upload an in-memory tar archive into a container's filesystem, rooted
+    /// at `dest_path`. Used to land a physical dump file (binary or
+    /// multi-file) before invoking the dialect's native restore tool against
+    /// it, instead of streaming SQL through `exec_with_stdin`.
+    pub async fn copy_into_container(
+        &self,
+        container_id: &str,
+        dest_path: &str,
+        tar_bytes: Vec<u8>,
+    ) -> Result<()> {
+        let docker = self.docker_for(container_id).await?;
+        let options = UploadToContainerOptions {
+            path: dest_path,
+            ..Default::default()
+        };
+
+        docker
+            .upload_to_container(container_id, Some(options), tar_bytes.into())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Download `src_path` (a file or directory) out of a container as an
+    /// in-memory tar archive. Used to pull physical-file backup formats
+    /// (e.g. Postgres custom-format dumps) out for upload to backup storage.
+    pub async fn copy_from_container(&self, container_id: &str, src_path: &str) -> Result<Bytes> {
+        let docker = self.docker_for(container_id).await?;
+        let options = DownloadFromContainerOptions { path: src_path };
+
+        let mut stream = docker.download_from_container(container_id, Some(options));
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = stream.next().await {
+            buf.extend_from_slice(&chunk?);
+        }
+
+        Ok(buf.freeze())
     }
 
     /// Create a pool container for a dialect
@@ -417,12 +1194,18 @@ impl DockerManager {
         env_vars: Vec<(String, String)>,
         container_port: u16,
         memory_limit_mb: u32,
+        wait_strategy: Option<WaitStrategy>,
     ) -> Result<(String, u16)> {
         let container_name = format!("db-api-pool-{}", dialect_name);
 
+        let endpoint_idx = self.schedule();
+        let endpoint = &self.endpoints[endpoint_idx];
+        let docker = endpoint.docker.clone();
+        info!("Scheduling pool container {} on endpoint {}", container_name, endpoint.name);
+
         // Check if image exists locally, pull if not
-        if self.docker.inspect_image(image).await.is_err() {
-            self.pull_image(image).await?;
+        if docker.inspect_image(image).await.is_err() {
+            Self::ensure_image_pulled(&docker, image).await?;
         }
 
         let env: Vec<String> = env_vars
@@ -469,20 +1252,20 @@ impl DockerManager {
             platform: None,
         };
 
-        let response = self.docker.create_container(Some(options), config).await?;
+        let response = docker.create_container(Some(options), config).await?;
         let container_id = response.id;
 
         info!("Created pool container: {} ({})", container_name, container_id);
 
         // Start the container
-        self.docker
+        docker
             .start_container(&container_id, None::<StartContainerOptions<String>>)
             .await?;
 
         info!("Started pool container: {}", container_id);
 
         // Get the assigned host port
-        let inspect = self.docker.inspect_container(&container_id, None).await?;
+        let inspect = docker.inspect_container(&container_id, None).await?;
         let host_port = inspect
             .network_settings
             .and_then(|ns| ns.ports)
@@ -495,174 +1278,260 @@ impl DockerManager {
 
         info!("Pool container {} mapped to host port {}", container_id, host_port);
 
+        endpoint.in_flight.fetch_add(1, Ordering::Relaxed);
+        self.register_container(&container_id, endpoint_idx).await;
+
+        if let Some(strategy) = &wait_strategy {
+            self.wait_until_ready(&container_id, host_port, strategy).await?;
+        }
+
         Ok((container_id, host_port))
     }
 
-    /// List all db-api pool containers
-    pub async fn list_pool_containers(&self) -> Result<Vec<DiscoveredPoolContainer>> {
-        use bollard::container::ListContainersOptions;
-
-        let mut filters = HashMap::new();
-        filters.insert("name", vec!["db-api-pool-"]);
+    /// Install SIGTERM/SIGINT handlers and, on signal, stop every db-api
+    /// container across every endpoint so a crash or `docker stop` of the
+    /// api doesn't leave them orphaned (the exact scenario
+    /// `recover_existing_instances` exists to paper over). When
+    /// `preserve_on_exit` is true, containers are only stopped so the next
+    /// process can recover them; otherwise they're destroyed outright, for
+    /// ephemeral deployments where nothing should outlive this process.
+    pub async fn shutdown_handler(self: Arc<Self>, preserve_on_exit: bool) {
+        let ctrl_c = async {
+            tokio::signal::ctrl_c()
+                .await
+                .expect("Failed to install Ctrl+C handler");
+        };
 
-        let options = ListContainersOptions {
-            all: true,
-            filters,
-            ..Default::default()
+        #[cfg(unix)]
+        let terminate = async {
+            tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                .expect("Failed to install SIGTERM handler")
+                .recv()
+                .await;
         };
 
-        let containers = self.docker.list_containers(Some(options)).await?;
-        let mut result = Vec::new();
+        #[cfg(not(unix))]
+        let terminate = std::future::pending::<()>();
 
-        for container in containers {
-            let container_id = match &container.id {
-                Some(id) => id.clone(),
-                None => continue,
-            };
+        tokio::select! {
+            _ = ctrl_c => {}
+            _ = terminate => {}
+        }
 
-            let inspect = match self.docker.inspect_container(&container_id, None).await {
-                Ok(i) => i,
-                Err(e) => {
-                    warn!("Failed to inspect pool container {}: {}", container_id, e);
-                    continue;
+        info!(
+            "Shutdown signal received, {} db-api containers",
+            if preserve_on_exit { "stopping" } else { "tearing down" }
+        );
+
+        let db_containers = self.list_db_containers().await.unwrap_or_default();
+        let pool_containers = self.list_pool_containers().await.unwrap_or_default();
+
+        let container_ids: Vec<String> = db_containers
+            .into_iter()
+            .map(|c| c.container_id)
+            .chain(pool_containers.into_iter().map(|c| c.container_id))
+            .collect();
+
+        const MAX_CONCURRENT_SHUTDOWNS: usize = 8;
+        futures::stream::iter(container_ids)
+            .for_each_concurrent(MAX_CONCURRENT_SHUTDOWNS, |container_id| {
+                let manager = self.clone();
+                async move {
+                    let result = if preserve_on_exit {
+                        manager.stop_container(&container_id).await
+                    } else {
+                        manager.destroy_container(&container_id).await
+                    };
+                    if let Err(e) = result {
+                        warn!("Failed to shut down container {}: {}", container_id, e);
+                    }
                 }
-            };
+            })
+            .await;
+    }
 
-            let labels = inspect.config.as_ref().and_then(|c| c.labels.as_ref());
+    /// List all db-api pool containers across every endpoint, tagging each
+    /// with its originating endpoint and registering it in the endpoint
+    /// cache so later per-container calls resolve directly instead of
+    /// falling back to `docker_for`'s probe-all-endpoints path.
+    pub async fn list_pool_containers(&self) -> Result<Vec<DiscoveredPoolContainer>> {
+        use bollard::container::ListContainersOptions;
 
-            // Check if it's a pool container
-            let is_pool = labels
-                .and_then(|l| l.get("db-api.pool"))
-                .map(|v| v == "true")
-                .unwrap_or(false);
+        let mut result = Vec::new();
 
-            if !is_pool {
-                continue;
-            }
+        for (endpoint_idx, endpoint) in self.endpoints.iter().enumerate() {
+            let mut filters = HashMap::new();
+            filters.insert("name", vec!["db-api-pool-"]);
 
-            let dialect = match labels.and_then(|l| l.get("db-api.dialect")) {
-                Some(d) => d.clone(),
-                None => continue,
+            let options = ListContainersOptions {
+                all: true,
+                filters,
+                ..Default::default()
             };
 
-            let container_port_str = labels.and_then(|l| l.get("db-api.container_port"));
-            let container_port: u16 = container_port_str
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(3306);
-
-            let host_port = inspect
-                .network_settings
-                .as_ref()
-                .and_then(|ns| ns.ports.as_ref())
-                .and_then(|ports| ports.get(&format!("{}/tcp", container_port)))
-                .and_then(|bindings| bindings.as_ref())
-                .and_then(|bindings| bindings.first())
-                .and_then(|binding| binding.host_port.as_ref())
-                .and_then(|port| port.parse::<u16>().ok())
-                .unwrap_or(0);
-
-            let is_running = inspect
-                .state
-                .as_ref()
-                .and_then(|s| s.running)
-                .unwrap_or(false);
-
-            result.push(DiscoveredPoolContainer {
-                container_id,
-                dialect,
-                host_port,
-                is_running,
-            });
+            let containers = endpoint.docker.list_containers(Some(options)).await?;
+
+            for container in containers {
+                let container_id = match &container.id {
+                    Some(id) => id.clone(),
+                    None => continue,
+                };
+
+                let inspect = match endpoint.docker.inspect_container(&container_id, None).await {
+                    Ok(i) => i,
+                    Err(e) => {
+                        warn!("Failed to inspect pool container {}: {}", container_id, e);
+                        continue;
+                    }
+                };
+
+                let labels = inspect.config.as_ref().and_then(|c| c.labels.as_ref());
+
+                // Check if it's a pool container
+                let is_pool = labels
+                    .and_then(|l| l.get("db-api.pool"))
+                    .map(|v| v == "true")
+                    .unwrap_or(false);
+
+                if !is_pool {
+                    continue;
+                }
+
+                let dialect = match labels.and_then(|l| l.get("db-api.dialect")) {
+                    Some(d) => d.clone(),
+                    None => continue,
+                };
+
+                let container_port_str = labels.and_then(|l| l.get("db-api.container_port"));
+                let container_port: u16 = container_port_str
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3306);
+
+                let host_port = inspect
+                    .network_settings
+                    .as_ref()
+                    .and_then(|ns| ns.ports.as_ref())
+                    .and_then(|ports| ports.get(&format!("{}/tcp", container_port)))
+                    .and_then(|bindings| bindings.as_ref())
+                    .and_then(|bindings| bindings.first())
+                    .and_then(|binding| binding.host_port.as_ref())
+                    .and_then(|port| port.parse::<u16>().ok())
+                    .unwrap_or(0);
+
+                let is_running = inspect
+                    .state
+                    .as_ref()
+                    .and_then(|s| s.running)
+                    .unwrap_or(false);
+
+                self.register_container(&container_id, endpoint_idx).await;
+
+                result.push(DiscoveredPoolContainer {
+                    container_id,
+                    dialect,
+                    host_port,
+                    is_running,
+                    endpoint_name: endpoint.name.clone(),
+                });
+            }
         }
 
         Ok(result)
     }
 
-    /// List all db-api containers and extract their metadata
+    /// List all db-api containers across every endpoint and extract their
+    /// metadata, tagging each with its originating endpoint and registering
+    /// it in the endpoint cache (see `list_pool_containers`).
     pub async fn list_db_containers(&self) -> Result<Vec<DiscoveredContainer>> {
         use bollard::container::ListContainersOptions;
 
-        let mut filters = HashMap::new();
-        filters.insert("name", vec!["db-api-"]);
-
-        let options = ListContainersOptions {
-            all: true,
-            filters,
-            ..Default::default()
-        };
-
-        let containers = self.docker.list_containers(Some(options)).await?;
         let mut result = Vec::new();
 
-        for container in containers {
-            let container_id = match &container.id {
-                Some(id) => id.clone(),
-                None => continue,
-            };
+        for (endpoint_idx, endpoint) in self.endpoints.iter().enumerate() {
+            let mut filters = HashMap::new();
+            filters.insert("name", vec!["db-api-"]);
 
-            // Get full container details for labels and port mappings
-            let inspect = match self.docker.inspect_container(&container_id, None).await {
-                Ok(i) => i,
-                Err(e) => {
-                    warn!("Failed to inspect container {}: {}", container_id, e);
-                    continue;
-                }
+            let options = ListContainersOptions {
+                all: true,
+                filters,
+                ..Default::default()
             };
 
-            let labels = inspect.config.as_ref().and_then(|c| c.labels.as_ref());
-
-            // Extract our labels
-            let db_id = labels
-                .and_then(|l| l.get("db-api.id"))
-                .and_then(|s| Uuid::parse_str(s).ok());
-            let dialect = labels.and_then(|l| l.get("db-api.dialect")).cloned();
-            let db_name = labels.and_then(|l| l.get("db-api.db_name")).cloned();
-            let db_user = labels.and_then(|l| l.get("db-api.db_user")).cloned();
-            let db_password = labels.and_then(|l| l.get("db-api.db_password")).cloned();
-
-            // All labels must be present
-            let (db_id, dialect, db_name, db_user, db_password) =
-                match (db_id, dialect, db_name, db_user, db_password) {
-                    (Some(id), Some(d), Some(n), Some(u), Some(p)) => (id, d, n, u, p),
-                    _ => {
-                        debug!("Container {} missing required labels, skipping", container_id);
+            let containers = endpoint.docker.list_containers(Some(options)).await?;
+
+            for container in containers {
+                let container_id = match &container.id {
+                    Some(id) => id.clone(),
+                    None => continue,
+                };
+
+                // Get full container details for labels and port mappings
+                let inspect = match endpoint.docker.inspect_container(&container_id, None).await {
+                    Ok(i) => i,
+                    Err(e) => {
+                        warn!("Failed to inspect container {}: {}", container_id, e);
                         continue;
                     }
                 };
 
-            // Get port from container info
-            let container_port_str = labels.and_then(|l| l.get("db-api.container_port"));
-            let container_port: u16 = container_port_str
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(3306);
-
-            let host_port = inspect
-                .network_settings
-                .as_ref()
-                .and_then(|ns| ns.ports.as_ref())
-                .and_then(|ports| ports.get(&format!("{}/tcp", container_port)))
-                .and_then(|bindings| bindings.as_ref())
-                .and_then(|bindings| bindings.first())
-                .and_then(|binding| binding.host_port.as_ref())
-                .and_then(|port| port.parse::<u16>().ok())
-                .unwrap_or(0);
-
-            let is_running = inspect
-                .state
-                .as_ref()
-                .and_then(|s| s.running)
-                .unwrap_or(false);
-
-            result.push(DiscoveredContainer {
-                container_id,
-                db_id,
-                dialect,
-                db_name,
-                db_user,
-                db_password,
-                host_port,
-                is_running,
-            });
+                let labels = inspect.config.as_ref().and_then(|c| c.labels.as_ref());
+
+                // Extract our labels
+                let db_id = labels
+                    .and_then(|l| l.get("db-api.id"))
+                    .and_then(|s| Uuid::parse_str(s).ok());
+                let dialect = labels.and_then(|l| l.get("db-api.dialect")).cloned();
+                let db_name = labels.and_then(|l| l.get("db-api.db_name")).cloned();
+                let db_user = labels.and_then(|l| l.get("db-api.db_user")).cloned();
+                let db_password = labels.and_then(|l| l.get("db-api.db_password")).cloned();
+
+                // All labels must be present
+                let (db_id, dialect, db_name, db_user, db_password) =
+                    match (db_id, dialect, db_name, db_user, db_password) {
+                        (Some(id), Some(d), Some(n), Some(u), Some(p)) => (id, d, n, u, p),
+                        _ => {
+                            debug!("Container {} missing required labels, skipping", container_id);
+                            continue;
+                        }
+                    };
+
+                // Get port from container info
+                let container_port_str = labels.and_then(|l| l.get("db-api.container_port"));
+                let container_port: u16 = container_port_str
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(3306);
+
+                let host_port = inspect
+                    .network_settings
+                    .as_ref()
+                    .and_then(|ns| ns.ports.as_ref())
+                    .and_then(|ports| ports.get(&format!("{}/tcp", container_port)))
+                    .and_then(|bindings| bindings.as_ref())
+                    .and_then(|bindings| bindings.first())
+                    .and_then(|binding| binding.host_port.as_ref())
+                    .and_then(|port| port.parse::<u16>().ok())
+                    .unwrap_or(0);
+
+                let is_running = inspect
+                    .state
+                    .as_ref()
+                    .and_then(|s| s.running)
+                    .unwrap_or(false);
+
+                self.register_container(&container_id, endpoint_idx).await;
+
+                result.push(DiscoveredContainer {
+                    container_id,
+                    db_id,
+                    dialect,
+                    db_name,
+                    db_user,
+                    db_password,
+                    host_port,
+                    is_running,
+                    endpoint_name: endpoint.name.clone(),
+                });
+            }
         }
 
         Ok(result)
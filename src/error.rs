@@ -23,6 +23,9 @@ pub enum AppError {
     #[error("SQL syntax error: {0}")]
     QuerySyntaxError(String),
 
+    #[error("Invalid query parameters: {0}")]
+    InvalidQueryParams(String),
+
     #[error("Database exceeded size limit")]
     DbSizeExceeded,
 
@@ -32,9 +35,39 @@ pub enum AppError {
     #[error("Backup has expired")]
     BackupExpired,
 
+    #[error("Restore already in progress")]
+    RestoreInProgress,
+
+    #[error("Metadata store error: {0}")]
+    Storage(String),
+
     #[error("Docker error: {0}")]
     Docker(#[from] bollard::errors::Error),
 
+    #[error("Backup storage error: {0}")]
+    R2(String),
+
+    #[error("Backup failed: {0}")]
+    BackupFailed(String),
+
+    #[error("Restore failed: {0}")]
+    RestoreFailed(String),
+
+    #[error("Failed to presign backup request: {0}")]
+    PresignFailed(String),
+
+    #[error("Invalid database identifier: {0}")]
+    InvalidIdentifier(String),
+
+    #[error("Missing or invalid API key")]
+    Unauthorized,
+
+    #[error("Pool container capacity exhausted")]
+    PoolExhausted,
+
+    #[error("Job not found")]
+    JobNotFound,
+
     #[error("Internal server error: {0}")]
     Internal(String),
 }
@@ -47,10 +80,21 @@ impl AppError {
             Self::DialectPullFailed(_) => "DIALECT_PULL_FAILED",
             Self::QueryTimeout => "QUERY_TIMEOUT",
             Self::QuerySyntaxError(_) => "QUERY_SYNTAX_ERROR",
+            Self::InvalidQueryParams(_) => "INVALID_QUERY_PARAMS",
             Self::DbSizeExceeded => "DB_SIZE_EXCEEDED",
             Self::BackupNotFound => "BACKUP_NOT_FOUND",
             Self::BackupExpired => "BACKUP_EXPIRED",
+            Self::RestoreInProgress => "RESTORE_IN_PROGRESS",
+            Self::Storage(_) => "STORAGE_ERROR",
             Self::Docker(_) => "DOCKER_ERROR",
+            Self::R2(_) => "BACKUP_STORAGE_ERROR",
+            Self::BackupFailed(_) => "BACKUP_FAILED",
+            Self::RestoreFailed(_) => "RESTORE_FAILED",
+            Self::PresignFailed(_) => "PRESIGN_FAILED",
+            Self::InvalidIdentifier(_) => "INVALID_IDENTIFIER",
+            Self::Unauthorized => "UNAUTHORIZED",
+            Self::PoolExhausted => "POOL_EXHAUSTED",
+            Self::JobNotFound => "JOB_NOT_FOUND",
             Self::Internal(_) => "INTERNAL_ERROR",
         }
     }
@@ -62,10 +106,21 @@ impl AppError {
             Self::DialectPullFailed(_) => StatusCode::SERVICE_UNAVAILABLE,
             Self::QueryTimeout => StatusCode::REQUEST_TIMEOUT,
             Self::QuerySyntaxError(_) => StatusCode::BAD_REQUEST,
+            Self::InvalidQueryParams(_) => StatusCode::BAD_REQUEST,
             Self::DbSizeExceeded => StatusCode::PAYLOAD_TOO_LARGE,
             Self::BackupNotFound => StatusCode::NOT_FOUND,
             Self::BackupExpired => StatusCode::GONE,
+            Self::RestoreInProgress => StatusCode::CONFLICT,
+            Self::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
             Self::Docker(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::R2(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::BackupFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::RestoreFailed(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            Self::PresignFailed(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::InvalidIdentifier(_) => StatusCode::BAD_REQUEST,
+            Self::Unauthorized => StatusCode::UNAUTHORIZED,
+            Self::PoolExhausted => StatusCode::SERVICE_UNAVAILABLE,
+            Self::JobNotFound => StatusCode::NOT_FOUND,
             Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -86,10 +141,19 @@ struct ErrorDetail {
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
+        tracing::warn!(code = self.code(), status = %self.status_code(), "{}", self);
+
         let detail = match &self {
             Self::QuerySyntaxError(msg) => Some(msg.clone()),
+            Self::InvalidQueryParams(msg) => Some(msg.clone()),
             Self::DialectPullFailed(msg) => Some(msg.clone()),
             Self::Docker(e) => Some(e.to_string()),
+            Self::R2(msg) => Some(msg.clone()),
+            Self::BackupFailed(msg) => Some(msg.clone()),
+            Self::RestoreFailed(msg) => Some(msg.clone()),
+            Self::PresignFailed(msg) => Some(msg.clone()),
+            Self::InvalidIdentifier(msg) => Some(msg.clone()),
+            Self::Storage(msg) => Some(msg.clone()),
             Self::Internal(msg) => Some(msg.clone()),
             _ => None,
         };
@@ -0,0 +1,205 @@
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::db::manager::InstanceManager;
+use crate::db::query::{QueryEvent, QueryExecutor};
+use crate::error::{AppError, Result};
+use crate::storage::{MetadataBackend, StoredJob};
+
+/// Job kinds understood by `run_job`. Stored as the plain string in
+/// `jobs.kind`, matched on dispatch.
+const JOB_KIND_QUERY: &str = "query";
+const JOB_KIND_BACKUP: &str = "backup";
+
+/// Payload for a `query` job, submitted via `POST /db/{db_id}/query/async`
+#[derive(Debug, Serialize, Deserialize)]
+struct QueryJobPayload {
+    db_id: Uuid,
+    sql: String,
+}
+
+/// Payload for a `backup` job, archiving an instance in the background
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupJobPayload {
+    db_id: Uuid,
+}
+
+/// Thin wrapper over the metadata store's job-queue methods, giving API
+/// handlers a single place to go through rather than reaching into
+/// `MetadataBackend` directly - mirrors how `InstanceManager` fronts
+/// instance-related metadata calls. The worker and reaper loops below call
+/// `MetadataBackend` directly.
+pub struct JobQueue {
+    metadata: Arc<dyn MetadataBackend>,
+}
+
+impl JobQueue {
+    pub fn new(metadata: Arc<dyn MetadataBackend>) -> Self {
+        Self { metadata }
+    }
+
+    /// Submit a query to run in the background, returning the new job's id
+    pub async fn enqueue_query(&self, db_id: Uuid, sql: String) -> Result<Uuid> {
+        let payload = serde_json::to_string(&QueryJobPayload { db_id, sql })
+            .map_err(|e| AppError::Internal(format!("Failed to encode job payload: {}", e)))?;
+
+        self.metadata.enqueue_job(JOB_KIND_QUERY, &payload).await
+    }
+
+    /// Submit an instance archive to run in the background
+    pub async fn enqueue_backup(&self, db_id: Uuid) -> Result<Uuid> {
+        let payload = serde_json::to_string(&BackupJobPayload { db_id })
+            .map_err(|e| AppError::Internal(format!("Failed to encode job payload: {}", e)))?;
+
+        self.metadata.enqueue_job(JOB_KIND_BACKUP, &payload).await
+    }
+
+    /// Look up a job by id, for status polling
+    pub async fn get_job(&self, id: Uuid) -> Result<Option<StoredJob>> {
+        self.metadata.get_job(id).await
+    }
+}
+
+/// Background worker: polls for the oldest `new` job, runs it, and records
+/// its result - a no-op while the queue is empty, backing off to
+/// `poll_interval` between claim attempts. While a job runs, a separate
+/// heartbeat ticks on `heartbeat_interval` so the reaper doesn't mistake a
+/// long-running query for a dead worker.
+pub fn start_worker_task(
+    metadata: Arc<dyn MetadataBackend>,
+    manager: Arc<InstanceManager>,
+    query_executor: Arc<QueryExecutor>,
+    config: &Config,
+) {
+    if config.job_poll_interval_secs == 0 {
+        info!("Job worker disabled (poll interval is 0)");
+        return;
+    }
+
+    let poll_interval = Duration::from_secs(config.job_poll_interval_secs);
+    let heartbeat_interval = Duration::from_secs(config.job_heartbeat_interval_secs);
+
+    tokio::spawn(async move {
+        let mut ticker = interval(poll_interval);
+
+        loop {
+            ticker.tick().await;
+
+            loop {
+                let job = match metadata.claim_next_job().await {
+                    Ok(Some(job)) => job,
+                    Ok(None) => break,
+                    Err(e) => {
+                        warn!("Failed to claim next job: {}", e);
+                        break;
+                    }
+                };
+
+                run_job(&metadata, &manager, &query_executor, job, heartbeat_interval).await;
+            }
+        }
+    });
+}
+
+/// Run a single claimed job to completion, recording its result or error on
+/// the job row. Errors running the job itself (a bad payload, an unknown
+/// job kind) are recorded as a failed job rather than propagated, since
+/// there's no caller left to propagate them to.
+async fn run_job(
+    metadata: &Arc<dyn MetadataBackend>,
+    manager: &Arc<InstanceManager>,
+    query_executor: &Arc<QueryExecutor>,
+    job: StoredJob,
+    heartbeat_interval: Duration,
+) {
+    let metadata_hb = metadata.clone();
+    let job_id = job.id;
+    let heartbeat = tokio::spawn(async move {
+        let mut ticker = interval(heartbeat_interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = metadata_hb.heartbeat_job(job_id).await {
+                warn!("Failed to heartbeat job {}: {}", job_id, e);
+            }
+        }
+    });
+
+    let outcome = match job.kind.as_str() {
+        JOB_KIND_QUERY => run_query_job(manager, query_executor, &job.payload).await,
+        JOB_KIND_BACKUP => run_backup_job(manager, &job.payload).await,
+        other => Err(AppError::Internal(format!("Unknown job kind: {}", other))),
+    };
+
+    heartbeat.abort();
+
+    match outcome {
+        Ok(result) => {
+            if let Err(e) = metadata.complete_job(job.id, &result).await {
+                warn!("Failed to record completion for job {}: {}", job.id, e);
+            }
+        }
+        Err(e) => {
+            warn!("Job {} ({}) failed: {}", job.id, job.kind, e);
+            if let Err(e) = metadata.fail_job(job.id, &e.to_string()).await {
+                warn!("Failed to record failure for job {}: {}", job.id, e);
+            }
+        }
+    }
+}
+
+async fn run_query_job(
+    manager: &Arc<InstanceManager>,
+    query_executor: &Arc<QueryExecutor>,
+    payload: &str,
+) -> Result<String> {
+    let payload: QueryJobPayload = serde_json::from_str(payload)
+        .map_err(|e| AppError::Internal(format!("Invalid query job payload: {}", e)))?;
+
+    let instance = manager.get_instance(payload.db_id).await?;
+    let stream = query_executor.execute(&instance, &payload.sql).await?;
+    let events: Vec<QueryEvent> = stream.collect().await;
+
+    serde_json::to_string(&events)
+        .map_err(|e| AppError::Internal(format!("Failed to encode query result: {}", e)))
+}
+
+async fn run_backup_job(manager: &Arc<InstanceManager>, payload: &str) -> Result<String> {
+    let payload: BackupJobPayload = serde_json::from_str(payload)
+        .map_err(|e| AppError::Internal(format!("Invalid backup job payload: {}", e)))?;
+
+    manager.archive_instance(payload.db_id).await?;
+
+    Ok(format!("archived {}", payload.db_id))
+}
+
+/// Background reaper: requeues any `running` job whose heartbeat has gone
+/// stale, for jobs whose worker died mid-run
+pub fn start_reaper_task(metadata: Arc<dyn MetadataBackend>, config: &Config) {
+    if config.job_reap_interval_secs == 0 {
+        info!("Job reaper disabled (reap interval is 0)");
+        return;
+    }
+
+    let reap_interval = Duration::from_secs(config.job_reap_interval_secs);
+    let stale_after = Duration::from_secs(config.job_stale_after_secs);
+
+    tokio::spawn(async move {
+        let mut ticker = interval(reap_interval);
+
+        loop {
+            ticker.tick().await;
+
+            match metadata.reap_stale_jobs(stale_after).await {
+                Ok(0) => {}
+                Ok(count) => info!("Reaped {} stale job(s)", count),
+                Err(e) => warn!("Failed to reap stale jobs: {}", e),
+            }
+        }
+    });
+}
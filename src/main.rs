@@ -3,35 +3,55 @@ mod config;
 mod db;
 mod docker;
 mod error;
+mod jobs;
 mod storage;
+mod systemd;
 
 use std::sync::Arc;
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::api::create_router;
-use crate::config::Config;
+use crate::config::{BackupBackendKind, Config, MetadataBackendKind};
+use crate::db::query::QueryExecutor;
 use crate::db::InstanceManager;
 use crate::docker::DockerManager;
-use crate::storage::{BackupManager, MetadataStore};
+use crate::jobs::JobQueue;
+use crate::storage::{
+    BackupManager, BackupStore, LocalBackupStore, MetadataBackend, MetadataStore,
+    PostgresMetadataStore, RetentionPolicy,
+};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "db_api=info,tower_http=info".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Initialize tracing. `LOG_TARGET=journald` routes structured fields
+    // (db_id, error code, query duration, ...) straight to the journal
+    // instead of formatting them into a stdout line; read directly from the
+    // environment since this has to happen before `Config::from_env` so
+    // even config-loading logs land in the right place.
+    let env_filter = || {
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| "db_api=info,tower_http=info".into())
+    };
+    if std::env::var("LOG_TARGET").as_deref() == Ok("journald") {
+        let journald = tracing_journald::layer().expect("Failed to connect to journald");
+        tracing_subscriber::registry()
+            .with(env_filter())
+            .with(journald)
+            .init();
+    } else {
+        tracing_subscriber::registry()
+            .with(env_filter())
+            .with(tracing_subscriber::fmt::layer())
+            .init();
+    }
 
     // Load configuration
     let config = Config::from_env();
     info!("Configuration loaded: {:?}", config);
 
     // Initialize Docker manager
-    let docker = DockerManager::new().expect("Failed to connect to Docker");
+    let docker = DockerManager::from_env().expect("Failed to connect to Docker");
     let docker = Arc::new(docker);
 
     // Check Docker connectivity
@@ -41,21 +61,65 @@ async fn main() -> anyhow::Result<()> {
         .expect("Failed to connect to Docker daemon");
     info!("Connected to Docker daemon");
 
-    // Initialize metadata store (SQLite)
-    let metadata = MetadataStore::new(&config.metadata_db_path)
-        .expect("Failed to initialize metadata store");
-    info!("Metadata store initialized at {}", config.metadata_db_path);
-
-    // Initialize backup manager (R2) if configured
-    let backup = if config.backup_enabled() {
-        match BackupManager::new(&config).await {
-            Ok(b) => {
-                info!("Backup manager initialized for bucket {}", config.r2_bucket);
-                Some(b)
-            }
-            Err(e) => {
-                tracing::warn!("Failed to initialize backup manager: {}. Backups disabled.", e);
-                None
+    // Initialize metadata store, selected from config so the control plane
+    // can run against either local SQLite (single-node) or shared Postgres
+    // (multiple nodes coordinating on the same instance set)
+    let metadata: Arc<dyn MetadataBackend> = match config.metadata_backend {
+        MetadataBackendKind::Sqlite => {
+            let store = Arc::new(
+                MetadataStore::new(&config.metadata_db_path, config.metadata_pool_size)
+                    .expect("Failed to initialize metadata store"),
+            );
+            info!("SQLite metadata store initialized at {}", config.metadata_db_path);
+            store.clone().start_wal_checkpoint_task(
+                config.wal_checkpoint_interval_secs,
+                config.wal_checkpoint_busy_timeout_secs,
+            );
+            store
+        }
+        MetadataBackendKind::Postgres => {
+            let store = PostgresMetadataStore::new(&config.metadata_postgres_url)
+                .await
+                .expect("Failed to initialize Postgres metadata store");
+            info!("Postgres metadata store initialized");
+            Arc::new(store)
+        }
+    };
+
+    // Initialize the backup store, selected from config so the service can
+    // run against either R2 (S3-compatible object storage) or a local
+    // directory, which needs no cloud credentials
+    let backup: Option<Arc<dyn BackupStore>> = if config.backup_enabled() {
+        match config.backup_backend {
+            BackupBackendKind::R2 => match BackupManager::new(&config).await {
+                Ok(b) => {
+                    info!("Backup store initialized for R2 bucket {}", config.r2_bucket);
+                    Some(Arc::new(b) as Arc<dyn BackupStore>)
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to initialize backup store: {}. Backups disabled.", e);
+                    None
+                }
+            },
+            BackupBackendKind::Local => {
+                let retention_policy = RetentionPolicy {
+                    retain_count: config.backup_retain_count,
+                    max_age_days: config.backup_max_age_days,
+                };
+                match LocalBackupStore::new(
+                    &config.backup_local_dir,
+                    config.backup_encryption_key.as_deref(),
+                    retention_policy,
+                ) {
+                    Ok(store) => {
+                        info!("Backup store initialized at local dir {}", config.backup_local_dir);
+                        Some(Arc::new(store) as Arc<dyn BackupStore>)
+                    }
+                    Err(e) => {
+                        tracing::warn!("Failed to initialize backup store: {}. Backups disabled.", e);
+                        None
+                    }
+                }
             }
         }
     } else {
@@ -65,8 +129,8 @@ async fn main() -> anyhow::Result<()> {
 
     // Initialize instance manager
     let manager = InstanceManager::new(
-        DockerManager::new().expect("Failed to create Docker manager"),
-        metadata,
+        DockerManager::from_env().expect("Failed to create Docker manager"),
+        metadata.clone(),
         backup,
         config.clone(),
     );
@@ -83,15 +147,87 @@ async fn main() -> anyhow::Result<()> {
     manager.clone().start_cleanup_task();
     info!("Started instance cleanup task");
 
+    // Start pool container health monitor
+    manager.clone().start_pool_monitor_task();
+    info!("Started pool health monitor task");
+
+    // Start periodic instance snapshot task
+    manager.clone().start_snapshot_task();
+
+    // Start memory-pressure sampler/eviction task (no-op unless configured)
+    manager.clone().start_memory_pressure_task();
+
+    // Start per-instance health watchdog (no-op unless configured)
+    manager.clone().start_instance_health_task();
+
+    // Shared query executor, so the HTTP query endpoints and the background
+    // job worker draw from the same native-connection pool instead of each
+    // keeping their own.
+    let query_executor = Arc::new(QueryExecutor::new(docker.clone(), config.query_timeout));
+    query_executor
+        .register_native_connector("postgres", Arc::new(db::native_pool::PostgresNativeConnector::default()))
+        .await;
+
+    // Background job queue: a worker claims and runs jobs (queries
+    // submitted via `/query/async`, instance backups), a reaper requeues
+    // any whose worker died mid-run.
+    let job_queue = Arc::new(JobQueue::new(metadata.clone()));
+    jobs::start_worker_task(metadata.clone(), manager.clone(), query_executor.clone(), &config);
+    jobs::start_reaper_task(metadata, &config);
+
+    // Tear down (or, if configured, just stop) every db-api container on
+    // SIGTERM/SIGINT so a crash or `docker stop` of the api doesn't leave
+    // orphans behind for `recover_existing_instances` to clean up later.
+    tokio::spawn(docker.clone().shutdown_handler(config.preserve_containers_on_exit));
+
     // Create router
-    let app = create_router(manager, docker, &config);
+    let app = create_router(manager, docker, &config, query_executor, job_queue);
 
     // Start server
     let addr = config.socket_addr();
     let listener = tokio::net::TcpListener::bind(addr).await?;
     info!("Server listening on {}", addr);
 
-    axum::serve(listener, app).await?;
+    // Everything's ready (listener bound, InstanceManager and backup store
+    // initialized) - tell systemd so dependent units can start
+    if config.systemd_notify {
+        systemd::notify_ready();
+        systemd::spawn_watchdog_task();
+    }
+
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal(config.systemd_notify))
+        .await?;
 
     Ok(())
 }
+
+/// Wait for Ctrl+C or SIGTERM, notifying systemd on the way out if enabled
+async fn shutdown_signal(systemd_notify: bool) {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+
+    info!("Shutdown signal received, stopping");
+    if systemd_notify {
+        systemd::notify_stopping();
+    }
+}
@@ -0,0 +1,142 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::error::Result;
+
+use super::metadata::{InstanceEvent, InstanceState, PoolContainer, StoredInstance, StoredJob, StoredSnapshot};
+
+/// Storage abstraction for instance/pool/snapshot metadata. Implemented by
+/// both the local SQLite store (single-node) and a Postgres-backed store
+/// (shared, multi-node), selected at startup from `Config`.
+#[async_trait]
+pub trait MetadataBackend: Send + Sync {
+    /// Insert a new instance
+    async fn insert_instance(&self, instance: &StoredInstance) -> Result<()>;
+
+    /// Get an instance by ID
+    async fn get_instance(&self, db_id: Uuid) -> Result<Option<StoredInstance>>;
+
+    /// Update an instance
+    async fn update_instance(&self, instance: &StoredInstance) -> Result<()>;
+
+    /// Mark an instance as archived with backup info
+    async fn mark_archived(&self, db_id: Uuid, backup_key: &str, size: i64) -> Result<()>;
+
+    /// Mark an instance as active with container info
+    async fn mark_active(&self, db_id: Uuid, container_id: &str, port: u16) -> Result<()>;
+
+    /// Update status only
+    async fn update_status(&self, db_id: Uuid, status: InstanceState) -> Result<()>;
+
+    /// Record the highest migration version applied to an instance's schema
+    async fn update_schema_version(&self, db_id: Uuid, version: u32) -> Result<()>;
+
+    /// Update last activity timestamp
+    async fn touch_activity(&self, db_id: Uuid) -> Result<()>;
+
+    /// List all active instances
+    async fn list_active_instances(&self) -> Result<Vec<StoredInstance>>;
+
+    /// Get instances that have been inactive longer than the timeout
+    async fn get_expired_instances(&self, timeout: Duration) -> Result<Vec<StoredInstance>>;
+
+    /// Get active instances whose absolute lease has passed its grace
+    /// period, regardless of activity
+    async fn get_lease_expired_instances(&self, grace: Duration) -> Result<Vec<StoredInstance>>;
+
+    /// Delete an instance from the metadata store
+    async fn delete_instance(&self, db_id: Uuid) -> Result<()>;
+
+    /// Get a pool container by its container id
+    async fn get_pool_container(&self, container_id: &str) -> Result<Option<PoolContainer>>;
+
+    /// List every pool container known to the metadata store
+    async fn list_pool_containers(&self) -> Result<Vec<PoolContainer>>;
+
+    /// List every pool container registered for a dialect
+    async fn list_pool_containers_for_dialect(&self, dialect: &str) -> Result<Vec<PoolContainer>>;
+
+    /// Insert or replace a pool container, keyed by its container id
+    async fn upsert_pool_container(&self, pool: &PoolContainer) -> Result<()>;
+
+    /// Remove a pool container by its container id
+    async fn delete_pool_container(&self, container_id: &str) -> Result<()>;
+
+    /// Apply a delta (positive or negative) to a pool container's live instance count
+    async fn adjust_pool_instance_count(&self, container_id: &str, delta: i32) -> Result<()>;
+
+    /// Record a new periodic snapshot for a still-active instance
+    async fn insert_snapshot(&self, snapshot: &StoredSnapshot) -> Result<()>;
+
+    /// List all snapshots for an instance, newest first
+    async fn list_snapshots(&self, db_id: Uuid) -> Result<Vec<StoredSnapshot>>;
+
+    /// Find the most recent snapshot at or before a point in time
+    async fn get_snapshot_at_or_before(
+        &self,
+        db_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<Option<StoredSnapshot>>;
+
+    /// Delete a single snapshot row (the caller is responsible for removing
+    /// the backing backup-store object)
+    async fn delete_snapshot(&self, id: Uuid) -> Result<()>;
+
+    /// Record a lifecycle event that isn't already implied by a row change
+    /// on `instances` (e.g. "recovered" on startup, where the row is
+    /// untouched), for transitions the `instances` triggers can't see
+    async fn record_event(
+        &self,
+        db_id: Uuid,
+        dialect: &str,
+        previous_status: Option<InstanceState>,
+        new_status: &str,
+        reason: &str,
+    ) -> Result<()>;
+
+    /// Read an instance's lifecycle history, newest first, capped at
+    /// `limit` rows when given (otherwise the full history)
+    async fn list_instance_events(&self, db_id: Uuid, limit: Option<u32>) -> Result<Vec<InstanceEvent>>;
+
+    /// Instance counts grouped by `status`, for the `/metrics` endpoint
+    async fn count_by_status(&self) -> Result<Vec<(String, i64)>>;
+
+    /// Instance counts grouped by `dialect`, for the `/metrics` endpoint
+    async fn count_by_dialect(&self) -> Result<Vec<(String, i64)>>;
+
+    /// Total `backup_size_bytes` summed across archived instances, for the
+    /// `/metrics` endpoint
+    async fn sum_backup_bytes(&self) -> Result<i64>;
+
+    // Background job queue (see `crate::jobs`)
+
+    /// Insert a new job row in `new` status, returning its generated id
+    async fn enqueue_job(&self, kind: &str, payload: &str) -> Result<Uuid>;
+
+    /// Atomically claim the oldest `new` job, flipping it to `running` and
+    /// bumping its heartbeat/attempt count in the same statement - the
+    /// SQLite/Postgres analogue of `FOR UPDATE SKIP LOCKED`, so concurrent
+    /// workers never double-process the same job. `None` means the queue is
+    /// empty.
+    async fn claim_next_job(&self) -> Result<Option<StoredJob>>;
+
+    /// Refresh a running job's heartbeat, so the reaper doesn't mistake a
+    /// still-alive worker for a dead one
+    async fn heartbeat_job(&self, id: Uuid) -> Result<()>;
+
+    /// Mark a job done, recording its result
+    async fn complete_job(&self, id: Uuid, result: &str) -> Result<()>;
+
+    /// Mark a job failed, recording the error
+    async fn fail_job(&self, id: Uuid, error: &str) -> Result<()>;
+
+    /// Look up a job by id, for status polling
+    async fn get_job(&self, id: Uuid) -> Result<Option<StoredJob>>;
+
+    /// Requeue any `running` job whose heartbeat is older than `stale_after`
+    /// back to `new`, for jobs whose worker died mid-run. Returns the number
+    /// of jobs requeued.
+    async fn reap_stale_jobs(&self, stale_after: Duration) -> Result<u64>;
+}
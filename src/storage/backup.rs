@@ -1,20 +1,41 @@
+use async_trait::async_trait;
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{CompletedMultipartUpload, CompletedPart};
 use aws_sdk_s3::Client;
+use bytes::Bytes;
 use chrono::Utc;
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
-use std::io::{Read, Write};
+use futures::{Stream, StreamExt};
+use std::io::Write;
+use std::time::Duration;
 use tracing::info;
 use uuid::Uuid;
 
 use crate::config::Config;
 use crate::error::{AppError, Result};
 
+use super::backup_store::{
+    compress_gzip, decompress_gzip, parse_backup_timestamp, select_prunable, BackupEntry,
+    BackupStore, BoxedByteStream, RetentionPolicy,
+};
+use super::encryption;
+
+/// Multipart upload part size. S3-compatible services require every part
+/// except the last to be at least 5 MiB; we use 8 MiB chunks so gzip
+/// compression stays well above that floor.
+const UPLOAD_PART_SIZE: usize = 8 * 1024 * 1024;
+
 /// Manages backups in R2 (S3-compatible) storage
 pub struct BackupManager {
     client: Client,
     bucket: String,
+    /// AES-256 key derived from `BACKUP_ENCRYPTION_KEY`, if configured. See
+    /// `storage::encryption`.
+    encryption_key: Option<[u8; 32]>,
+    retention_policy: RetentionPolicy,
 }
 
 impl BackupManager {
@@ -44,31 +65,125 @@ impl BackupManager {
 
         let client = Client::from_conf(s3_config);
 
+        let encryption_key = config
+            .backup_encryption_key
+            .as_deref()
+            .map(encryption::derive_key)
+            .transpose()?;
+
         info!("BackupManager initialized for bucket: {}", config.r2_bucket);
 
         Ok(Self {
             client,
             bucket: config.r2_bucket.clone(),
+            encryption_key,
+            retention_policy: RetentionPolicy {
+                retain_count: config.backup_retain_count,
+                max_age_days: config.backup_max_age_days,
+            },
         })
     }
 
+    /// Drain `source`, gzip-compressing and uploading 8 MiB parts as they
+    /// fill. Returns the completed part list and the number of uncompressed
+    /// source bytes read.
+    async fn stream_parts_to_upload<S>(
+        &self,
+        key: &str,
+        upload_id: &str,
+        source: &mut S,
+    ) -> Result<(Vec<CompletedPart>, i64)>
+    where
+        S: Stream<Item = Result<Bytes>> + Unpin,
+    {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let mut completed_parts = Vec::new();
+        let mut part_number = 1;
+        let mut source_bytes: i64 = 0;
+
+        while let Some(chunk) = source.next().await {
+            let chunk = chunk?;
+            source_bytes += chunk.len() as i64;
+            encoder
+                .write_all(&chunk)
+                .map_err(|e| AppError::BackupFailed(format!("Compression failed: {}", e)))?;
+
+            if encoder.get_ref().len() >= UPLOAD_PART_SIZE {
+                let part_data = std::mem::take(encoder.get_mut());
+                let part = self
+                    .upload_part(key, upload_id, part_number, part_data)
+                    .await?;
+                completed_parts.push(part);
+                part_number += 1;
+            }
+        }
+
+        let final_data = encoder
+            .finish()
+            .map_err(|e| AppError::BackupFailed(format!("Compression finalize failed: {}", e)))?;
+
+        // S3 requires at least one part even for an empty upload
+        if !final_data.is_empty() || completed_parts.is_empty() {
+            let part = self
+                .upload_part(key, upload_id, part_number, final_data)
+                .await?;
+            completed_parts.push(part);
+        }
+
+        Ok((completed_parts, source_bytes))
+    }
+
+    async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: i32,
+        data: Vec<u8>,
+    ) -> Result<CompletedPart> {
+        let response = self
+            .client
+            .upload_part()
+            .bucket(&self.bucket)
+            .key(key)
+            .upload_id(upload_id)
+            .part_number(part_number)
+            .body(ByteStream::from(data))
+            .send()
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to upload part {}: {}", part_number, e)))?;
+
+        Ok(CompletedPart::builder()
+            .e_tag(response.e_tag().unwrap_or_default())
+            .part_number(part_number)
+            .build())
+    }
+
+}
+
+#[async_trait]
+impl BackupStore for BackupManager {
     /// Upload a database backup (SQL dump) to R2
     /// Returns (object_key, size_bytes)
-    pub async fn upload_backup(&self, db_id: Uuid, sql_data: &[u8]) -> Result<(String, i64)> {
+    async fn upload_backup(&self, db_id: Uuid, sql_data: &[u8]) -> Result<(String, i64)> {
         // Compress the SQL dump
         let compressed = compress_gzip(sql_data)?;
-        let size = compressed.len() as i64;
+        let body = match &self.encryption_key {
+            Some(key) => encryption::encrypt(&compressed, key)?,
+            None => compressed,
+        };
+        let size = body.len() as i64;
 
-        // Generate key: backups/{db_id}/{timestamp}.sql.gz
+        // Generate key: backups/{db_id}/{timestamp}.sql.gz[.enc]
         let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-        let key = format!("backups/{}/{}.sql.gz", db_id, timestamp);
+        let extension = if self.encryption_key.is_some() { "sql.gz.enc" } else { "sql.gz" };
+        let key = format!("backups/{}/{}.{}", db_id, timestamp, extension);
 
         // Upload to R2
         self.client
             .put_object()
             .bucket(&self.bucket)
             .key(&key)
-            .body(ByteStream::from(compressed))
+            .body(ByteStream::from(body))
             .content_type("application/gzip")
             .send()
             .await
@@ -79,12 +194,19 @@ impl BackupManager {
             db_id, key, size
         );
 
+        if let Err(e) = self.prune_backups(db_id, self.retention_policy).await {
+            tracing::warn!("Failed to prune old backups for {}: {}", db_id, e);
+        }
+
         Ok((key, size))
     }
 
-    /// Download a backup from R2 and decompress it
+    /// Download a backup from R2 and decompress it, decrypting first if it
+    /// was written encrypted (detected from the object's magic bytes rather
+    /// than the current config, so objects uploaded before encryption was
+    /// enabled still download fine)
     /// Returns the raw SQL data
-    pub async fn download_backup(&self, key: &str) -> Result<Vec<u8>> {
+    async fn download_backup(&self, key: &str) -> Result<Vec<u8>> {
         let response = self
             .client
             .get_object()
@@ -94,7 +216,7 @@ impl BackupManager {
             .await
             .map_err(|e| AppError::R2(format!("Failed to download backup: {}", e)))?;
 
-        let compressed = response
+        let body = response
             .body
             .collect()
             .await
@@ -102,6 +224,15 @@ impl BackupManager {
             .into_bytes()
             .to_vec();
 
+        let compressed = if encryption::is_encrypted(&body) {
+            let key = self.encryption_key.ok_or_else(|| {
+                AppError::RestoreFailed("Backup is encrypted but BACKUP_ENCRYPTION_KEY is not set".to_string())
+            })?;
+            encryption::decrypt(&body, &key)?
+        } else {
+            body
+        };
+
         let sql_data = decompress_gzip(&compressed)?;
 
         info!(
@@ -114,8 +245,162 @@ impl BackupManager {
         Ok(sql_data)
     }
 
+    /// Upload a database dump to R2 without buffering it in memory.
+    ///
+    /// `source` is typically the stdout stream of a running `docker exec`
+    /// dump command. Chunks are gzip-compressed on the fly and shipped to
+    /// R2 as a multipart upload so memory use stays bounded regardless of
+    /// database size. Returns (object_key, source_bytes_read).
+    ///
+    /// NOTE: unlike `upload_backup`, this path is not encrypted even when
+    /// `BACKUP_ENCRYPTION_KEY` is set - whole-object AEAD needs the full
+    /// ciphertext up front, which conflicts with streaming multipart upload.
+    /// Encrypting this path would need a chunked AEAD scheme, which is out
+    /// of scope here.
+    async fn upload_backup_stream(
+        &self,
+        db_id: Uuid,
+        mut source: BoxedByteStream,
+    ) -> Result<(String, i64)> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let key = format!("backups/{}/{}.sql.gz", db_id, timestamp);
+
+        let create = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&key)
+            .content_type("application/gzip")
+            .send()
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to start multipart upload: {}", e)))?;
+
+        let upload_id = create
+            .upload_id()
+            .ok_or_else(|| AppError::R2("Multipart upload response missing upload id".to_string()))?
+            .to_string();
+
+        let result = self
+            .stream_parts_to_upload(&key, &upload_id, &mut source)
+            .await;
+
+        let (completed_parts, source_bytes) = match result {
+            Ok(parts) => parts,
+            Err(e) => {
+                let _ = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(&self.bucket)
+                    .key(&key)
+                    .upload_id(&upload_id)
+                    .send()
+                    .await;
+                return Err(e);
+            }
+        };
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(&key)
+            .upload_id(&upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(completed_parts))
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to complete multipart upload: {}", e)))?;
+
+        info!(
+            "Streamed backup for {} to {} ({} bytes source)",
+            db_id, key, source_bytes
+        );
+
+        if let Err(e) = self.prune_backups(db_id, self.retention_policy).await {
+            tracing::warn!("Failed to prune old backups for {}: {}", db_id, e);
+        }
+
+        Ok((key, source_bytes))
+    }
+
+    /// Download a backup from R2, decompressing it incrementally instead of
+    /// buffering the whole compressed (or decompressed) object in memory.
+    ///
+    /// NOTE: does not decrypt - see the NOTE on `upload_backup_stream`.
+    /// Paired with that method, objects at this path are never encrypted.
+    async fn download_backup_stream(&self, key: &str) -> Result<BoxedByteStream> {
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to download backup: {}", e)))?;
+
+        let state = (response.body, GzDecoder::new(Vec::new()), false);
+
+        Ok(Box::pin(futures::stream::unfold(
+            state,
+            |(mut body, mut decoder, finished)| async move {
+                if finished {
+                    return None;
+                }
+
+                loop {
+                    match body.next().await {
+                        Some(Ok(bytes)) => {
+                            if let Err(e) = decoder.write_all(&bytes) {
+                                return Some((
+                                    Err(AppError::RestoreFailed(format!(
+                                        "Decompression failed: {}",
+                                        e
+                                    ))),
+                                    (body, decoder, true),
+                                ));
+                            }
+
+                            if decoder.get_ref().is_empty() {
+                                // Not enough compressed input yet to produce output
+                                continue;
+                            }
+
+                            let out = std::mem::take(decoder.get_mut());
+                            return Some((Ok(Bytes::from(out)), (body, decoder, false)));
+                        }
+                        None => {
+                            if let Err(e) = decoder.try_finish() {
+                                return Some((
+                                    Err(AppError::RestoreFailed(format!(
+                                        "Decompression failed: {}",
+                                        e
+                                    ))),
+                                    (body, decoder, true),
+                                ));
+                            }
+
+                            let out = std::mem::take(decoder.get_mut());
+                            if out.is_empty() {
+                                return None;
+                            }
+                            return Some((Ok(Bytes::from(out)), (body, decoder, true)));
+                        }
+                        Some(Err(e)) => {
+                            return Some((
+                                Err(AppError::R2(format!("Failed to read backup body: {}", e))),
+                                (body, decoder, true),
+                            ));
+                        }
+                    }
+                }
+            },
+        )))
+    }
+
     /// Check if a backup exists
-    pub async fn backup_exists(&self, key: &str) -> Result<bool> {
+    async fn backup_exists(&self, key: &str) -> Result<bool> {
         match self
             .client
             .head_object()
@@ -140,7 +425,7 @@ impl BackupManager {
     }
 
     /// Delete a backup from R2
-    pub async fn delete_backup(&self, key: &str) -> Result<()> {
+    async fn delete_backup(&self, key: &str) -> Result<()> {
         self.client
             .delete_object()
             .bucket(&self.bucket)
@@ -153,25 +438,103 @@ impl BackupManager {
 
         Ok(())
     }
-}
 
-/// Compress data with gzip
-fn compress_gzip(data: &[u8]) -> Result<Vec<u8>> {
-    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
-    encoder
-        .write_all(data)
-        .map_err(|e| AppError::BackupFailed(format!("Compression failed: {}", e)))?;
-    encoder
-        .finish()
-        .map_err(|e| AppError::BackupFailed(format!("Compression finalize failed: {}", e)))
-}
+    /// List every backup for `db_id` under `backups/{db_id}/`, newest first
+    async fn list_backups(&self, db_id: Uuid) -> Result<Vec<BackupEntry>> {
+        let prefix = format!("backups/{}/", db_id);
+        let mut entries = Vec::new();
+        let mut continuation_token = None;
+
+        loop {
+            let mut request = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(&prefix);
+            if let Some(token) = &continuation_token {
+                request = request.continuation_token(token);
+            }
+
+            let response = request
+                .send()
+                .await
+                .map_err(|e| AppError::R2(format!("Failed to list backups: {}", e)))?;
+
+            for object in response.contents() {
+                let Some(key) = object.key() else { continue };
+                let Some(file_name) = key.rsplit('/').next() else { continue };
+                let Some(timestamp) = parse_backup_timestamp(file_name) else { continue };
+                entries.push(BackupEntry {
+                    key: key.to_string(),
+                    timestamp,
+                    size_bytes: object.size().unwrap_or(0),
+                });
+            }
+
+            continuation_token = response.next_continuation_token().map(|t| t.to_string());
+            if continuation_token.is_none() {
+                break;
+            }
+        }
 
-/// Decompress gzip data
-fn decompress_gzip(data: &[u8]) -> Result<Vec<u8>> {
-    let mut decoder = GzDecoder::new(data);
-    let mut decompressed = Vec::new();
-    decoder
-        .read_to_end(&mut decompressed)
-        .map_err(|e| AppError::RestoreFailed(format!("Decompression failed: {}", e)))?;
-    Ok(decompressed)
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    /// Delete backups for `db_id` that fall outside `policy`
+    async fn prune_backups(&self, db_id: Uuid, policy: RetentionPolicy) -> Result<Vec<String>> {
+        let entries = self.list_backups(db_id).await?;
+        let prunable = select_prunable(entries, policy);
+
+        let mut deleted = Vec::with_capacity(prunable.len());
+        for entry in prunable {
+            self.delete_backup(&entry.key).await?;
+            deleted.push(entry.key);
+        }
+
+        Ok(deleted)
+    }
+
+    /// Issue a presigned GET URL for an existing backup, letting an API
+    /// client pull the object directly from R2 without streaming it through
+    /// this service. Valid for `ttl`.
+    async fn presigned_download_url(&self, key: &str, ttl: Duration) -> Result<String> {
+        let presigning_config = PresigningConfig::expires_in(ttl)
+            .map_err(|e| AppError::PresignFailed(format!("Invalid presign TTL: {}", e)))?;
+
+        let presigned = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| AppError::PresignFailed(format!("Failed to presign download: {}", e)))?;
+
+        Ok(presigned.uri().to_string())
+    }
+
+    /// Issue a presigned PUT URL for a new backup object, letting an API
+    /// client push a dump directly to R2 without streaming it through this
+    /// service. Returns (url, object_key); the key follows the same
+    /// `backups/{db_id}/{timestamp}.sql.gz` convention as `upload_backup`.
+    async fn presigned_upload_url(&self, db_id: Uuid, ttl: Duration) -> Result<(String, String)> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let key = format!("backups/{}/{}.sql.gz", db_id, timestamp);
+
+        let presigning_config = PresigningConfig::expires_in(ttl)
+            .map_err(|e| AppError::PresignFailed(format!("Invalid presign TTL: {}", e)))?;
+
+        let presigned = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(&key)
+            .content_type("application/gzip")
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| AppError::PresignFailed(format!("Failed to presign upload: {}", e)))?;
+
+        Ok((presigned.uri().to_string(), key))
+    }
 }
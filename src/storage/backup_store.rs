@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDateTime, Utc};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+use std::pin::Pin;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+/// A boxed, owned byte stream - the common currency between backup stores,
+/// since `impl Stream` can't appear in a trait object's method signature
+pub type BoxedByteStream = Pin<Box<dyn futures::Stream<Item = Result<Bytes>> + Send>>;
+
+/// One backup object, as returned by `BackupStore::list_backups`
+#[derive(Debug, Clone)]
+pub struct BackupEntry {
+    pub key: String,
+    pub timestamp: DateTime<Utc>,
+    pub size_bytes: i64,
+}
+
+/// Retention policy applied by `BackupStore::prune_backups`. An entry is
+/// pruned if it fails either constraint that's enabled (0 disables that
+/// constraint), so `max_age_days` acts as a hard cutoff even within the
+/// `retain_count` most recent backups.
+#[derive(Debug, Clone, Copy)]
+pub struct RetentionPolicy {
+    /// Keep at most this many backups (0 = unlimited)
+    pub retain_count: u32,
+    /// Prune backups older than this many days (0 = unlimited)
+    pub max_age_days: u32,
+}
+
+/// Storage abstraction for backup blobs, implemented by both the R2
+/// (S3-compatible) store and a local-filesystem store so the service can run
+/// (and be tested) without any cloud credentials, selected at startup from
+/// `Config`.
+#[async_trait]
+pub trait BackupStore: Send + Sync {
+    /// Upload a database backup (SQL dump). Returns (object_key, size_bytes).
+    async fn upload_backup(&self, db_id: Uuid, sql_data: &[u8]) -> Result<(String, i64)>;
+
+    /// Download a backup and decompress it, returning the raw SQL data
+    async fn download_backup(&self, key: &str) -> Result<Vec<u8>>;
+
+    /// Stream a database dump straight to storage without buffering the
+    /// whole thing in memory. Returns (object_key, source_bytes_read).
+    async fn upload_backup_stream(
+        &self,
+        db_id: Uuid,
+        source: BoxedByteStream,
+    ) -> Result<(String, i64)>;
+
+    /// Stream a backup back out, decompressing incrementally instead of
+    /// buffering the whole object in memory
+    async fn download_backup_stream(&self, key: &str) -> Result<BoxedByteStream>;
+
+    /// Check if a backup exists
+    async fn backup_exists(&self, key: &str) -> Result<bool>;
+
+    /// Delete a backup
+    async fn delete_backup(&self, key: &str) -> Result<()>;
+
+    /// List every backup belonging to `db_id`, newest first
+    async fn list_backups(&self, db_id: Uuid) -> Result<Vec<BackupEntry>>;
+
+    /// Delete backups for `db_id` that fall outside `policy`. Returns the
+    /// keys that were deleted.
+    async fn prune_backups(&self, db_id: Uuid, policy: RetentionPolicy) -> Result<Vec<String>>;
+
+    /// Issue a presigned GET URL for an existing backup, letting an API
+    /// client pull the object directly from storage without streaming it
+    /// through this service. Valid for `ttl`. Only meaningful for stores with
+    /// a notion of presigned URLs (e.g. S3-compatible R2); the default
+    /// errors out, which is what `LocalBackupStore` falls back to.
+    async fn presigned_download_url(&self, _key: &str, _ttl: std::time::Duration) -> Result<String> {
+        Err(AppError::PresignFailed(
+            "this backup store does not support presigned URLs".to_string(),
+        ))
+    }
+
+    /// Issue a presigned PUT URL for a new backup object. Returns
+    /// (url, object_key); the key follows the same
+    /// `backups/{db_id}/{timestamp}.sql.gz` convention as `upload_backup`.
+    async fn presigned_upload_url(&self, _db_id: Uuid, _ttl: std::time::Duration) -> Result<(String, String)> {
+        Err(AppError::PresignFailed(
+            "this backup store does not support presigned URLs".to_string(),
+        ))
+    }
+}
+
+/// Compress data with gzip
+pub(crate) fn compress_gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .map_err(|e| AppError::BackupFailed(format!("Compression failed: {}", e)))?;
+    encoder
+        .finish()
+        .map_err(|e| AppError::BackupFailed(format!("Compression finalize failed: {}", e)))
+}
+
+/// Decompress gzip data
+pub(crate) fn decompress_gzip(data: &[u8]) -> Result<Vec<u8>> {
+    let mut decoder = GzDecoder::new(data);
+    let mut decompressed = Vec::new();
+    decoder
+        .read_to_end(&mut decompressed)
+        .map_err(|e| AppError::RestoreFailed(format!("Decompression failed: {}", e)))?;
+    Ok(decompressed)
+}
+
+/// Parse the `{timestamp}` component out of a
+/// `backups/{db_id}/{timestamp}.sql.gz[.enc]` key, as written by
+/// `upload_backup`/`upload_backup_stream`
+pub(crate) fn parse_backup_timestamp(file_name: &str) -> Option<DateTime<Utc>> {
+    let stem = file_name.split('.').next()?;
+    NaiveDateTime::parse_from_str(stem, "%Y%m%d_%H%M%S")
+        .ok()
+        .map(|naive| naive.and_utc())
+}
+
+/// Apply `policy` to `entries` (which need not be sorted), returning the
+/// ones that should be pruned. An entry survives only if it's within both
+/// the `retain_count` most recent and the `max_age_days` window - whichever
+/// constraint is enabled and stricter wins.
+pub(crate) fn select_prunable(mut entries: Vec<BackupEntry>, policy: RetentionPolicy) -> Vec<BackupEntry> {
+    entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    let cutoff = (policy.max_age_days > 0)
+        .then(|| Utc::now() - ChronoDuration::days(policy.max_age_days as i64));
+
+    entries
+        .into_iter()
+        .enumerate()
+        .filter(|(i, entry)| {
+            let beyond_retain_count = policy.retain_count > 0 && *i >= policy.retain_count as usize;
+            let too_old = cutoff.is_some_and(|cutoff| entry.timestamp < cutoff);
+            beyond_retain_count || too_old
+        })
+        .map(|(_, entry)| entry)
+        .collect()
+}
@@ -0,0 +1,87 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::Argon2;
+
+use crate::error::{AppError, Result};
+
+/// Identifies an encrypted backup object, distinguishing it from a plain
+/// `.sql.gz` blob written before encryption was turned on
+const MAGIC: [u8; 4] = *b"DBE1";
+const FORMAT_VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+
+/// Fixed, non-secret salt for deriving a key from a passphrase. It only
+/// needs to be fixed (not random) because the secret itself - not the salt -
+/// is what provides the entropy; a random per-install salt would have to be
+/// stored somewhere the attacker we're defending against (bucket access)
+/// can't also read.
+const KEY_DERIVATION_SALT: &[u8] = b"db-api-backup-encryption-salt-v1";
+
+/// Derive a 32-byte AES-256 key from `BACKUP_ENCRYPTION_KEY`. A value that's
+/// already exactly 32 bytes is used as-is (raw key material); anything else
+/// is treated as a passphrase and stretched with Argon2id.
+pub(crate) fn derive_key(secret: &str) -> Result<[u8; 32]> {
+    if secret.len() == 32 {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(secret.as_bytes());
+        return Ok(key);
+    }
+
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(secret.as_bytes(), KEY_DERIVATION_SALT, &mut key)
+        .map_err(|e| AppError::BackupFailed(format!("Failed to derive encryption key: {}", e)))?;
+    Ok(key)
+}
+
+/// Whether `data` looks like it was written by [`encrypt`] - used on
+/// download to stay backward-compatible with objects uploaded before
+/// encryption was enabled.
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.len() >= MAGIC.len() && data[..MAGIC.len()] == MAGIC
+}
+
+/// Encrypt `plaintext` (the gzip-compressed dump) with AES-256-GCM under a
+/// fresh random nonce. Layout: `magic(4) || version(1) || nonce(12) ||
+/// ciphertext+tag`.
+pub(crate) fn encrypt(plaintext: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|e| AppError::BackupFailed(format!("Encryption failed: {}", e)))?;
+
+    let mut out = Vec::with_capacity(MAGIC.len() + 1 + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&MAGIC);
+    out.push(FORMAT_VERSION);
+    out.extend_from_slice(&nonce);
+    out.extend_from_slice(&ciphertext);
+    Ok(out)
+}
+
+/// Reverse [`encrypt`], verifying the AEAD tag in the process
+pub(crate) fn decrypt(data: &[u8], key: &[u8; 32]) -> Result<Vec<u8>> {
+    let header_len = MAGIC.len() + 1 + NONCE_LEN;
+    if data.len() < header_len {
+        return Err(AppError::RestoreFailed(
+            "Encrypted backup is truncated".to_string(),
+        ));
+    }
+
+    let version = data[MAGIC.len()];
+    if version != FORMAT_VERSION {
+        return Err(AppError::RestoreFailed(format!(
+            "Unsupported backup encryption version: {}",
+            version
+        )));
+    }
+
+    let nonce = Nonce::from_slice(&data[MAGIC.len() + 1..header_len]);
+    let ciphertext = &data[header_len..];
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+    cipher
+        .decrypt(nonce, ciphertext)
+        .map_err(|_| AppError::RestoreFailed("Backup decryption/authentication failed".to_string()))
+}
@@ -0,0 +1,278 @@
+use async_trait::async_trait;
+use bytes::Bytes;
+use chrono::Utc;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::StreamExt;
+use std::io::Write;
+use std::path::PathBuf;
+use tokio::io::AsyncWriteExt;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+use super::backup_store::{
+    compress_gzip, decompress_gzip, parse_backup_timestamp, select_prunable, BackupEntry,
+    BackupStore, BoxedByteStream, RetentionPolicy,
+};
+use super::encryption;
+
+/// Filesystem-backed `BackupStore`, keyed by the same
+/// `backups/{db_id}/{timestamp}.sql.gz` layout as the R2 store but rooted
+/// under a configurable local directory. Lets the service run (and be
+/// tested) without any cloud credentials.
+pub struct LocalBackupStore {
+    root: PathBuf,
+    /// AES-256 key derived from `BACKUP_ENCRYPTION_KEY`, if configured. See
+    /// `storage::encryption`.
+    encryption_key: Option<[u8; 32]>,
+    retention_policy: RetentionPolicy,
+}
+
+impl LocalBackupStore {
+    /// `encryption_secret` is `Config::backup_encryption_key` as-is (a raw
+    /// 32-byte key or a passphrase); see `storage::encryption::derive_key`.
+    pub fn new(
+        root: impl Into<PathBuf>,
+        encryption_secret: Option<&str>,
+        retention_policy: RetentionPolicy,
+    ) -> Result<Self> {
+        let encryption_key = encryption_secret.map(encryption::derive_key).transpose()?;
+        Ok(Self {
+            root: root.into(),
+            encryption_key,
+            retention_policy,
+        })
+    }
+
+    fn path_for(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+#[async_trait]
+impl BackupStore for LocalBackupStore {
+    /// Compress and write a database backup (SQL dump) under the local root
+    /// Returns (object_key, size_bytes)
+    async fn upload_backup(&self, db_id: Uuid, sql_data: &[u8]) -> Result<(String, i64)> {
+        let compressed = compress_gzip(sql_data)?;
+        let body = match &self.encryption_key {
+            Some(key) => encryption::encrypt(&compressed, key)?,
+            None => compressed,
+        };
+        let size = body.len() as i64;
+
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let extension = if self.encryption_key.is_some() { "sql.gz.enc" } else { "sql.gz" };
+        let key = format!("backups/{}/{}.{}", db_id, timestamp, extension);
+        let path = self.path_for(&key);
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::R2(format!("Failed to create backup directory: {}", e)))?;
+        }
+
+        tokio::fs::write(&path, &body)
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to write backup: {}", e)))?;
+
+        info!(
+            "Wrote backup for {} to {} ({} bytes compressed)",
+            db_id,
+            path.display(),
+            size
+        );
+
+        if let Err(e) = self.prune_backups(db_id, self.retention_policy).await {
+            tracing::warn!("Failed to prune old backups for {}: {}", db_id, e);
+        }
+
+        Ok((key, size))
+    }
+
+    /// Read a backup from the local root and decompress it, decrypting
+    /// first if it was written encrypted (detected from the file's magic
+    /// bytes rather than the current config, so files written before
+    /// encryption was enabled still read fine)
+    /// Returns the raw SQL data
+    async fn download_backup(&self, key: &str) -> Result<Vec<u8>> {
+        let path = self.path_for(key);
+        let body = tokio::fs::read(&path)
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to read backup {}: {}", key, e)))?;
+
+        let compressed = if encryption::is_encrypted(&body) {
+            let key = self.encryption_key.ok_or_else(|| {
+                AppError::RestoreFailed("Backup is encrypted but BACKUP_ENCRYPTION_KEY is not set".to_string())
+            })?;
+            encryption::decrypt(&body, &key)?
+        } else {
+            body
+        };
+
+        decompress_gzip(&compressed)
+    }
+
+    /// Gzip-compress `source` on the fly and write it straight to disk, so
+    /// memory use stays bounded regardless of database size
+    ///
+    /// NOTE: unlike `upload_backup`, this path is not encrypted even when
+    /// `BACKUP_ENCRYPTION_KEY` is set, for the same reason as the R2 store's
+    /// `upload_backup_stream` - whole-object AEAD needs the full ciphertext
+    /// up front, which would mean buffering the whole dump in memory anyway.
+    async fn upload_backup_stream(
+        &self,
+        db_id: Uuid,
+        mut source: BoxedByteStream,
+    ) -> Result<(String, i64)> {
+        let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+        let key = format!("backups/{}/{}.sql.gz", db_id, timestamp);
+        let path = self.path_for(&key);
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| AppError::R2(format!("Failed to create backup directory: {}", e)))?;
+        }
+
+        let mut file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to create backup file: {}", e)))?;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        let mut source_bytes: i64 = 0;
+
+        while let Some(chunk) = source.next().await {
+            let chunk = chunk?;
+            source_bytes += chunk.len() as i64;
+            encoder
+                .write_all(&chunk)
+                .map_err(|e| AppError::BackupFailed(format!("Compression failed: {}", e)))?;
+
+            let pending = std::mem::take(encoder.get_mut());
+            if !pending.is_empty() {
+                file.write_all(&pending)
+                    .await
+                    .map_err(|e| AppError::R2(format!("Failed to write backup: {}", e)))?;
+            }
+        }
+
+        let final_data = encoder
+            .finish()
+            .map_err(|e| AppError::BackupFailed(format!("Compression finalize failed: {}", e)))?;
+        if !final_data.is_empty() {
+            file.write_all(&final_data)
+                .await
+                .map_err(|e| AppError::R2(format!("Failed to write backup: {}", e)))?;
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to flush backup file: {}", e)))?;
+
+        info!(
+            "Streamed backup for {} to {} ({} bytes source)",
+            db_id,
+            path.display(),
+            source_bytes
+        );
+
+        if let Err(e) = self.prune_backups(db_id, self.retention_policy).await {
+            tracing::warn!("Failed to prune old backups for {}: {}", db_id, e);
+        }
+
+        Ok((key, source_bytes))
+    }
+
+    /// Read a backup back out and decompress it. Unlike the R2 store, the
+    /// whole object is read up front - local disk reads don't carry the
+    /// same latency/backpressure concerns a remote GET does.
+    async fn download_backup_stream(&self, key: &str) -> Result<BoxedByteStream> {
+        let path = self.path_for(key);
+        let body = tokio::fs::read(&path)
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to read backup {}: {}", key, e)))?;
+
+        let compressed = if encryption::is_encrypted(&body) {
+            let key = self.encryption_key.ok_or_else(|| {
+                AppError::RestoreFailed("Backup is encrypted but BACKUP_ENCRYPTION_KEY is not set".to_string())
+            })?;
+            encryption::decrypt(&body, &key)?
+        } else {
+            body
+        };
+
+        let sql_data = decompress_gzip(&compressed)?;
+        Ok(Box::pin(futures::stream::once(async move {
+            Ok(Bytes::from(sql_data))
+        })))
+    }
+
+    /// Check if a backup file exists under the local root
+    async fn backup_exists(&self, key: &str) -> Result<bool> {
+        Ok(tokio::fs::metadata(self.path_for(key)).await.is_ok())
+    }
+
+    /// Delete a backup file from the local root
+    async fn delete_backup(&self, key: &str) -> Result<()> {
+        let path = self.path_for(key);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {
+                info!("Deleted backup: {}", path.display());
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::R2(format!("Failed to delete backup {}: {}", key, e))),
+        }
+    }
+
+    /// List every backup for `db_id` under `{root}/backups/{db_id}/`, newest first
+    async fn list_backups(&self, db_id: Uuid) -> Result<Vec<BackupEntry>> {
+        let dir = self.path_for(&format!("backups/{}", db_id));
+        let mut read_dir = match tokio::fs::read_dir(&dir).await {
+            Ok(read_dir) => read_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::R2(format!("Failed to list backups: {}", e))),
+        };
+
+        let mut entries = Vec::new();
+        while let Some(dir_entry) = read_dir
+            .next_entry()
+            .await
+            .map_err(|e| AppError::R2(format!("Failed to list backups: {}", e)))?
+        {
+            let file_name = dir_entry.file_name();
+            let Some(file_name) = file_name.to_str() else { continue };
+            let Some(timestamp) = parse_backup_timestamp(file_name) else { continue };
+            let metadata = dir_entry
+                .metadata()
+                .await
+                .map_err(|e| AppError::R2(format!("Failed to stat backup {}: {}", file_name, e)))?;
+
+            entries.push(BackupEntry {
+                key: format!("backups/{}/{}", db_id, file_name),
+                timestamp,
+                size_bytes: metadata.len() as i64,
+            });
+        }
+
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(entries)
+    }
+
+    /// Delete backups for `db_id` that fall outside `policy`
+    async fn prune_backups(&self, db_id: Uuid, policy: RetentionPolicy) -> Result<Vec<String>> {
+        let entries = self.list_backups(db_id).await?;
+        let prunable = select_prunable(entries, policy);
+
+        let mut deleted = Vec::with_capacity(prunable.len());
+        for entry in prunable {
+            self.delete_backup(&entry.key).await?;
+            deleted.push(entry.key);
+        }
+
+        Ok(deleted)
+    }
+}
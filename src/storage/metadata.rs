@@ -1,13 +1,20 @@
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use r2d2::{CustomizeConnection, Pool};
+use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, Connection, OptionalExtension};
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::Semaphore;
 use tracing::info;
 use uuid::Uuid;
 
 use crate::error::{AppError, Result};
 
+use super::backend::MetadataBackend;
+use super::migrations;
+
 /// Instance state in the metadata store
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InstanceState {
@@ -35,6 +42,94 @@ impl InstanceState {
     }
 }
 
+/// A shared pool container hosting logical databases for one dialect
+#[derive(Debug, Clone)]
+pub struct PoolContainer {
+    pub dialect: String,
+    pub container_id: String,
+    pub host_port: u16,
+    pub root_password: String,
+    pub created_at: DateTime<Utc>,
+    pub status: String,
+    /// Capacity for this container (0 = unlimited), from `max_instances_per_pool`
+    pub max_instances: u32,
+    /// Number of logical databases currently living in this container,
+    /// persisted so scale-out decisions survive a restart
+    pub instance_count: u32,
+}
+
+/// A point-in-time snapshot of a still-active instance, stored alongside
+/// (not instead of) the single archive-time backup on `StoredInstance`
+#[derive(Debug, Clone)]
+pub struct StoredSnapshot {
+    pub id: Uuid,
+    pub db_id: Uuid,
+    pub backup_key: String,
+    pub size_bytes: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Lifecycle state of a row in the `jobs` table (see `crate::jobs`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobStatus {
+    New,
+    Running,
+    Done,
+    Failed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::New => "new",
+            Self::Running => "running",
+            Self::Done => "done",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "new" => Some(Self::New),
+            "running" => Some(Self::Running),
+            "done" => Some(Self::Done),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
+/// A background job row, persisted so a submitted long-running query or
+/// backup survives a restart and can be polled independently of the request
+/// that submitted it. See `crate::jobs` for the worker loop that claims and
+/// runs these.
+#[derive(Debug, Clone)]
+pub struct StoredJob {
+    pub id: Uuid,
+    pub kind: String,
+    pub payload: String,
+    pub status: JobStatus,
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    pub result: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A single lifecycle transition recorded for an instance, written by
+/// database-side triggers on the `instances` table (plus a few explicit
+/// call sites, for transitions that don't touch the row)
+#[derive(Debug, Clone)]
+pub struct InstanceEvent {
+    pub id: i64,
+    pub db_id: Uuid,
+    pub dialect: String,
+    pub previous_status: Option<String>,
+    pub new_status: String,
+    pub reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Instance data stored in SQLite
 #[derive(Debug, Clone)]
 pub struct StoredInstance {
@@ -51,16 +146,48 @@ pub struct StoredInstance {
     pub archived_at: Option<DateTime<Utc>>,
     pub backup_key: Option<String>,
     pub backup_size_bytes: Option<i64>,
+    /// Highest migration version applied to this instance's schema, so
+    /// `restore_instance` can skip migrations that are already current
+    pub schema_version: u32,
+    /// Absolute deadline after which the instance is torn down regardless
+    /// of activity, set at creation from `Config::lease_ttl_secs`. `None`
+    /// when no hard TTL applies.
+    pub lease_expires_at: Option<DateTime<Utc>>,
+}
+
+/// Applied to every connection as the pool opens it, so a
+/// connection created lazily mid-run gets the same WAL/busy-timeout
+/// settings as the ones opened at startup.
+#[derive(Debug)]
+struct ConnectionSetup {
+    busy_timeout: Duration,
+}
+
+impl CustomizeConnection<Connection, rusqlite::Error> for ConnectionSetup {
+    fn on_acquire(&self, conn: &mut Connection) -> std::result::Result<(), rusqlite::Error> {
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.busy_timeout(self.busy_timeout)?;
+        Ok(())
+    }
 }
 
 /// SQLite-backed metadata store for instance tracking
+///
+/// Connections are checked out of an `r2d2` pool rather than held behind a
+/// single shared `Mutex`, so readers (`get_instance`, `list_active_instances`,
+/// ...) can run concurrently under WAL instead of serializing behind one
+/// global lock. `semaphore` bounds in-flight checkouts to the pool size, so
+/// callers wait on a permit (an async, runtime-friendly wait) instead of
+/// blocking a worker thread on a full pool.
 pub struct MetadataStore {
-    conn: Arc<Mutex<Connection>>,
+    pool: Pool<SqliteConnectionManager>,
+    semaphore: Arc<Semaphore>,
 }
 
 impl MetadataStore {
     /// Create a new metadata store, initializing the database if needed
-    pub fn new<P: AsRef<Path>>(db_path: P) -> Result<Self> {
+    pub fn new<P: AsRef<Path>>(db_path: P, pool_size: u32) -> Result<Self> {
         // Ensure parent directory exists
         if let Some(parent) = db_path.as_ref().parent() {
             std::fs::create_dir_all(parent).map_err(|e| {
@@ -68,268 +195,1009 @@ impl MetadataStore {
             })?;
         }
 
-        let conn = Connection::open(db_path)
-            .map_err(|e| AppError::Storage(format!("Failed to open metadata database: {}", e)))?;
-
-        // Initialize schema
-        conn.execute_batch(
-            r#"
-            CREATE TABLE IF NOT EXISTS instances (
-                db_id TEXT PRIMARY KEY,
-                dialect TEXT NOT NULL,
-                db_name TEXT NOT NULL,
-                db_user TEXT NOT NULL,
-                db_password TEXT NOT NULL,
-                status TEXT NOT NULL,
-                container_id TEXT,
-                host_port INTEGER,
-                created_at TEXT NOT NULL,
-                last_activity TEXT NOT NULL,
-                archived_at TEXT,
-                backup_key TEXT,
-                backup_size_bytes INTEGER
-            );
-
-            CREATE INDEX IF NOT EXISTS idx_instances_status ON instances(status);
-            CREATE INDEX IF NOT EXISTS idx_instances_last_activity ON instances(last_activity);
-            "#,
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to initialize schema: {}", e)))?;
-
-        info!("Metadata store initialized");
+        let manager = SqliteConnectionManager::file(db_path.as_ref());
+        let pool = Pool::builder()
+            .max_size(pool_size)
+            .connection_customizer(Box::new(ConnectionSetup {
+                busy_timeout: Duration::from_secs(5),
+            }))
+            .build(manager)
+            .map_err(|e| AppError::Storage(format!("Failed to build metadata pool: {}", e)))?;
+
+        // Bring the schema up to date, tracking applied migrations in
+        // SQLite's own `PRAGMA user_version` so re-opening an existing
+        // deployment's database only runs what's new. Runs against a
+        // dedicated connection up front, before any pooled checkout races it.
+        let mut conn = pool
+            .get()
+            .map_err(|e| AppError::Storage(format!("Failed to get metadata connection: {}", e)))?;
+        migrations::migrate(&mut conn)?;
+        drop(conn);
+
+        info!("Metadata store initialized with pool size {}", pool_size);
 
         Ok(Self {
-            conn: Arc::new(Mutex::new(conn)),
+            pool,
+            semaphore: Arc::new(Semaphore::new(pool_size as usize)),
         })
     }
 
-    /// Insert a new instance
-    pub fn insert_instance(&self, instance: &StoredInstance) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            r#"
-            INSERT INTO instances (
-                db_id, dialect, db_name, db_user, db_password, status,
-                container_id, host_port, created_at, last_activity,
-                archived_at, backup_key, backup_size_bytes
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
-            "#,
-            params![
-                instance.db_id.to_string(),
-                instance.dialect,
-                instance.db_name,
-                instance.db_user,
-                instance.db_password,
-                instance.status.as_str(),
-                instance.container_id,
-                instance.host_port,
-                instance.created_at.to_rfc3339(),
-                instance.last_activity.to_rfc3339(),
-                instance.archived_at.map(|dt| dt.to_rfc3339()),
-                instance.backup_key,
-                instance.backup_size_bytes,
-            ],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to insert instance: {}", e)))?;
+    /// Acquire a semaphore permit, then run `f` against a pooled connection
+    /// on the blocking thread pool. Keeps synchronous rusqlite calls off the
+    /// async runtime threads without making every metadata call compete for
+    /// one global lock. A panic inside `f` is resumed on the calling task,
+    /// the same way it would surface if `f` had run inline.
+    async fn run_blocking<F, T>(&self, f: F) -> Result<T>
+    where
+        F: FnOnce(&Connection) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|e| AppError::Storage(format!("Metadata semaphore closed: {}", e)))?;
 
-        Ok(())
+        let pool = self.pool.clone();
+        let handle = tokio::task::spawn_blocking(move || {
+            let conn = pool
+                .get()
+                .map_err(|e| AppError::Storage(format!("Failed to get metadata connection: {}", e)))?;
+            f(&conn)
+        });
+
+        match handle.await {
+            Ok(result) => result,
+            Err(join_err) => std::panic::resume_unwind(join_err.into_panic()),
+        }
     }
 
-    /// Get an instance by ID
-    pub fn get_instance(&self, db_id: Uuid) -> Result<Option<StoredInstance>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn
-            .prepare(
+    /// Checkpoint the WAL file back into the main database and truncate it,
+    /// so it doesn't grow unbounded under frequent short writes. Applies
+    /// `busy_timeout` to the connection first, so a write already in
+    /// progress causes this to wait rather than fail outright; callers that
+    /// want a hard skip instead should pass a short timeout. Returns the
+    /// number of WAL frames checkpointed (and thus reclaimed).
+    async fn checkpoint_wal(&self, busy_timeout: Duration) -> Result<i64> {
+        self.run_blocking(move |conn| {
+            conn.busy_timeout(busy_timeout)
+                .map_err(|e| AppError::Storage(format!("Failed to set busy timeout: {}", e)))?;
+
+            let (_busy, _log_frames, checkpointed): (i64, i64, i64) = conn
+                .query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |row| {
+                    Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+                })
+                .map_err(|e| AppError::Storage(format!("WAL checkpoint failed: {}", e)))?;
+
+            Ok(checkpointed)
+        })
+        .await
+    }
+
+    /// Background task: periodically truncates the WAL file. A 0 interval
+    /// disables the task entirely.
+    pub fn start_wal_checkpoint_task(self: Arc<Self>, interval_secs: u64, busy_timeout_secs: u64) {
+        if interval_secs == 0 {
+            info!("WAL checkpoint task disabled (interval is 0)");
+            return;
+        }
+
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+            let busy_timeout = Duration::from_secs(busy_timeout_secs);
+
+            loop {
+                ticker.tick().await;
+                match self.checkpoint_wal(busy_timeout).await {
+                    Ok(checkpointed) if checkpointed > 0 => {
+                        info!("WAL checkpoint reclaimed {} page(s)", checkpointed);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("WAL checkpoint failed: {}", e),
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl MetadataBackend for MetadataStore {
+    /// Insert a new instance
+    async fn insert_instance(&self, instance: &StoredInstance) -> Result<()> {
+        let instance = instance.clone();
+        self.run_blocking(move |conn| {
+            conn.execute(
                 r#"
-            SELECT db_id, dialect, db_name, db_user, db_password, status,
-                   container_id, host_port, created_at, last_activity,
-                   archived_at, backup_key, backup_size_bytes
-            FROM instances WHERE db_id = ?1
-            "#,
+                INSERT INTO instances (
+                    db_id, dialect, db_name, db_user, db_password, status,
+                    container_id, host_port, created_at, last_activity,
+                    archived_at, backup_key, backup_size_bytes, schema_version,
+                    lease_expires_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
+                "#,
+                params![
+                    instance.db_id.to_string(),
+                    instance.dialect,
+                    instance.db_name,
+                    instance.db_user,
+                    instance.db_password,
+                    instance.status.as_str(),
+                    instance.container_id,
+                    instance.host_port,
+                    instance.created_at.to_rfc3339(),
+                    instance.last_activity.to_rfc3339(),
+                    instance.archived_at.map(|dt| dt.to_rfc3339()),
+                    instance.backup_key,
+                    instance.backup_size_bytes,
+                    instance.schema_version,
+                    instance.lease_expires_at.map(|dt| dt.to_rfc3339()),
+                ],
             )
-            .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+            .map_err(|e| AppError::Storage(format!("Failed to insert instance: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Get an instance by ID
+    async fn get_instance(&self, db_id: Uuid) -> Result<Option<StoredInstance>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances WHERE db_id = ?1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
 
-        let result = stmt
-            .query_row(params![db_id.to_string()], |row| {
-                Ok(Self::row_to_instance(row)?)
-            })
-            .optional()
-            .map_err(|e| AppError::Storage(format!("Failed to query instance: {}", e)))?;
+            let result = stmt
+                .query_row(params![db_id.to_string()], |row| {
+                    Ok(StoredInstance::from_row(row)?)
+                })
+                .optional()
+                .map_err(|e| AppError::Storage(format!("Failed to query instance: {}", e)))?;
 
-        Ok(result)
+            Ok(result)
+        })
+        .await
     }
 
     /// Update an instance
-    pub fn update_instance(&self, instance: &StoredInstance) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            r#"
-            UPDATE instances SET
-                dialect = ?2, db_name = ?3, db_user = ?4, db_password = ?5,
-                status = ?6, container_id = ?7, host_port = ?8,
-                created_at = ?9, last_activity = ?10, archived_at = ?11,
-                backup_key = ?12, backup_size_bytes = ?13
-            WHERE db_id = ?1
-            "#,
-            params![
-                instance.db_id.to_string(),
-                instance.dialect,
-                instance.db_name,
-                instance.db_user,
-                instance.db_password,
-                instance.status.as_str(),
-                instance.container_id,
-                instance.host_port,
-                instance.created_at.to_rfc3339(),
-                instance.last_activity.to_rfc3339(),
-                instance.archived_at.map(|dt| dt.to_rfc3339()),
-                instance.backup_key,
-                instance.backup_size_bytes,
-            ],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to update instance: {}", e)))?;
+    async fn update_instance(&self, instance: &StoredInstance) -> Result<()> {
+        let instance = instance.clone();
+        self.run_blocking(move |conn| {
+            conn.execute(
+                r#"
+                UPDATE instances SET
+                    dialect = ?2, db_name = ?3, db_user = ?4, db_password = ?5,
+                    status = ?6, container_id = ?7, host_port = ?8,
+                    created_at = ?9, last_activity = ?10, archived_at = ?11,
+                    backup_key = ?12, backup_size_bytes = ?13, schema_version = ?14,
+                    lease_expires_at = ?15
+                WHERE db_id = ?1
+                "#,
+                params![
+                    instance.db_id.to_string(),
+                    instance.dialect,
+                    instance.db_name,
+                    instance.db_user,
+                    instance.db_password,
+                    instance.status.as_str(),
+                    instance.container_id,
+                    instance.host_port,
+                    instance.created_at.to_rfc3339(),
+                    instance.last_activity.to_rfc3339(),
+                    instance.archived_at.map(|dt| dt.to_rfc3339()),
+                    instance.backup_key,
+                    instance.backup_size_bytes,
+                    instance.schema_version,
+                    instance.lease_expires_at.map(|dt| dt.to_rfc3339()),
+                ],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to update instance: {}", e)))?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     /// Mark an instance as archived with backup info
-    pub fn mark_archived(&self, db_id: Uuid, backup_key: &str, size: i64) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now().to_rfc3339();
-        conn.execute(
-            r#"
-            UPDATE instances SET
-                status = 'archived',
-                container_id = NULL,
-                host_port = NULL,
-                archived_at = ?2,
-                backup_key = ?3,
-                backup_size_bytes = ?4
-            WHERE db_id = ?1
-            "#,
-            params![db_id.to_string(), now, backup_key, size],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to mark archived: {}", e)))?;
+    async fn mark_archived(&self, db_id: Uuid, backup_key: &str, size: i64) -> Result<()> {
+        let backup_key = backup_key.to_string();
+        self.run_blocking(move |conn| {
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                r#"
+                UPDATE instances SET
+                    status = 'archived',
+                    container_id = NULL,
+                    host_port = NULL,
+                    archived_at = ?2,
+                    backup_key = ?3,
+                    backup_size_bytes = ?4
+                WHERE db_id = ?1
+                "#,
+                params![db_id.to_string(), now, backup_key, size],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to mark archived: {}", e)))?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     /// Mark an instance as active with container info
-    pub fn mark_active(&self, db_id: Uuid, container_id: &str, port: u16) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now().to_rfc3339();
-        conn.execute(
-            r#"
-            UPDATE instances SET
-                status = 'active',
-                container_id = ?2,
-                host_port = ?3,
-                last_activity = ?4,
-                archived_at = NULL
-            WHERE db_id = ?1
-            "#,
-            params![db_id.to_string(), container_id, port, now],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to mark active: {}", e)))?;
+    async fn mark_active(&self, db_id: Uuid, container_id: &str, port: u16) -> Result<()> {
+        let container_id = container_id.to_string();
+        self.run_blocking(move |conn| {
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                r#"
+                UPDATE instances SET
+                    status = 'active',
+                    container_id = ?2,
+                    host_port = ?3,
+                    last_activity = ?4,
+                    archived_at = NULL
+                WHERE db_id = ?1
+                "#,
+                params![db_id.to_string(), container_id, port, now],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to mark active: {}", e)))?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     /// Update status only
-    pub fn update_status(&self, db_id: Uuid, status: InstanceState) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "UPDATE instances SET status = ?2 WHERE db_id = ?1",
-            params![db_id.to_string(), status.as_str()],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to update status: {}", e)))?;
+    async fn update_status(&self, db_id: Uuid, status: InstanceState) -> Result<()> {
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "UPDATE instances SET status = ?2 WHERE db_id = ?1",
+                params![db_id.to_string(), status.as_str()],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to update status: {}", e)))?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
+    }
+
+    /// Record the highest migration version applied to an instance's schema
+    async fn update_schema_version(&self, db_id: Uuid, version: u32) -> Result<()> {
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "UPDATE instances SET schema_version = ?2 WHERE db_id = ?1",
+                params![db_id.to_string(), version],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to update schema version: {}", e)))?;
+
+            Ok(())
+        })
+        .await
     }
 
     /// Update last activity timestamp
-    pub fn touch_activity(&self, db_id: Uuid) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        let now = Utc::now().to_rfc3339();
-        conn.execute(
-            "UPDATE instances SET last_activity = ?2 WHERE db_id = ?1",
-            params![db_id.to_string(), now],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to touch activity: {}", e)))?;
+    async fn touch_activity(&self, db_id: Uuid) -> Result<()> {
+        self.run_blocking(move |conn| {
+            let now = Utc::now().to_rfc3339();
+            conn.execute(
+                "UPDATE instances SET last_activity = ?2 WHERE db_id = ?1",
+                params![db_id.to_string(), now],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to touch activity: {}", e)))?;
 
-        Ok(())
+            Ok(())
+        })
+        .await
     }
 
     /// List all active instances
-    pub fn list_active_instances(&self) -> Result<Vec<StoredInstance>> {
-        let conn = self.conn.lock().unwrap();
-        let mut stmt = conn
-            .prepare(
+    async fn list_active_instances(&self) -> Result<Vec<StoredInstance>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances WHERE status = 'active'
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let instances = stmt
+                .query_map([], |row| Ok(StoredInstance::from_row(row)?))
+                .map_err(|e| AppError::Storage(format!("Failed to query instances: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect instances: {}", e)))?;
+
+            Ok(instances)
+        })
+        .await
+    }
+
+    /// Get instances that have been inactive longer than the timeout
+    async fn get_expired_instances(&self, timeout: Duration) -> Result<Vec<StoredInstance>> {
+        self.run_blocking(move |conn| {
+            let cutoff = (Utc::now() - chrono::Duration::from_std(timeout).unwrap()).to_rfc3339();
+
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances
+                WHERE status = 'active' AND last_activity < ?1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let instances = stmt
+                .query_map(params![cutoff], |row| Ok(StoredInstance::from_row(row)?))
+                .map_err(|e| AppError::Storage(format!("Failed to query expired: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect expired: {}", e)))?;
+
+            Ok(instances)
+        })
+        .await
+    }
+
+    /// Get active instances whose absolute lease has passed its grace
+    /// period (`lease_expires_at + grace <= now`), regardless of activity
+    async fn get_lease_expired_instances(&self, grace: Duration) -> Result<Vec<StoredInstance>> {
+        self.run_blocking(move |conn| {
+            let cutoff = (Utc::now() - chrono::Duration::from_std(grace).unwrap()).to_rfc3339();
+
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances
+                WHERE status = 'active' AND lease_expires_at IS NOT NULL AND lease_expires_at <= ?1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let instances = stmt
+                .query_map(params![cutoff], |row| Ok(StoredInstance::from_row(row)?))
+                .map_err(|e| AppError::Storage(format!("Failed to query lease-expired: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect lease-expired: {}", e)))?;
+
+            Ok(instances)
+        })
+        .await
+    }
+
+    /// Delete an instance from the metadata store
+    async fn delete_instance(&self, db_id: Uuid) -> Result<()> {
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "DELETE FROM instances WHERE db_id = ?1",
+                params![db_id.to_string()],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to delete instance: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    /// Get a pool container by its container id
+    async fn get_pool_container(&self, container_id: &str) -> Result<Option<PoolContainer>> {
+        let container_id = container_id.to_string();
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT dialect, container_id, host_port, root_password, created_at, status,
+                       max_instances, instance_count
+                FROM pool_containers WHERE container_id = ?1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let result = stmt
+                .query_row(params![container_id], |row| Ok(Self::row_to_pool_container(row)?))
+                .optional()
+                .map_err(|e| AppError::Storage(format!("Failed to query pool container: {}", e)))?;
+
+            Ok(result)
+        })
+        .await
+    }
+
+    /// List every pool container known to the metadata store
+    async fn list_pool_containers(&self) -> Result<Vec<PoolContainer>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT dialect, container_id, host_port, root_password, created_at, status,
+                       max_instances, instance_count
+                FROM pool_containers
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let pools = stmt
+                .query_map([], |row| Ok(Self::row_to_pool_container(row)?))
+                .map_err(|e| AppError::Storage(format!("Failed to query pool containers: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect pool containers: {}", e)))?;
+
+            Ok(pools)
+        })
+        .await
+    }
+
+    /// List every pool container registered for a dialect, for least-loaded
+    /// selection and scale-out decisions
+    async fn list_pool_containers_for_dialect(&self, dialect: &str) -> Result<Vec<PoolContainer>> {
+        let dialect = dialect.to_string();
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT dialect, container_id, host_port, root_password, created_at, status,
+                       max_instances, instance_count
+                FROM pool_containers WHERE dialect = ?1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let pools = stmt
+                .query_map(params![dialect], |row| Ok(Self::row_to_pool_container(row)?))
+                .map_err(|e| AppError::Storage(format!("Failed to query pool containers: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect pool containers: {}", e)))?;
+
+            Ok(pools)
+        })
+        .await
+    }
+
+    /// Insert or replace a pool container, keyed by its container id
+    async fn upsert_pool_container(&self, pool: &PoolContainer) -> Result<()> {
+        let pool = pool.clone();
+        self.run_blocking(move |conn| {
+            conn.execute(
                 r#"
-            SELECT db_id, dialect, db_name, db_user, db_password, status,
-                   container_id, host_port, created_at, last_activity,
-                   archived_at, backup_key, backup_size_bytes
-            FROM instances WHERE status = 'active'
-            "#,
+                INSERT INTO pool_containers
+                    (container_id, dialect, host_port, root_password, created_at, status, max_instances, instance_count)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT(container_id) DO UPDATE SET
+                    dialect = excluded.dialect,
+                    host_port = excluded.host_port,
+                    root_password = excluded.root_password,
+                    created_at = excluded.created_at,
+                    status = excluded.status,
+                    max_instances = excluded.max_instances,
+                    instance_count = excluded.instance_count
+                "#,
+                params![
+                    pool.container_id,
+                    pool.dialect,
+                    pool.host_port,
+                    pool.root_password,
+                    pool.created_at.to_rfc3339(),
+                    pool.status,
+                    pool.max_instances,
+                    pool.instance_count,
+                ],
             )
-            .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+            .map_err(|e| AppError::Storage(format!("Failed to upsert pool container: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
 
-        let instances = stmt
-            .query_map([], |row| Ok(Self::row_to_instance(row)?))
-            .map_err(|e| AppError::Storage(format!("Failed to query instances: {}", e)))?
-            .collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(|e| AppError::Storage(format!("Failed to collect instances: {}", e)))?;
+    /// Remove a pool container by its container id
+    async fn delete_pool_container(&self, container_id: &str) -> Result<()> {
+        let container_id = container_id.to_string();
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "DELETE FROM pool_containers WHERE container_id = ?1",
+                params![container_id],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to delete pool container: {}", e)))?;
 
-        Ok(instances)
+            Ok(())
+        })
+        .await
     }
 
-    /// Get instances that have been inactive longer than the timeout
-    pub fn get_expired_instances(&self, timeout: Duration) -> Result<Vec<StoredInstance>> {
-        let conn = self.conn.lock().unwrap();
-        let cutoff = (Utc::now() - chrono::Duration::from_std(timeout).unwrap()).to_rfc3339();
+    /// Apply a delta (positive or negative) to a pool container's live
+    /// instance count, so scale-out/scale-in decisions survive a restart
+    async fn adjust_pool_instance_count(&self, container_id: &str, delta: i32) -> Result<()> {
+        let container_id = container_id.to_string();
+        self.run_blocking(move |conn| {
+            conn.execute(
+                r#"
+                UPDATE pool_containers
+                SET instance_count = MAX(0, instance_count + ?2)
+                WHERE container_id = ?1
+                "#,
+                params![container_id, delta],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to adjust pool instance count: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
 
-        let mut stmt = conn
-            .prepare(
+    /// Record a new periodic snapshot for a still-active instance
+    async fn insert_snapshot(&self, snapshot: &StoredSnapshot) -> Result<()> {
+        let snapshot = snapshot.clone();
+        self.run_blocking(move |conn| {
+            conn.execute(
                 r#"
-            SELECT db_id, dialect, db_name, db_user, db_password, status,
-                   container_id, host_port, created_at, last_activity,
-                   archived_at, backup_key, backup_size_bytes
-            FROM instances
-            WHERE status = 'active' AND last_activity < ?1
-            "#,
+                INSERT INTO snapshots (id, db_id, backup_key, size_bytes, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5)
+                "#,
+                params![
+                    snapshot.id.to_string(),
+                    snapshot.db_id.to_string(),
+                    snapshot.backup_key,
+                    snapshot.size_bytes,
+                    snapshot.created_at.to_rfc3339(),
+                ],
             )
-            .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+            .map_err(|e| AppError::Storage(format!("Failed to insert snapshot: {}", e)))?;
 
-        let instances = stmt
-            .query_map(params![cutoff], |row| Ok(Self::row_to_instance(row)?))
-            .map_err(|e| AppError::Storage(format!("Failed to query expired: {}", e)))?
-            .collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(|e| AppError::Storage(format!("Failed to collect expired: {}", e)))?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// List all snapshots for an instance, newest first
+    async fn list_snapshots(&self, db_id: Uuid) -> Result<Vec<StoredSnapshot>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT id, db_id, backup_key, size_bytes, created_at
+                FROM snapshots WHERE db_id = ?1
+                ORDER BY created_at DESC
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let snapshots = stmt
+                .query_map(params![db_id.to_string()], |row| {
+                    Ok(Self::row_to_snapshot(row)?)
+                })
+                .map_err(|e| AppError::Storage(format!("Failed to query snapshots: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect snapshots: {}", e)))?;
 
-        Ok(instances)
+            Ok(snapshots)
+        })
+        .await
     }
 
-    /// Delete an instance from the metadata store
-    pub fn delete_instance(&self, db_id: Uuid) -> Result<()> {
-        let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "DELETE FROM instances WHERE db_id = ?1",
-            params![db_id.to_string()],
-        )
-        .map_err(|e| AppError::Storage(format!("Failed to delete instance: {}", e)))?;
+    /// Find the most recent snapshot at or before a point in time, for
+    /// point-in-time restore
+    async fn get_snapshot_at_or_before(
+        &self,
+        db_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<Option<StoredSnapshot>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT id, db_id, backup_key, size_bytes, created_at
+                FROM snapshots WHERE db_id = ?1 AND created_at <= ?2
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
 
-        Ok(())
+            let result = stmt
+                .query_row(params![db_id.to_string(), at.to_rfc3339()], |row| {
+                    Ok(Self::row_to_snapshot(row)?)
+                })
+                .optional()
+                .map_err(|e| AppError::Storage(format!("Failed to query snapshot: {}", e)))?;
+
+            Ok(result)
+        })
+        .await
+    }
+
+    /// Delete a single snapshot row (the caller is responsible for removing
+    /// the backing backup-store object)
+    async fn delete_snapshot(&self, id: Uuid) -> Result<()> {
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "DELETE FROM snapshots WHERE id = ?1",
+                params![id.to_string()],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to delete snapshot: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn record_event(
+        &self,
+        db_id: Uuid,
+        dialect: &str,
+        previous_status: Option<InstanceState>,
+        new_status: &str,
+        reason: &str,
+    ) -> Result<()> {
+        let dialect = dialect.to_string();
+        let new_status = new_status.to_string();
+        let reason = reason.to_string();
+        self.run_blocking(move |conn| {
+            conn.execute(
+                r#"
+                INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                params![
+                    db_id.to_string(),
+                    dialect,
+                    previous_status.map(|s| s.as_str()),
+                    new_status,
+                    reason,
+                    Utc::now().to_rfc3339(),
+                ],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to record instance event: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn list_instance_events(&self, db_id: Uuid, limit: Option<u32>) -> Result<Vec<InstanceEvent>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT id, db_id, dialect, previous_status, new_status, reason, created_at
+                FROM instance_events WHERE db_id = ?1
+                ORDER BY created_at DESC
+                LIMIT ?2
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let events = stmt
+                .query_map(
+                    params![db_id.to_string(), limit.unwrap_or(u32::MAX)],
+                    |row| Ok(Self::row_to_instance_event(row)?),
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to query instance events: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect instance events: {}", e)))?;
+
+            Ok(events)
+        })
+        .await
+    }
+
+    /// Instance counts grouped by `status`, for the `/metrics` endpoint
+    async fn count_by_status(&self) -> Result<Vec<(String, i64)>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare("SELECT status, COUNT(*) FROM instances GROUP BY status")
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let counts = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+                .map_err(|e| AppError::Storage(format!("Failed to query status counts: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect status counts: {}", e)))?;
+
+            Ok(counts)
+        })
+        .await
     }
 
-    fn row_to_instance(row: &rusqlite::Row) -> rusqlite::Result<StoredInstance> {
-        let db_id_str: String = row.get(0)?;
+    /// Instance counts grouped by `dialect`, for the `/metrics` endpoint
+    async fn count_by_dialect(&self) -> Result<Vec<(String, i64)>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare("SELECT dialect, COUNT(*) FROM instances GROUP BY dialect")
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let counts = stmt
+                .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+                .map_err(|e| AppError::Storage(format!("Failed to query dialect counts: {}", e)))?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Storage(format!("Failed to collect dialect counts: {}", e)))?;
+
+            Ok(counts)
+        })
+        .await
+    }
+
+    /// Total `backup_size_bytes` summed across archived instances, for the
+    /// `/metrics` endpoint
+    async fn sum_backup_bytes(&self) -> Result<i64> {
+        self.run_blocking(move |conn| {
+            conn.query_row(
+                "SELECT COALESCE(SUM(backup_size_bytes), 0) FROM instances WHERE status = 'archived'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to sum backup bytes: {}", e)))
+        })
+        .await
+    }
+
+    async fn enqueue_job(&self, kind: &str, payload: &str) -> Result<Uuid> {
+        let kind = kind.to_string();
+        let payload = payload.to_string();
+        self.run_blocking(move |conn| {
+            let id = Uuid::new_v4();
+            conn.execute(
+                r#"
+                INSERT INTO jobs (id, kind, payload, status, attempts, created_at)
+                VALUES (?1, ?2, ?3, 'new', 0, ?4)
+                "#,
+                params![id.to_string(), kind, payload, Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to enqueue job: {}", e)))?;
+
+            Ok(id)
+        })
+        .await
+    }
+
+    async fn claim_next_job(&self) -> Result<Option<StoredJob>> {
+        self.run_blocking(move |conn| {
+            let now = Utc::now().to_rfc3339();
+            let result = conn
+                .query_row(
+                    r#"
+                UPDATE jobs SET
+                    status = 'running',
+                    attempts = attempts + 1,
+                    heartbeat_at = ?1
+                WHERE id = (
+                    SELECT id FROM jobs WHERE status = 'new' ORDER BY created_at LIMIT 1
+                )
+                RETURNING id, kind, payload, status, attempts, created_at, heartbeat_at, result, error
+                "#,
+                    params![now],
+                    |row| Ok(Self::row_to_job(row)?),
+                )
+                .optional()
+                .map_err(|e| AppError::Storage(format!("Failed to claim job: {}", e)))?;
+
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn heartbeat_job(&self, id: Uuid) -> Result<()> {
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "UPDATE jobs SET heartbeat_at = ?2 WHERE id = ?1",
+                params![id.to_string(), Utc::now().to_rfc3339()],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to update job heartbeat: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn complete_job(&self, id: Uuid, result: &str) -> Result<()> {
+        let result = result.to_string();
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "UPDATE jobs SET status = 'done', result = ?2, error = NULL WHERE id = ?1",
+                params![id.to_string(), result],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to complete job: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn fail_job(&self, id: Uuid, error: &str) -> Result<()> {
+        let error = error.to_string();
+        self.run_blocking(move |conn| {
+            conn.execute(
+                "UPDATE jobs SET status = 'failed', error = ?2 WHERE id = ?1",
+                params![id.to_string(), error],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to fail job: {}", e)))?;
+
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_job(&self, id: Uuid) -> Result<Option<StoredJob>> {
+        self.run_blocking(move |conn| {
+            let mut stmt = conn
+                .prepare(
+                    r#"
+                SELECT id, kind, payload, status, attempts, created_at, heartbeat_at, result, error
+                FROM jobs WHERE id = ?1
+                "#,
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to prepare query: {}", e)))?;
+
+            let result = stmt
+                .query_row(params![id.to_string()], |row| Ok(Self::row_to_job(row)?))
+                .optional()
+                .map_err(|e| AppError::Storage(format!("Failed to query job: {}", e)))?;
+
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn reap_stale_jobs(&self, stale_after: Duration) -> Result<u64> {
+        self.run_blocking(move |conn| {
+            let cutoff = (Utc::now() - chrono::Duration::from_std(stale_after).unwrap()).to_rfc3339();
+
+            let reaped = conn
+                .execute(
+                    r#"
+                UPDATE jobs SET status = 'new', heartbeat_at = NULL
+                WHERE status = 'running' AND heartbeat_at < ?1
+                "#,
+                    params![cutoff],
+                )
+                .map_err(|e| AppError::Storage(format!("Failed to reap stale jobs: {}", e)))?;
+
+            Ok(reaped as u64)
+        })
+        .await
+    }
+}
+
+impl MetadataStore {
+    fn row_to_pool_container(row: &rusqlite::Row) -> rusqlite::Result<PoolContainer> {
+        Ok(PoolContainer {
+            dialect: row.get(0)?,
+            container_id: row.get(1)?,
+            host_port: row.get(2)?,
+            root_password: row.get(3)?,
+            created_at: row_extract_datetime(row, 4, "created_at")?,
+            status: row.get(5)?,
+            max_instances: row.get(6)?,
+            instance_count: row.get(7)?,
+        })
+    }
+
+    fn row_to_instance_event(row: &rusqlite::Row) -> rusqlite::Result<InstanceEvent> {
+        let db_id = row_extract_uuid(row, 1, "db_id")?;
+
+        Ok(InstanceEvent {
+            id: row.get(0)?,
+            db_id,
+            dialect: row.get(2)?,
+            previous_status: row.get(3)?,
+            new_status: row.get(4)?,
+            reason: row.get(5)?,
+            created_at: row_extract_datetime(row, 6, "created_at")?,
+        })
+    }
+
+    fn row_to_snapshot(row: &rusqlite::Row) -> rusqlite::Result<StoredSnapshot> {
+        let id = row_extract_uuid(row, 0, "id")?;
+        let db_id = row_extract_uuid(row, 1, "db_id")?;
+
+        Ok(StoredSnapshot {
+            id,
+            db_id,
+            backup_key: row.get(2)?,
+            size_bytes: row.get(3)?,
+            created_at: row_extract_datetime(row, 4, "created_at")?,
+        })
+    }
+
+    fn row_to_job(row: &rusqlite::Row) -> rusqlite::Result<StoredJob> {
+        let id = row_extract_uuid(row, 0, "id")?;
+        let status_str: String = row.get(3)?;
+
+        Ok(StoredJob {
+            id,
+            kind: row.get(1)?,
+            payload: row.get(2)?,
+            status: JobStatus::from_str(&status_str).unwrap_or(JobStatus::Failed),
+            attempts: row.get(4)?,
+            created_at: row_extract_datetime(row, 5, "created_at")?,
+            heartbeat_at: row_extract_datetime_opt(row, 6, "heartbeat_at")?,
+            result: row.get(7)?,
+            error: row.get(8)?,
+        })
+    }
+}
+
+/// A value that can be read from a `rusqlite::Row` without panicking - used
+/// in place of the ad hoc `row_to_*` helpers for types whose columns need
+/// non-trivial parsing (UUIDs, RFC3339 timestamps), so a malformed value in
+/// the metadata file surfaces as a `rusqlite::Error` (and from there an
+/// `AppError::Storage`) instead of panicking the whole daemon.
+trait FromRow: Sized {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self>;
+}
+
+/// Wraps a column-parse failure with the offending raw value and field name,
+/// so the error surfacing through `AppError::Storage` says what was wrong
+/// rather than just "conversion failed"
+#[derive(Debug)]
+struct RowParseError(String);
+
+impl std::fmt::Display for RowParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for RowParseError {}
+
+fn row_parse_error(idx: usize, field: &str, raw: &str, e: impl std::fmt::Display) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        idx,
+        rusqlite::types::Type::Text,
+        Box::new(RowParseError(format!("invalid {} '{}': {}", field, raw, e))),
+    )
+}
+
+fn row_extract_uuid(row: &rusqlite::Row, idx: usize, field: &str) -> rusqlite::Result<Uuid> {
+    let raw: String = row.get(idx)?;
+    Uuid::parse_str(&raw).map_err(|e| row_parse_error(idx, field, &raw, e))
+}
+
+fn row_extract_datetime(row: &rusqlite::Row, idx: usize, field: &str) -> rusqlite::Result<DateTime<Utc>> {
+    let raw: String = row.get(idx)?;
+    DateTime::parse_from_rfc3339(&raw)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| row_parse_error(idx, field, &raw, e))
+}
+
+fn row_extract_datetime_opt(
+    row: &rusqlite::Row,
+    idx: usize,
+    field: &str,
+) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    let raw: Option<String> = row.get(idx)?;
+    raw.map(|s| {
+        DateTime::parse_from_rfc3339(&s)
+            .map(|dt| dt.with_timezone(&Utc))
+            .map_err(|e| row_parse_error(idx, field, &s, e))
+    })
+    .transpose()
+}
+
+impl FromRow for StoredInstance {
+    fn from_row(row: &rusqlite::Row) -> rusqlite::Result<Self> {
+        let db_id = row_extract_uuid(row, 0, "db_id")?;
         let status_str: String = row.get(5)?;
-        let created_at_str: String = row.get(8)?;
-        let last_activity_str: String = row.get(9)?;
-        let archived_at_str: Option<String> = row.get(10)?;
 
         Ok(StoredInstance {
-            db_id: Uuid::parse_str(&db_id_str).unwrap(),
+            db_id,
             dialect: row.get(1)?,
             db_name: row.get(2)?,
             db_user: row.get(3)?,
@@ -337,19 +1205,13 @@ impl MetadataStore {
             status: InstanceState::from_str(&status_str).unwrap_or(InstanceState::Active),
             container_id: row.get(6)?,
             host_port: row.get(7)?,
-            created_at: DateTime::parse_from_rfc3339(&created_at_str)
-                .unwrap()
-                .with_timezone(&Utc),
-            last_activity: DateTime::parse_from_rfc3339(&last_activity_str)
-                .unwrap()
-                .with_timezone(&Utc),
-            archived_at: archived_at_str.map(|s| {
-                DateTime::parse_from_rfc3339(&s)
-                    .unwrap()
-                    .with_timezone(&Utc)
-            }),
+            created_at: row_extract_datetime(row, 8, "created_at")?,
+            last_activity: row_extract_datetime(row, 9, "last_activity")?,
+            archived_at: row_extract_datetime_opt(row, 10, "archived_at")?,
             backup_key: row.get(11)?,
             backup_size_bytes: row.get(12)?,
+            schema_version: row.get(13)?,
+            lease_expires_at: row_extract_datetime_opt(row, 14, "lease_expires_at")?,
         })
     }
 }
@@ -0,0 +1,207 @@
+use chrono::Utc;
+use rusqlite::Connection;
+use tracing::info;
+
+use crate::error::{AppError, Result};
+
+/// A single forward-only schema migration, applied in order and tracked via
+/// SQLite's `PRAGMA user_version`. Modeled on `rusqlite_migration`'s `M`
+/// type, but inlined here since we only need the forward path.
+pub struct Migration {
+    pub version: u32,
+    pub description: &'static str,
+    pub sql: &'static str,
+}
+
+/// Ordered list of migrations. Append new entries as the schema evolves;
+/// never edit or reorder an entry once it has shipped, since `version` is
+/// compared against deployments' existing `PRAGMA user_version`.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "create instances, pool_containers, and snapshots tables",
+    sql: r#"
+        CREATE TABLE IF NOT EXISTS instances (
+            db_id TEXT PRIMARY KEY,
+            dialect TEXT NOT NULL,
+            db_name TEXT NOT NULL,
+            db_user TEXT NOT NULL,
+            db_password TEXT NOT NULL,
+            status TEXT NOT NULL,
+            container_id TEXT,
+            host_port INTEGER,
+            created_at TEXT NOT NULL,
+            last_activity TEXT NOT NULL,
+            archived_at TEXT,
+            backup_key TEXT,
+            backup_size_bytes INTEGER,
+            schema_version INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_instances_status ON instances(status);
+        CREATE INDEX IF NOT EXISTS idx_instances_last_activity ON instances(last_activity);
+
+        CREATE TABLE IF NOT EXISTS pool_containers (
+            container_id TEXT PRIMARY KEY,
+            dialect TEXT NOT NULL,
+            host_port INTEGER NOT NULL,
+            root_password TEXT NOT NULL,
+            created_at TEXT NOT NULL,
+            status TEXT NOT NULL,
+            max_instances INTEGER NOT NULL DEFAULT 0,
+            instance_count INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_pool_containers_dialect ON pool_containers(dialect);
+
+        CREATE TABLE IF NOT EXISTS snapshots (
+            id TEXT PRIMARY KEY,
+            db_id TEXT NOT NULL,
+            backup_key TEXT NOT NULL,
+            size_bytes INTEGER NOT NULL,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_snapshots_db_id_created_at
+            ON snapshots(db_id, created_at);
+    "#,
+}, Migration {
+    version: 2,
+    description: "add append-only instance_events audit log, written by triggers on instances",
+    sql: r#"
+        CREATE TABLE IF NOT EXISTS instance_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            db_id TEXT NOT NULL,
+            dialect TEXT NOT NULL,
+            previous_status TEXT,
+            new_status TEXT NOT NULL,
+            reason TEXT,
+            created_at TEXT NOT NULL
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_instance_events_db_id
+            ON instance_events(db_id, created_at);
+
+        -- Every row written to `instances` is mirrored into the audit log
+        -- database-side, so the history can't be bypassed by a code path
+        -- that forgets to log it.
+        CREATE TRIGGER IF NOT EXISTS trg_instances_insert
+        AFTER INSERT ON instances
+        BEGIN
+            INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason, created_at)
+            VALUES (NEW.db_id, NEW.dialect, NULL, NEW.status, 'created', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_instances_status_update
+        AFTER UPDATE OF status ON instances
+        WHEN OLD.status <> NEW.status
+        BEGIN
+            INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason, created_at)
+            VALUES (NEW.db_id, NEW.dialect, OLD.status, NEW.status, 'status-changed', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS trg_instances_delete
+        AFTER DELETE ON instances
+        BEGIN
+            INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason, created_at)
+            VALUES (OLD.db_id, OLD.dialect, OLD.status, 'destroyed', 'destroyed', strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+        END;
+    "#,
+}, Migration {
+    version: 3,
+    description: "add lease_expires_at for absolute TTL expiry, alongside the idle timeout",
+    sql: r#"
+        ALTER TABLE instances ADD COLUMN lease_expires_at TEXT;
+    "#,
+}, Migration {
+    version: 4,
+    description: "add schema_migrations table recording each migration's applied_at timestamp",
+    sql: r#"
+        CREATE TABLE IF NOT EXISTS schema_migrations (
+            version INTEGER PRIMARY KEY,
+            applied_at TEXT NOT NULL
+        );
+    "#,
+}, Migration {
+    version: 5,
+    description: "add jobs table backing the background job queue (see crate::jobs)",
+    sql: r#"
+        CREATE TABLE IF NOT EXISTS jobs (
+            id TEXT PRIMARY KEY,
+            kind TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'new',
+            attempts INTEGER NOT NULL DEFAULT 0,
+            created_at TEXT NOT NULL,
+            heartbeat_at TEXT,
+            result TEXT,
+            error TEXT
+        );
+
+        CREATE INDEX IF NOT EXISTS idx_jobs_status_created_at ON jobs(status, created_at);
+    "#,
+}];
+
+/// First migration version tracked in `schema_migrations`. Versions before
+/// this one only ever bumped `PRAGMA user_version`, since the table didn't
+/// exist yet - `migrate` backfills a best-effort row for each of them (with
+/// this run's timestamp, not their true history) once the table is created.
+const FIRST_TRACKED_VERSION: u32 = 4;
+
+/// Run every migration whose version exceeds the database's current
+/// `user_version`, each inside its own transaction, bumping the pragma as
+/// it commits so a crash mid-migration re-runs from the last applied step.
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    let current: u32 = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| AppError::Storage(format!("Failed to read schema version: {}", e)))?;
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        let tx = conn.transaction().map_err(|e| {
+            AppError::Storage(format!("Failed to start migration transaction: {}", e))
+        })?;
+
+        tx.execute_batch(migration.sql).map_err(|e| {
+            AppError::Storage(format!(
+                "Migration {} ({}) failed: {}",
+                migration.version, migration.description, e
+            ))
+        })?;
+
+        tx.pragma_update(None, "user_version", migration.version)
+            .map_err(|e| AppError::Storage(format!("Failed to bump schema version: {}", e)))?;
+
+        // `schema_migrations` itself is created by FIRST_TRACKED_VERSION, so
+        // only record into it from that version on - and backfill rows for
+        // the versions that predate it in that same step, now that the
+        // table exists.
+        if migration.version >= FIRST_TRACKED_VERSION {
+            let now = Utc::now().to_rfc3339();
+            tx.execute(
+                "INSERT OR IGNORE INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+                rusqlite::params![migration.version, now],
+            )
+            .map_err(|e| AppError::Storage(format!("Failed to record migration {}: {}", migration.version, e)))?;
+
+            if migration.version == FIRST_TRACKED_VERSION {
+                for earlier in MIGRATIONS.iter().filter(|m| m.version < FIRST_TRACKED_VERSION) {
+                    tx.execute(
+                        "INSERT OR IGNORE INTO schema_migrations (version, applied_at) VALUES (?1, ?2)",
+                        rusqlite::params![earlier.version, now],
+                    )
+                    .map_err(|e| AppError::Storage(format!("Failed to backfill migration {}: {}", earlier.version, e)))?;
+                }
+            }
+        }
+
+        tx.commit().map_err(|e| {
+            AppError::Storage(format!("Failed to commit migration {}: {}", migration.version, e))
+        })?;
+
+        info!(
+            "Applied metadata schema migration {} ({})",
+            migration.version, migration.description
+        );
+    }
+
+    Ok(())
+}
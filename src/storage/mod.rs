@@ -0,0 +1,18 @@
+pub mod backend;
+pub mod backup;
+pub mod backup_store;
+pub(crate) mod encryption;
+pub mod local_backup;
+pub mod metadata;
+pub mod migrations;
+pub mod postgres;
+
+pub use backend::MetadataBackend;
+pub use backup::BackupManager;
+pub use backup_store::{BackupEntry, BackupStore, BoxedByteStream, RetentionPolicy};
+pub use local_backup::LocalBackupStore;
+pub use metadata::{
+    InstanceEvent, InstanceState, JobStatus, MetadataStore, PoolContainer, StoredInstance,
+    StoredJob, StoredSnapshot,
+};
+pub use postgres::PostgresMetadataStore;
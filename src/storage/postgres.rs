@@ -0,0 +1,859 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use tokio_postgres::{Client, NoTls, Row};
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::error::{AppError, Result};
+
+use super::backend::MetadataBackend;
+use super::metadata::{
+    InstanceEvent, InstanceState, JobStatus, PoolContainer, StoredInstance, StoredJob,
+    StoredSnapshot,
+};
+
+/// Postgres-backed metadata store. Shares one connection set across all
+/// `db-api` nodes, so multiple nodes can coordinate on the same instance
+/// set instead of each keeping a local SQLite file.
+pub struct PostgresMetadataStore {
+    client: Client,
+}
+
+impl PostgresMetadataStore {
+    /// Connect to Postgres and initialize the schema if needed
+    pub async fn new(connection_string: &str) -> Result<Self> {
+        let (client, connection) = tokio_postgres::connect(connection_string, NoTls)
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to connect to Postgres: {}", e)))?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                error!("Postgres connection error: {}", e);
+            }
+        });
+
+        client
+            .batch_execute(
+                r#"
+            CREATE TABLE IF NOT EXISTS instances (
+                db_id TEXT PRIMARY KEY,
+                dialect TEXT NOT NULL,
+                db_name TEXT NOT NULL,
+                db_user TEXT NOT NULL,
+                db_password TEXT NOT NULL,
+                status TEXT NOT NULL,
+                container_id TEXT,
+                host_port INTEGER,
+                created_at TIMESTAMPTZ NOT NULL,
+                last_activity TIMESTAMPTZ NOT NULL,
+                archived_at TIMESTAMPTZ,
+                backup_key TEXT,
+                backup_size_bytes BIGINT,
+                schema_version INTEGER NOT NULL DEFAULT 0,
+                lease_expires_at TIMESTAMPTZ
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_instances_status ON instances(status);
+            CREATE INDEX IF NOT EXISTS idx_instances_last_activity ON instances(last_activity);
+
+            CREATE TABLE IF NOT EXISTS pool_containers (
+                container_id TEXT PRIMARY KEY,
+                dialect TEXT NOT NULL,
+                host_port INTEGER NOT NULL,
+                root_password TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                status TEXT NOT NULL,
+                max_instances INTEGER NOT NULL DEFAULT 0,
+                instance_count INTEGER NOT NULL DEFAULT 0
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_pool_containers_dialect ON pool_containers(dialect);
+
+            CREATE TABLE IF NOT EXISTS snapshots (
+                id TEXT PRIMARY KEY,
+                db_id TEXT NOT NULL,
+                backup_key TEXT NOT NULL,
+                size_bytes BIGINT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_snapshots_db_id_created_at
+                ON snapshots(db_id, created_at);
+
+            CREATE TABLE IF NOT EXISTS instance_events (
+                id BIGSERIAL PRIMARY KEY,
+                db_id TEXT NOT NULL,
+                dialect TEXT NOT NULL,
+                previous_status TEXT,
+                new_status TEXT NOT NULL,
+                reason TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_instance_events_db_id
+                ON instance_events(db_id, created_at);
+
+            -- Mirror every write to `instances` into the audit log
+            -- database-side, so the history can't be bypassed by a code
+            -- path that forgets to log it.
+            CREATE OR REPLACE FUNCTION log_instance_event() RETURNS TRIGGER AS $trigger$
+            BEGIN
+                IF TG_OP = 'INSERT' THEN
+                    INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason)
+                    VALUES (NEW.db_id, NEW.dialect, NULL, NEW.status, 'created');
+                ELSIF TG_OP = 'UPDATE' THEN
+                    IF OLD.status IS DISTINCT FROM NEW.status THEN
+                        INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason)
+                        VALUES (NEW.db_id, NEW.dialect, OLD.status, NEW.status, 'status-changed');
+                    END IF;
+                ELSIF TG_OP = 'DELETE' THEN
+                    INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason)
+                    VALUES (OLD.db_id, OLD.dialect, OLD.status, 'destroyed', 'destroyed');
+                END IF;
+                RETURN NULL;
+            END;
+            $trigger$ LANGUAGE plpgsql;
+
+            DROP TRIGGER IF EXISTS trg_instances_audit ON instances;
+            CREATE TRIGGER trg_instances_audit
+            AFTER INSERT OR UPDATE OR DELETE ON instances
+            FOR EACH ROW EXECUTE FUNCTION log_instance_event();
+
+            CREATE TABLE IF NOT EXISTS jobs (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                payload TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'new',
+                attempts INTEGER NOT NULL DEFAULT 0,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                heartbeat_at TIMESTAMPTZ,
+                result TEXT,
+                error TEXT
+            );
+
+            CREATE INDEX IF NOT EXISTS idx_jobs_status_created_at ON jobs(status, created_at);
+            "#,
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to initialize schema: {}", e)))?;
+
+        info!("Postgres metadata store initialized");
+
+        Ok(Self { client })
+    }
+
+    fn row_to_instance(row: &Row) -> Result<StoredInstance> {
+        let db_id_str: String = row.get(0);
+        let status_str: String = row.get(5);
+
+        Ok(StoredInstance {
+            db_id: Uuid::parse_str(&db_id_str)
+                .map_err(|e| AppError::Storage(format!("Invalid db_id: {}", e)))?,
+            dialect: row.get(1),
+            db_name: row.get(2),
+            db_user: row.get(3),
+            db_password: row.get(4),
+            status: InstanceState::from_str(&status_str).unwrap_or(InstanceState::Active),
+            container_id: row.get(6),
+            host_port: row.get::<_, Option<i32>>(7).map(|p| p as u16),
+            created_at: row.get(8),
+            last_activity: row.get(9),
+            archived_at: row.get(10),
+            backup_key: row.get(11),
+            backup_size_bytes: row.get(12),
+            schema_version: row.get::<_, i32>(13) as u32,
+            lease_expires_at: row.get(14),
+        })
+    }
+
+    fn row_to_pool_container(row: &Row) -> Result<PoolContainer> {
+        Ok(PoolContainer {
+            dialect: row.get(0),
+            container_id: row.get(1),
+            host_port: row.get::<_, i32>(2) as u16,
+            root_password: row.get(3),
+            created_at: row.get(4),
+            status: row.get(5),
+            max_instances: row.get::<_, i32>(6) as u32,
+            instance_count: row.get::<_, i32>(7) as u32,
+        })
+    }
+
+    fn row_to_instance_event(row: &Row) -> Result<InstanceEvent> {
+        let db_id_str: String = row.get(1);
+
+        Ok(InstanceEvent {
+            id: row.get(0),
+            db_id: Uuid::parse_str(&db_id_str)
+                .map_err(|e| AppError::Storage(format!("Invalid db_id: {}", e)))?,
+            dialect: row.get(2),
+            previous_status: row.get(3),
+            new_status: row.get(4),
+            reason: row.get(5),
+            created_at: row.get(6),
+        })
+    }
+
+    fn row_to_snapshot(row: &Row) -> Result<StoredSnapshot> {
+        let id_str: String = row.get(0);
+        let db_id_str: String = row.get(1);
+
+        Ok(StoredSnapshot {
+            id: Uuid::parse_str(&id_str)
+                .map_err(|e| AppError::Storage(format!("Invalid snapshot id: {}", e)))?,
+            db_id: Uuid::parse_str(&db_id_str)
+                .map_err(|e| AppError::Storage(format!("Invalid db_id: {}", e)))?,
+            backup_key: row.get(2),
+            size_bytes: row.get(3),
+            created_at: row.get(4),
+        })
+    }
+
+    fn row_to_job(row: &Row) -> Result<StoredJob> {
+        let id_str: String = row.get(0);
+        let status_str: String = row.get(3);
+
+        Ok(StoredJob {
+            id: Uuid::parse_str(&id_str)
+                .map_err(|e| AppError::Storage(format!("Invalid job id: {}", e)))?,
+            kind: row.get(1),
+            payload: row.get(2),
+            status: JobStatus::from_str(&status_str).unwrap_or(JobStatus::Failed),
+            attempts: row.get::<_, i32>(4) as u32,
+            created_at: row.get(5),
+            heartbeat_at: row.get(6),
+            result: row.get(7),
+            error: row.get(8),
+        })
+    }
+}
+
+#[async_trait]
+impl MetadataBackend for PostgresMetadataStore {
+    async fn insert_instance(&self, instance: &StoredInstance) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                INSERT INTO instances (
+                    db_id, dialect, db_name, db_user, db_password, status,
+                    container_id, host_port, created_at, last_activity,
+                    archived_at, backup_key, backup_size_bytes, schema_version,
+                    lease_expires_at
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+                "#,
+                &[
+                    &instance.db_id.to_string(),
+                    &instance.dialect,
+                    &instance.db_name,
+                    &instance.db_user,
+                    &instance.db_password,
+                    &instance.status.as_str(),
+                    &instance.container_id,
+                    &instance.host_port.map(|p| p as i32),
+                    &instance.created_at,
+                    &instance.last_activity,
+                    &instance.archived_at,
+                    &instance.backup_key,
+                    &instance.backup_size_bytes,
+                    &(instance.schema_version as i32),
+                    &instance.lease_expires_at,
+                ],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to insert instance: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_instance(&self, db_id: Uuid) -> Result<Option<StoredInstance>> {
+        let row = self
+            .client
+            .query_opt(
+                r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances WHERE db_id = $1
+                "#,
+                &[&db_id.to_string()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query instance: {}", e)))?;
+
+        row.map(|r| Self::row_to_instance(&r)).transpose()
+    }
+
+    async fn update_instance(&self, instance: &StoredInstance) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                UPDATE instances SET
+                    dialect = $2, db_name = $3, db_user = $4, db_password = $5,
+                    status = $6, container_id = $7, host_port = $8,
+                    created_at = $9, last_activity = $10, archived_at = $11,
+                    backup_key = $12, backup_size_bytes = $13, schema_version = $14,
+                    lease_expires_at = $15
+                WHERE db_id = $1
+                "#,
+                &[
+                    &instance.db_id.to_string(),
+                    &instance.dialect,
+                    &instance.db_name,
+                    &instance.db_user,
+                    &instance.db_password,
+                    &instance.status.as_str(),
+                    &instance.container_id,
+                    &instance.host_port.map(|p| p as i32),
+                    &instance.created_at,
+                    &instance.last_activity,
+                    &instance.archived_at,
+                    &instance.backup_key,
+                    &instance.backup_size_bytes,
+                    &(instance.schema_version as i32),
+                    &instance.lease_expires_at,
+                ],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to update instance: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_archived(&self, db_id: Uuid, backup_key: &str, size: i64) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                UPDATE instances SET
+                    status = 'archived',
+                    container_id = NULL,
+                    host_port = NULL,
+                    archived_at = $2,
+                    backup_key = $3,
+                    backup_size_bytes = $4
+                WHERE db_id = $1
+                "#,
+                &[&db_id.to_string(), &Utc::now(), &backup_key, &size],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to mark archived: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_active(&self, db_id: Uuid, container_id: &str, port: u16) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                UPDATE instances SET
+                    status = 'active',
+                    container_id = $2,
+                    host_port = $3,
+                    last_activity = $4,
+                    archived_at = NULL
+                WHERE db_id = $1
+                "#,
+                &[
+                    &db_id.to_string(),
+                    &container_id,
+                    &(port as i32),
+                    &Utc::now(),
+                ],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to mark active: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn update_status(&self, db_id: Uuid, status: InstanceState) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE instances SET status = $2 WHERE db_id = $1",
+                &[&db_id.to_string(), &status.as_str()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to update status: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn update_schema_version(&self, db_id: Uuid, version: u32) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE instances SET schema_version = $2 WHERE db_id = $1",
+                &[&db_id.to_string(), &(version as i32)],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to update schema version: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn touch_activity(&self, db_id: Uuid) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE instances SET last_activity = $2 WHERE db_id = $1",
+                &[&db_id.to_string(), &Utc::now()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to touch activity: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list_active_instances(&self) -> Result<Vec<StoredInstance>> {
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances WHERE status = 'active'
+                "#,
+                &[],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query instances: {}", e)))?;
+
+        rows.iter().map(Self::row_to_instance).collect()
+    }
+
+    async fn get_expired_instances(&self, timeout: Duration) -> Result<Vec<StoredInstance>> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(timeout).unwrap();
+
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances
+                WHERE status = 'active' AND last_activity < $1
+                "#,
+                &[&cutoff],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query expired: {}", e)))?;
+
+        rows.iter().map(Self::row_to_instance).collect()
+    }
+
+    async fn get_lease_expired_instances(&self, grace: Duration) -> Result<Vec<StoredInstance>> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(grace).unwrap();
+
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT db_id, dialect, db_name, db_user, db_password, status,
+                       container_id, host_port, created_at, last_activity,
+                       archived_at, backup_key, backup_size_bytes, schema_version,
+                       lease_expires_at
+                FROM instances
+                WHERE status = 'active' AND lease_expires_at IS NOT NULL AND lease_expires_at <= $1
+                "#,
+                &[&cutoff],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query lease-expired: {}", e)))?;
+
+        rows.iter().map(Self::row_to_instance).collect()
+    }
+
+    async fn delete_instance(&self, db_id: Uuid) -> Result<()> {
+        self.client
+            .execute(
+                "DELETE FROM instances WHERE db_id = $1",
+                &[&db_id.to_string()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to delete instance: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_pool_container(&self, container_id: &str) -> Result<Option<PoolContainer>> {
+        let row = self
+            .client
+            .query_opt(
+                r#"
+                SELECT dialect, container_id, host_port, root_password, created_at, status,
+                       max_instances, instance_count
+                FROM pool_containers WHERE container_id = $1
+                "#,
+                &[&container_id],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query pool container: {}", e)))?;
+
+        row.map(|r| Self::row_to_pool_container(&r)).transpose()
+    }
+
+    async fn list_pool_containers(&self) -> Result<Vec<PoolContainer>> {
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT dialect, container_id, host_port, root_password, created_at, status,
+                       max_instances, instance_count
+                FROM pool_containers
+                "#,
+                &[],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query pool containers: {}", e)))?;
+
+        rows.iter().map(Self::row_to_pool_container).collect()
+    }
+
+    async fn list_pool_containers_for_dialect(&self, dialect: &str) -> Result<Vec<PoolContainer>> {
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT dialect, container_id, host_port, root_password, created_at, status,
+                       max_instances, instance_count
+                FROM pool_containers WHERE dialect = $1
+                "#,
+                &[&dialect],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query pool containers: {}", e)))?;
+
+        rows.iter().map(Self::row_to_pool_container).collect()
+    }
+
+    async fn upsert_pool_container(&self, pool: &PoolContainer) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                INSERT INTO pool_containers
+                    (container_id, dialect, host_port, root_password, created_at, status, max_instances, instance_count)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT(container_id) DO UPDATE SET
+                    dialect = excluded.dialect,
+                    host_port = excluded.host_port,
+                    root_password = excluded.root_password,
+                    created_at = excluded.created_at,
+                    status = excluded.status,
+                    max_instances = excluded.max_instances,
+                    instance_count = excluded.instance_count
+                "#,
+                &[
+                    &pool.container_id,
+                    &pool.dialect,
+                    &(pool.host_port as i32),
+                    &pool.root_password,
+                    &pool.created_at,
+                    &pool.status,
+                    &(pool.max_instances as i32),
+                    &(pool.instance_count as i32),
+                ],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to upsert pool container: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn delete_pool_container(&self, container_id: &str) -> Result<()> {
+        self.client
+            .execute(
+                "DELETE FROM pool_containers WHERE container_id = $1",
+                &[&container_id],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to delete pool container: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn adjust_pool_instance_count(&self, container_id: &str, delta: i32) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                UPDATE pool_containers
+                SET instance_count = GREATEST(0, instance_count + $2)
+                WHERE container_id = $1
+                "#,
+                &[&container_id, &delta],
+            )
+            .await
+            .map_err(|e| {
+                AppError::Storage(format!("Failed to adjust pool instance count: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    async fn insert_snapshot(&self, snapshot: &StoredSnapshot) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                INSERT INTO snapshots (id, db_id, backup_key, size_bytes, created_at)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                &[
+                    &snapshot.id.to_string(),
+                    &snapshot.db_id.to_string(),
+                    &snapshot.backup_key,
+                    &snapshot.size_bytes,
+                    &snapshot.created_at,
+                ],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to insert snapshot: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list_snapshots(&self, db_id: Uuid) -> Result<Vec<StoredSnapshot>> {
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT id, db_id, backup_key, size_bytes, created_at
+                FROM snapshots WHERE db_id = $1
+                ORDER BY created_at DESC
+                "#,
+                &[&db_id.to_string()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query snapshots: {}", e)))?;
+
+        rows.iter().map(Self::row_to_snapshot).collect()
+    }
+
+    async fn get_snapshot_at_or_before(
+        &self,
+        db_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<Option<StoredSnapshot>> {
+        let row = self
+            .client
+            .query_opt(
+                r#"
+                SELECT id, db_id, backup_key, size_bytes, created_at
+                FROM snapshots WHERE db_id = $1 AND created_at <= $2
+                ORDER BY created_at DESC
+                LIMIT 1
+                "#,
+                &[&db_id.to_string(), &at],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query snapshot: {}", e)))?;
+
+        row.map(|r| Self::row_to_snapshot(&r)).transpose()
+    }
+
+    async fn delete_snapshot(&self, id: Uuid) -> Result<()> {
+        self.client
+            .execute(
+                "DELETE FROM snapshots WHERE id = $1",
+                &[&id.to_string()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to delete snapshot: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn record_event(
+        &self,
+        db_id: Uuid,
+        dialect: &str,
+        previous_status: Option<InstanceState>,
+        new_status: &str,
+        reason: &str,
+    ) -> Result<()> {
+        self.client
+            .execute(
+                r#"
+                INSERT INTO instance_events (db_id, dialect, previous_status, new_status, reason)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                &[
+                    &db_id.to_string(),
+                    &dialect,
+                    &previous_status.map(|s| s.as_str()),
+                    &new_status,
+                    &reason,
+                ],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to record instance event: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn list_instance_events(&self, db_id: Uuid, limit: Option<u32>) -> Result<Vec<InstanceEvent>> {
+        let limit = limit.map(|l| l as i64).unwrap_or(i64::MAX);
+        let rows = self
+            .client
+            .query(
+                r#"
+                SELECT id, db_id, dialect, previous_status, new_status, reason, created_at
+                FROM instance_events WHERE db_id = $1
+                ORDER BY created_at DESC
+                LIMIT $2
+                "#,
+                &[&db_id.to_string(), &limit],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query instance events: {}", e)))?;
+
+        rows.iter().map(Self::row_to_instance_event).collect()
+    }
+
+    async fn count_by_status(&self) -> Result<Vec<(String, i64)>> {
+        let rows = self
+            .client
+            .query("SELECT status, COUNT(*) FROM instances GROUP BY status", &[])
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query status counts: {}", e)))?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn count_by_dialect(&self) -> Result<Vec<(String, i64)>> {
+        let rows = self
+            .client
+            .query("SELECT dialect, COUNT(*) FROM instances GROUP BY dialect", &[])
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query dialect counts: {}", e)))?;
+
+        Ok(rows.iter().map(|row| (row.get(0), row.get(1))).collect())
+    }
+
+    async fn sum_backup_bytes(&self) -> Result<i64> {
+        let row = self
+            .client
+            .query_one(
+                "SELECT COALESCE(SUM(backup_size_bytes), 0)::bigint FROM instances WHERE status = 'archived'",
+                &[],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to sum backup bytes: {}", e)))?;
+
+        Ok(row.get(0))
+    }
+
+    async fn enqueue_job(&self, kind: &str, payload: &str) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+
+        self.client
+            .execute(
+                r#"
+                INSERT INTO jobs (id, kind, payload, status, attempts, created_at)
+                VALUES ($1, $2, $3, 'new', 0, now())
+                "#,
+                &[&id.to_string(), &kind, &payload],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to enqueue job: {}", e)))?;
+
+        Ok(id)
+    }
+
+    async fn claim_next_job(&self) -> Result<Option<StoredJob>> {
+        let row = self
+            .client
+            .query_opt(
+                r#"
+                UPDATE jobs SET
+                    status = 'running',
+                    attempts = attempts + 1,
+                    heartbeat_at = now()
+                WHERE id = (
+                    SELECT id FROM jobs
+                    WHERE status = 'new'
+                    ORDER BY created_at
+                    LIMIT 1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, kind, payload, status, attempts, created_at, heartbeat_at, result, error
+                "#,
+                &[],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to claim job: {}", e)))?;
+
+        row.map(|r| Self::row_to_job(&r)).transpose()
+    }
+
+    async fn heartbeat_job(&self, id: Uuid) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE jobs SET heartbeat_at = now() WHERE id = $1",
+                &[&id.to_string()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to update job heartbeat: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn complete_job(&self, id: Uuid, result: &str) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE jobs SET status = 'done', result = $2, error = NULL WHERE id = $1",
+                &[&id.to_string(), &result],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to complete job: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn fail_job(&self, id: Uuid, error: &str) -> Result<()> {
+        self.client
+            .execute(
+                "UPDATE jobs SET status = 'failed', error = $2 WHERE id = $1",
+                &[&id.to_string(), &error],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to fail job: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_job(&self, id: Uuid) -> Result<Option<StoredJob>> {
+        let row = self
+            .client
+            .query_opt(
+                r#"
+                SELECT id, kind, payload, status, attempts, created_at, heartbeat_at, result, error
+                FROM jobs WHERE id = $1
+                "#,
+                &[&id.to_string()],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to query job: {}", e)))?;
+
+        row.map(|r| Self::row_to_job(&r)).transpose()
+    }
+
+    async fn reap_stale_jobs(&self, stale_after: Duration) -> Result<u64> {
+        let cutoff = Utc::now() - chrono::Duration::from_std(stale_after).unwrap();
+
+        let reaped = self
+            .client
+            .execute(
+                r#"
+                UPDATE jobs SET status = 'new', heartbeat_at = NULL
+                WHERE status = 'running' AND heartbeat_at < $1
+                "#,
+                &[&cutoff],
+            )
+            .await
+            .map_err(|e| AppError::Storage(format!("Failed to reap stale jobs: {}", e)))?;
+
+        Ok(reaped)
+    }
+}
@@ -0,0 +1,55 @@
+//! Service-lifecycle integration for deployments run under systemd: startup
+//! readiness, watchdog pings, and a stopping notification. Every function
+//! here is a no-op unless `NOTIFY_SOCKET` is set (i.e. the process was
+//! actually started by systemd), so it's safe to call unconditionally in
+//! non-systemd environments (local dev, Docker Compose, etc.).
+
+use sd_notify::NotifyState;
+use tracing::{info, warn};
+
+/// Tell systemd the service has finished starting up (HTTP listener bound,
+/// `InstanceManager`/backup store ready). Call this once, right before
+/// `axum::serve`.
+pub fn notify_ready() {
+    if let Err(e) = sd_notify::notify(false, &[NotifyState::Ready]) {
+        warn!("Failed to send systemd READY notification: {}", e);
+    }
+}
+
+/// Tell systemd the service is shutting down gracefully
+pub fn notify_stopping() {
+    if let Err(e) = sd_notify::notify(false, &[NotifyState::Stopping]) {
+        warn!("Failed to send systemd STOPPING notification: {}", e);
+    }
+}
+
+/// Spawn a background task that pings the systemd watchdog at half of
+/// `WATCHDOG_USEC` (the interval systemd reports it's willing to wait
+/// between pings before considering the service hung). No-op if systemd
+/// didn't enable the watchdog for this unit.
+pub fn spawn_watchdog_task() {
+    let Some(interval) = watchdog_interval() else {
+        return;
+    };
+
+    tokio::spawn(async move {
+        info!("systemd watchdog enabled, pinging every {:?}", interval);
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            if let Err(e) = sd_notify::notify(false, &[NotifyState::Watchdog]) {
+                warn!("Failed to send systemd WATCHDOG notification: {}", e);
+            }
+        }
+    });
+}
+
+/// Half of `WATCHDOG_USEC`, as recommended by `sd_watchdog_enabled(3)`, or
+/// `None` if the watchdog isn't enabled for this unit
+fn watchdog_interval() -> Option<std::time::Duration> {
+    let usec: u64 = std::env::var("WATCHDOG_USEC").ok()?.parse().ok()?;
+    if usec == 0 {
+        return None;
+    }
+    Some(std::time::Duration::from_micros(usec / 2))
+}